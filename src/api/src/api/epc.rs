@@ -0,0 +1,942 @@
+//! GS1 EPC Tag Data Standard encoding of 96-bit EPCs.
+//!
+//! [`Epc`] holds an SGTIN-96, SSCC-96, GRAI-96, GIAI-96 or GID-96 identifier. It is built
+//! from GS1 keys ([`Epc::sgtin`], [`Epc::sscc`], [`Epc::grai`], [`Epc::giai`]) or parsed
+//! from an EPC Tag URI, encoded into the twelve bytes stored from word 2 of the EPC bank,
+//! and decoded from them back into its EPC Tag URI and Pure Identity URI.
+//!
+//! GS1 keys do not say where the company prefix ends, so the constructors take its length
+//! in digits (6 to 12). The filter value (0 to 7) tells readers what kind of object the
+//! tag is on, e.g. 1 for a point-of-sale item or 2 for a full case.
+
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Length of a 96-bit EPC in bytes
+pub const EPC_96_BYTES: usize = 12;
+
+/// Shortest GS1 company prefix in digits
+pub const MIN_COMPANY_PREFIX_DIGITS: usize = 6;
+
+/// Longest GS1 company prefix in digits
+pub const MAX_COMPANY_PREFIX_DIGITS: usize = 12;
+
+/// Largest filter value
+pub const MAX_FILTER: u8 = 7;
+
+const SGTIN_96_HEADER: u8 = 0x30;
+const SSCC_96_HEADER: u8 = 0x31;
+const GRAI_96_HEADER: u8 = 0x33;
+const GIAI_96_HEADER: u8 = 0x34;
+const GID_96_HEADER: u8 = 0x35;
+
+/// Bits of the company prefix by partition value; the partition value is 12 minus the
+/// number of company prefix digits
+const COMPANY_PREFIX_BITS: [u32; 7] = [40, 37, 34, 30, 27, 24, 20];
+
+/// Bits of the serial number of SGTIN-96 and GRAI-96
+const SERIAL_BITS: u32 = 38;
+
+/// Unused bits at the end of SSCC-96
+const SSCC_RESERVED_BITS: u32 = 24;
+
+const GID_MANAGER_BITS: u32 = 28;
+const GID_CLASS_BITS: u32 = 24;
+const GID_SERIAL_BITS: u32 = 36;
+
+/// Errors encoding or decoding EPCs
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum EpcError {
+    /// The EPC does not start with the header of a supported 96-bit scheme
+    #[error("Header 0x{0:02X} is not SGTIN-96, SSCC-96, GRAI-96, GIAI-96 or GID-96")]
+    UnsupportedHeader(u8),
+
+    /// The EPC is not 96 bits long
+    #[error("A 96-bit EPC has {EPC_96_BYTES} bytes, not {0}")]
+    InvalidLength(usize),
+
+    /// The partition value is not defined
+    #[error("Partition value {0} is not defined")]
+    InvalidPartition(u8),
+
+    /// A GS1 key is malformed
+    #[error("Invalid GS1 key: {0}")]
+    InvalidKey(String),
+
+    /// The check digit of a GS1 key does not match its other digits
+    #[error("Check digit is {found}, expected {expected}")]
+    CheckDigit {
+        /// Check digit computed from the other digits
+        expected: u8,
+        /// Check digit given in the key
+        found: u8,
+    },
+
+    /// A field does not fit its place in the EPC
+    #[error("{0}")]
+    OutOfRange(String),
+
+    /// An EPC Tag URI is malformed
+    #[error("Invalid EPC Tag URI: {0}")]
+    InvalidUri(String),
+}
+
+/// Result alias for EPC encoding
+pub type Result<T> = std::result::Result<T, EpcError>;
+
+/// 96-bit EPC of one of the GS1 Tag Data Standard schemes
+///
+/// Company prefixes and references are kept as digit strings, since their leading zeros
+/// are significant.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Epc {
+    /// Serialised Global Trade Item Number
+    Sgtin96 {
+        /// Filter value (0-7)
+        filter: u8,
+        /// GS1 company prefix
+        company_prefix: String,
+        /// Indicator digit followed by the item reference, 13 digits with the company prefix
+        item_reference: String,
+        /// Serial number, below 2^38
+        serial: u64,
+    },
+    /// Serial Shipping Container Code
+    Sscc96 {
+        /// Filter value (0-7)
+        filter: u8,
+        /// GS1 company prefix
+        company_prefix: String,
+        /// Extension digit followed by the serial reference, 17 digits with the company prefix
+        serial_reference: String,
+    },
+    /// Global Returnable Asset Identifier
+    Grai96 {
+        /// Filter value (0-7)
+        filter: u8,
+        /// GS1 company prefix
+        company_prefix: String,
+        /// Asset type, 12 digits with the company prefix
+        asset_type: String,
+        /// Serial number, below 2^38
+        serial: u64,
+    },
+    /// Global Individual Asset Identifier
+    Giai96 {
+        /// Filter value (0-7)
+        filter: u8,
+        /// GS1 company prefix
+        company_prefix: String,
+        /// Individual asset reference, at most 24 digits with the company prefix
+        asset_reference: u64,
+    },
+    /// General Identifier, outside the GS1 system
+    Gid96 {
+        /// General manager number, below 2^28
+        manager: u32,
+        /// Object class, below 2^24
+        object_class: u32,
+        /// Serial number, below 2^36
+        serial: u64,
+    },
+}
+
+impl Epc {
+    /// SGTIN-96 of a GTIN-8, -12, -13 or -14 and a serial number
+    ///
+    /// # Errors
+    /// Returns an error if the GTIN is malformed or has a wrong check digit, or a field does
+    /// not fit the EPC.
+    pub fn sgtin(gtin: &str, company_prefix_len: usize, serial: u64, filter: u8) -> Result<Self> {
+        if ![8, 12, 13, 14].contains(&gtin.len()) {
+            return Err(EpcError::InvalidKey(format!(
+                "A GTIN has 8, 12, 13 or 14 digits, not {}",
+                gtin.len()
+            )));
+        }
+        let gtin = format!("{gtin:0>14}");
+        let body = split_key(&gtin, company_prefix_len, 1)?;
+        let epc = Epc::Sgtin96 {
+            filter,
+            company_prefix: body[1..=company_prefix_len].to_string(),
+            item_reference: format!("{}{}", &body[..1], &body[company_prefix_len + 1..]),
+            serial,
+        };
+        epc.validate()?;
+        Ok(epc)
+    }
+
+    /// SSCC-96 of an 18-digit SSCC
+    ///
+    /// # Errors
+    /// Returns an error if the SSCC is malformed or has a wrong check digit, or a field does
+    /// not fit the EPC.
+    pub fn sscc(sscc: &str, company_prefix_len: usize, filter: u8) -> Result<Self> {
+        if sscc.len() != 18 {
+            return Err(EpcError::InvalidKey(format!(
+                "An SSCC has 18 digits, not {}",
+                sscc.len()
+            )));
+        }
+        let body = split_key(sscc, company_prefix_len, 1)?;
+        let epc = Epc::Sscc96 {
+            filter,
+            company_prefix: body[1..=company_prefix_len].to_string(),
+            serial_reference: format!("{}{}", &body[..1], &body[company_prefix_len + 1..]),
+        };
+        epc.validate()?;
+        Ok(epc)
+    }
+
+    /// GRAI-96 of a 14-digit GRAI without its serial component, and a serial number
+    ///
+    /// # Errors
+    /// Returns an error if the GRAI is malformed or has a wrong check digit, or a field does
+    /// not fit the EPC.
+    pub fn grai(grai: &str, company_prefix_len: usize, serial: u64, filter: u8) -> Result<Self> {
+        if grai.len() != 14 || !grai.starts_with('0') {
+            return Err(EpcError::InvalidKey(
+                "A GRAI has 14 digits starting with 0, followed by the serial number".to_string(),
+            ));
+        }
+        let body = split_key(grai, company_prefix_len, 1)?;
+        let epc = Epc::Grai96 {
+            filter,
+            company_prefix: body[1..=company_prefix_len].to_string(),
+            asset_type: body[company_prefix_len + 1..].to_string(),
+            serial,
+        };
+        epc.validate()?;
+        Ok(epc)
+    }
+
+    /// GIAI-96 of an all-numeric GIAI
+    ///
+    /// # Errors
+    /// Returns an error if the GIAI is malformed or a field does not fit the EPC.
+    pub fn giai(giai: &str, company_prefix_len: usize, filter: u8) -> Result<Self> {
+        let (company_prefix, reference) =
+            split_key(giai, company_prefix_len, 0).map(|_| giai.split_at(company_prefix_len))?;
+        let epc = Epc::Giai96 {
+            filter,
+            company_prefix: company_prefix.to_string(),
+            asset_reference: parse_number(reference, "individual asset reference")?,
+        };
+        epc.validate()?;
+        Ok(epc)
+    }
+
+    /// Decode the twelve bytes of a 96-bit EPC
+    ///
+    /// # Errors
+    /// Returns an error if the EPC is not 96 bits long, its header is not a supported scheme,
+    /// or its fields are out of range.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let bytes: [u8; EPC_96_BYTES] = bytes
+            .try_into()
+            .map_err(|_| EpcError::InvalidLength(bytes.len()))?;
+        let mut reader = BitReader::new(bytes);
+        let header = reader.take_u8(8);
+        if header == GID_96_HEADER {
+            return Ok(Epc::Gid96 {
+                manager: reader.take_u32(GID_MANAGER_BITS),
+                object_class: reader.take_u32(GID_CLASS_BITS),
+                serial: reader.take_u64(GID_SERIAL_BITS),
+            });
+        }
+        let Some(layout) = Layout::of_header(header) else {
+            return Err(EpcError::UnsupportedHeader(header));
+        };
+        let filter = reader.take_u8(3);
+        let partition = reader.take_u8(3);
+        let company_digits = MAX_COMPANY_PREFIX_DIGITS
+            .checked_sub(usize::from(partition))
+            .filter(|digits| *digits >= MIN_COMPANY_PREFIX_DIGITS)
+            .ok_or(EpcError::InvalidPartition(partition))?;
+        let company_bits = COMPANY_PREFIX_BITS[usize::from(partition)];
+        let company_prefix = digits(reader.take(company_bits), company_digits, "company prefix")?;
+        let reference = reader.take(layout.reference_bits(company_bits));
+        let reference_digits = layout.reference_digits(company_digits);
+        let epc = match header {
+            SGTIN_96_HEADER => Epc::Sgtin96 {
+                filter,
+                company_prefix,
+                item_reference: digits(reference, reference_digits, "item reference")?,
+                serial: reader.take_u64(SERIAL_BITS),
+            },
+            SSCC_96_HEADER => Epc::Sscc96 {
+                filter,
+                company_prefix,
+                serial_reference: digits(reference, reference_digits, "serial reference")?,
+            },
+            GRAI_96_HEADER => Epc::Grai96 {
+                filter,
+                company_prefix,
+                asset_type: digits(reference, reference_digits, "asset type")?,
+                serial: reader.take_u64(SERIAL_BITS),
+            },
+            _ => Epc::Giai96 {
+                filter,
+                company_prefix,
+                asset_reference: u64::try_from(reference).map_err(|_| {
+                    EpcError::OutOfRange("Individual asset reference is too long".to_string())
+                })?,
+            },
+        };
+        epc.validate()?;
+        Ok(epc)
+    }
+
+    /// Decode a 96-bit EPC given as hex, as in inventory results
+    ///
+    /// # Errors
+    /// Returns an error if the text is not hex or the EPC does not decode.
+    pub fn from_hex(epc: &str) -> Result<Self> {
+        let bytes = hex::decode(epc).map_err(|_| EpcError::InvalidLength(epc.len() / 2))?;
+        Self::decode(&bytes)
+    }
+
+    /// Encode the EPC into the twelve bytes stored in the EPC bank
+    ///
+    /// # Errors
+    /// Returns an error if a field does not fit the EPC.
+    pub fn encode(&self) -> Result<[u8; EPC_96_BYTES]> {
+        self.validate()?;
+        let mut writer = BitWriter::default();
+        if let Epc::Gid96 {
+            manager,
+            object_class,
+            serial,
+        } = self
+        {
+            writer.push(u128::from(GID_96_HEADER), 8);
+            writer.push(u128::from(*manager), GID_MANAGER_BITS);
+            writer.push(u128::from(*object_class), GID_CLASS_BITS);
+            writer.push(u128::from(*serial), GID_SERIAL_BITS);
+        } else {
+            let (filter, company_prefix, reference, serial) = self.partitioned_fields();
+            let layout = self.layout();
+            let partition = MAX_COMPANY_PREFIX_DIGITS - company_prefix.len();
+            let company_bits = COMPANY_PREFIX_BITS[partition];
+            writer.push(u128::from(layout.header), 8);
+            writer.push(u128::from(filter), 3);
+            writer.push(partition as u128, 3);
+            writer.push(number(company_prefix), company_bits);
+            writer.push(reference, layout.reference_bits(company_bits));
+            writer.push(u128::from(serial.unwrap_or(0)), layout.serial_bits);
+        }
+        Ok(writer.finish())
+    }
+
+    /// Pure Identity URI naming the object, e.g. `urn:epc:id:sgtin:0614141.812345.6789`
+    #[must_use]
+    pub fn pure_identity_uri(&self) -> String {
+        match self {
+            Epc::Sgtin96 {
+                company_prefix,
+                item_reference,
+                serial,
+                ..
+            } => format!("urn:epc:id:sgtin:{company_prefix}.{item_reference}.{serial}"),
+            Epc::Sscc96 {
+                company_prefix,
+                serial_reference,
+                ..
+            } => format!("urn:epc:id:sscc:{company_prefix}.{serial_reference}"),
+            Epc::Grai96 {
+                company_prefix,
+                asset_type,
+                serial,
+                ..
+            } => format!("urn:epc:id:grai:{company_prefix}.{asset_type}.{serial}"),
+            Epc::Giai96 {
+                company_prefix,
+                asset_reference,
+                ..
+            } => format!("urn:epc:id:giai:{company_prefix}.{asset_reference}"),
+            Epc::Gid96 {
+                manager,
+                object_class,
+                serial,
+            } => format!("urn:epc:id:gid:{manager}.{object_class}.{serial}"),
+        }
+    }
+
+    /// EPC Tag URI, which adds the scheme and filter value to the Pure Identity URI, e.g.
+    /// `urn:epc:tag:sgtin-96:3.0614141.812345.6789`
+    #[must_use]
+    pub fn tag_uri(&self) -> String {
+        let identity = self.pure_identity_uri();
+        let fields = identity.splitn(5, ':').nth(4).unwrap_or_default();
+        match self.filter() {
+            Some(filter) => format!("urn:epc:tag:{}:{filter}.{fields}", self.scheme()),
+            None => format!("urn:epc:tag:{}:{fields}", self.scheme()),
+        }
+    }
+
+    /// Tag URI scheme name, e.g. `sgtin-96`
+    #[must_use]
+    pub fn scheme(&self) -> &'static str {
+        match self {
+            Epc::Gid96 { .. } => "gid-96",
+            _ => self.layout().scheme,
+        }
+    }
+
+    /// Filter value; GID-96 has none
+    #[must_use]
+    pub fn filter(&self) -> Option<u8> {
+        match self {
+            Epc::Sgtin96 { filter, .. }
+            | Epc::Sscc96 { filter, .. }
+            | Epc::Grai96 { filter, .. }
+            | Epc::Giai96 { filter, .. } => Some(*filter),
+            Epc::Gid96 { .. } => None,
+        }
+    }
+
+    fn layout(&self) -> &'static Layout {
+        let header = match self {
+            Epc::Sgtin96 { .. } => SGTIN_96_HEADER,
+            Epc::Sscc96 { .. } => SSCC_96_HEADER,
+            Epc::Grai96 { .. } => GRAI_96_HEADER,
+            Epc::Giai96 { .. } | Epc::Gid96 { .. } => GIAI_96_HEADER,
+        };
+        Layout::of_header(header).unwrap_or(&LAYOUTS[0])
+    }
+
+    /// Filter, company prefix, reference as a number and serial of the partitioned schemes
+    fn partitioned_fields(&self) -> (u8, &str, u128, Option<u64>) {
+        match self {
+            Epc::Sgtin96 {
+                filter,
+                company_prefix,
+                item_reference,
+                serial,
+            } => (
+                *filter,
+                company_prefix,
+                number(item_reference),
+                Some(*serial),
+            ),
+            Epc::Sscc96 {
+                filter,
+                company_prefix,
+                serial_reference,
+            } => (*filter, company_prefix, number(serial_reference), None),
+            Epc::Grai96 {
+                filter,
+                company_prefix,
+                asset_type,
+                serial,
+            } => (*filter, company_prefix, number(asset_type), Some(*serial)),
+            Epc::Giai96 {
+                filter,
+                company_prefix,
+                asset_reference,
+            } => (*filter, company_prefix, u128::from(*asset_reference), None),
+            Epc::Gid96 { .. } => (0, "", 0, None),
+        }
+    }
+
+    /// Check that every field fits its place in the EPC
+    fn validate(&self) -> Result<()> {
+        if let Epc::Gid96 {
+            manager,
+            object_class,
+            serial,
+        } = self
+        {
+            check_bits(
+                u128::from(*manager),
+                GID_MANAGER_BITS,
+                "General manager number",
+            )?;
+            check_bits(u128::from(*object_class), GID_CLASS_BITS, "Object class")?;
+            return check_bits(u128::from(*serial), GID_SERIAL_BITS, "Serial number");
+        }
+        let (filter, company_prefix, reference, serial) = self.partitioned_fields();
+        if filter > MAX_FILTER {
+            return Err(EpcError::OutOfRange(format!(
+                "Filter value must be at most {MAX_FILTER}"
+            )));
+        }
+        check_digits(company_prefix, "Company prefix")?;
+        if !(MIN_COMPANY_PREFIX_DIGITS..=MAX_COMPANY_PREFIX_DIGITS).contains(&company_prefix.len())
+        {
+            return Err(EpcError::OutOfRange(format!(
+                "Company prefix must have {MIN_COMPANY_PREFIX_DIGITS} to \
+                 {MAX_COMPANY_PREFIX_DIGITS} digits"
+            )));
+        }
+        let layout = self.layout();
+        let reference_digits = layout.reference_digits(company_prefix.len());
+        match self {
+            Epc::Sgtin96 {
+                item_reference: reference,
+                ..
+            }
+            | Epc::Sscc96 {
+                serial_reference: reference,
+                ..
+            }
+            | Epc::Grai96 {
+                asset_type: reference,
+                ..
+            } => {
+                check_digits(reference, layout.reference_name)?;
+                if reference.len() != reference_digits {
+                    return Err(EpcError::OutOfRange(format!(
+                        "{} must have {reference_digits} digits with a {}-digit company prefix",
+                        layout.reference_name,
+                        company_prefix.len()
+                    )));
+                }
+            }
+            _ => {
+                if reference.to_string().len() > reference_digits {
+                    return Err(EpcError::OutOfRange(format!(
+                        "{} must have at most {reference_digits} digits with a {}-digit \
+                         company prefix",
+                        layout.reference_name,
+                        company_prefix.len()
+                    )));
+                }
+                let company_bits = COMPANY_PREFIX_BITS[12 - company_prefix.len()];
+                check_bits(
+                    reference,
+                    layout.reference_bits(company_bits),
+                    layout.reference_name,
+                )?;
+            }
+        }
+        match serial {
+            Some(serial) => check_bits(u128::from(serial), SERIAL_BITS, "Serial number"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Epc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.pure_identity_uri())
+    }
+}
+
+impl FromStr for Epc {
+    type Err = EpcError;
+
+    /// Parse an EPC Tag URI, e.g. `urn:epc:tag:sgtin-96:3.0614141.812345.6789`
+    fn from_str(uri: &str) -> Result<Self> {
+        let invalid = |reason: &str| EpcError::InvalidUri(format!("{uri}: {reason}"));
+        let rest = uri
+            .strip_prefix("urn:epc:tag:")
+            .ok_or_else(|| invalid("expected urn:epc:tag:<scheme>:<fields>"))?;
+        let (scheme, fields) = rest
+            .split_once(':')
+            .ok_or_else(|| invalid("missing fields"))?;
+        let fields: Vec<&str> = fields.split('.').collect();
+        let number = |field: &str, name: &str| {
+            parse_number(field, name).map_err(|e| invalid(&e.to_string()))
+        };
+        let filter = |field: &str| {
+            number(field, "filter value").and_then(|filter| {
+                u8::try_from(filter).map_err(|_| invalid("filter value out of range"))
+            })
+        };
+        let epc = match (scheme, fields.as_slice()) {
+            ("sgtin-96", [f, company_prefix, item_reference, serial]) => Epc::Sgtin96 {
+                filter: filter(f)?,
+                company_prefix: (*company_prefix).to_string(),
+                item_reference: (*item_reference).to_string(),
+                serial: number(serial, "serial number")?,
+            },
+            ("sscc-96", [f, company_prefix, serial_reference]) => Epc::Sscc96 {
+                filter: filter(f)?,
+                company_prefix: (*company_prefix).to_string(),
+                serial_reference: (*serial_reference).to_string(),
+            },
+            ("grai-96", [f, company_prefix, asset_type, serial]) => Epc::Grai96 {
+                filter: filter(f)?,
+                company_prefix: (*company_prefix).to_string(),
+                asset_type: (*asset_type).to_string(),
+                serial: number(serial, "serial number")?,
+            },
+            ("giai-96", [f, company_prefix, asset_reference]) => Epc::Giai96 {
+                filter: filter(f)?,
+                company_prefix: (*company_prefix).to_string(),
+                asset_reference: number(asset_reference, "individual asset reference")?,
+            },
+            ("gid-96", [manager, object_class, serial]) => Epc::Gid96 {
+                manager: u32::try_from(number(manager, "general manager number")?)
+                    .map_err(|_| invalid("general manager number out of range"))?,
+                object_class: u32::try_from(number(object_class, "object class")?)
+                    .map_err(|_| invalid("object class out of range"))?,
+                serial: number(serial, "serial number")?,
+            },
+            ("sgtin-96" | "sscc-96" | "grai-96" | "giai-96" | "gid-96", _) => {
+                return Err(invalid("wrong number of fields"));
+            }
+            _ => return Err(invalid("unsupported scheme")),
+        };
+        epc.validate()?;
+        Ok(epc)
+    }
+}
+
+/// Field widths of a partitioned scheme
+struct Layout {
+    header: u8,
+    scheme: &'static str,
+    /// Bits of company prefix and reference together
+    partitioned_bits: u32,
+    /// Digits of company prefix and reference together
+    partitioned_digits: usize,
+    /// Bits after the partitioned fields: the serial number or reserved bits
+    serial_bits: u32,
+    reference_name: &'static str,
+}
+
+const LAYOUTS: [Layout; 4] = [
+    Layout {
+        header: SGTIN_96_HEADER,
+        scheme: "sgtin-96",
+        partitioned_bits: 44,
+        partitioned_digits: 13,
+        serial_bits: SERIAL_BITS,
+        reference_name: "Item reference",
+    },
+    Layout {
+        header: SSCC_96_HEADER,
+        scheme: "sscc-96",
+        partitioned_bits: 58,
+        partitioned_digits: 17,
+        serial_bits: SSCC_RESERVED_BITS,
+        reference_name: "Serial reference",
+    },
+    Layout {
+        header: GRAI_96_HEADER,
+        scheme: "grai-96",
+        partitioned_bits: 44,
+        partitioned_digits: 12,
+        serial_bits: SERIAL_BITS,
+        reference_name: "Asset type",
+    },
+    Layout {
+        header: GIAI_96_HEADER,
+        scheme: "giai-96",
+        partitioned_bits: 82,
+        partitioned_digits: 24,
+        serial_bits: 0,
+        reference_name: "Individual asset reference",
+    },
+];
+
+impl Layout {
+    fn of_header(header: u8) -> Option<&'static Layout> {
+        LAYOUTS.iter().find(|layout| layout.header == header)
+    }
+
+    fn reference_bits(&self, company_bits: u32) -> u32 {
+        self.partitioned_bits - company_bits
+    }
+
+    fn reference_digits(&self, company_digits: usize) -> usize {
+        self.partitioned_digits - company_digits
+    }
+}
+
+/// Check the digits of a GS1 key and return them without the check digit
+///
+/// `before_prefix` digits (the indicator or extension digit) precede the company prefix.
+fn split_key(key: &str, company_prefix_len: usize, before_prefix: usize) -> Result<&str> {
+    check_digits(key, "GS1 key")
+        .map_err(|_| EpcError::InvalidKey(format!("{key} must contain only digits")))?;
+    if !(MIN_COMPANY_PREFIX_DIGITS..=MAX_COMPANY_PREFIX_DIGITS).contains(&company_prefix_len) {
+        return Err(EpcError::OutOfRange(format!(
+            "Company prefix must have {MIN_COMPANY_PREFIX_DIGITS} to \
+             {MAX_COMPANY_PREFIX_DIGITS} digits"
+        )));
+    }
+    if before_prefix == 0 {
+        // Keys without a check digit
+        return if key.len() > company_prefix_len {
+            Ok(key)
+        } else {
+            Err(EpcError::InvalidKey(format!(
+                "{key} is not longer than its company prefix"
+            )))
+        };
+    }
+    let (body, check) = key.split_at(key.len() - 1);
+    let found = check.as_bytes()[0] - b'0';
+    let expected = check_digit(body);
+    if found != expected {
+        return Err(EpcError::CheckDigit { expected, found });
+    }
+    if body.len() < before_prefix + company_prefix_len {
+        return Err(EpcError::InvalidKey(format!(
+            "{key} is shorter than its company prefix"
+        )));
+    }
+    Ok(body)
+}
+
+/// GS1 check digit of `digits`: weights 3 and 1 alternate from the rightmost digit
+fn check_digit(digits: &str) -> u8 {
+    let sum: u32 = digits
+        .bytes()
+        .rev()
+        .enumerate()
+        .map(|(i, digit)| u32::from(digit - b'0') * if i % 2 == 0 { 3 } else { 1 })
+        .sum();
+    // The remainder is below 10
+    ((10 - sum % 10) % 10) as u8
+}
+
+fn check_digits(field: &str, name: &str) -> Result<()> {
+    if !field.is_empty() && field.bytes().all(|b| b.is_ascii_digit()) {
+        Ok(())
+    } else {
+        Err(EpcError::OutOfRange(format!(
+            "{name} must be a non-empty string of digits"
+        )))
+    }
+}
+
+fn check_bits(value: u128, bits: u32, name: &str) -> Result<()> {
+    if value >> bits == 0 {
+        Ok(())
+    } else {
+        Err(EpcError::OutOfRange(format!(
+            "{name} must be below 2^{bits}"
+        )))
+    }
+}
+
+/// Parse a decimal number without leading zeros, as EPC serial numbers are written
+fn parse_number(field: &str, name: &str) -> Result<u64> {
+    let invalid = || EpcError::OutOfRange(format!("{name} must be a number without leading zeros"));
+    if field.len() > 1 && field.starts_with('0') {
+        return Err(invalid());
+    }
+    check_digits(field, name).map_err(|_| invalid())?;
+    field.parse().map_err(|_| invalid())
+}
+
+/// Value of a string of digits checked by `check_digits`
+fn number(digits: &str) -> u128 {
+    digits
+        .bytes()
+        .fold(0, |value, digit| value * 10 + u128::from(digit - b'0'))
+}
+
+/// Decimal digits of a decoded field, zero-padded to `len`
+fn digits(value: u128, len: usize, name: &str) -> Result<String> {
+    let digits = format!("{value:0len$}");
+    if digits.len() == len {
+        Ok(digits)
+    } else {
+        Err(EpcError::OutOfRange(format!(
+            "Decoded {name} has more than {len} digits"
+        )))
+    }
+}
+
+/// Reads fields most significant bit first
+struct BitReader {
+    value: u128,
+    remaining: u32,
+}
+
+impl BitReader {
+    fn new(bytes: [u8; EPC_96_BYTES]) -> Self {
+        let mut padded = [0u8; 16];
+        padded[16 - EPC_96_BYTES..].copy_from_slice(&bytes);
+        Self {
+            value: u128::from_be_bytes(padded),
+            remaining: 96,
+        }
+    }
+
+    fn take(&mut self, bits: u32) -> u128 {
+        self.remaining -= bits;
+        (self.value >> self.remaining) & ((1 << bits) - 1)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn take_u8(&mut self, bits: u32) -> u8 {
+        self.take(bits) as u8
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn take_u32(&mut self, bits: u32) -> u32 {
+        self.take(bits) as u32
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn take_u64(&mut self, bits: u32) -> u64 {
+        self.take(bits) as u64
+    }
+}
+
+/// Appends fields most significant bit first
+#[derive(Default)]
+struct BitWriter {
+    value: u128,
+    len: u32,
+}
+
+impl BitWriter {
+    fn push(&mut self, value: u128, bits: u32) {
+        if bits > 0 {
+            self.value = (self.value << bits) | value;
+            self.len += bits;
+        }
+    }
+
+    fn finish(self) -> [u8; EPC_96_BYTES] {
+        debug_assert_eq!(self.len, 96);
+        let bytes = self.value.to_be_bytes();
+        let mut epc = [0u8; EPC_96_BYTES];
+        epc.copy_from_slice(&bytes[16 - EPC_96_BYTES..]);
+        epc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sgtin_matches_the_tag_data_standard_example() {
+        let epc = Epc::sgtin("80614141123458", 7, 6789, 3).unwrap();
+        assert_eq!(epc.tag_uri(), "urn:epc:tag:sgtin-96:3.0614141.812345.6789");
+        assert_eq!(
+            epc.pure_identity_uri(),
+            "urn:epc:id:sgtin:0614141.812345.6789"
+        );
+        let bytes = epc.encode().unwrap();
+        assert_eq!(hex::encode_upper(bytes), "3074257BF7194E4000001A85");
+        assert_eq!(Epc::decode(&bytes).unwrap(), epc);
+        assert_eq!(epc.tag_uri().parse::<Epc>().unwrap(), epc);
+    }
+
+    #[test]
+    fn tag_uris_encode_like_the_tag_data_standard() {
+        let vectors = [
+            (
+                "urn:epc:tag:sscc-96:3.0614141.1234567890",
+                "3174257BF4499602D2000000",
+            ),
+            (
+                "urn:epc:tag:grai-96:3.0614141.12345.400",
+                "3374257BF40C0E4000000190",
+            ),
+            (
+                "urn:epc:tag:giai-96:3.0614141.5678",
+                "3474257BF40000000000162E",
+            ),
+            (
+                "urn:epc:tag:gid-96:95100000.12345.400",
+                "355AB1C60003039000000190",
+            ),
+        ];
+        for (uri, expected) in vectors {
+            let epc: Epc = uri.parse().unwrap();
+            assert_eq!(hex::encode_upper(epc.encode().unwrap()), expected, "{uri}");
+            assert_eq!(Epc::from_hex(expected).unwrap().tag_uri(), uri);
+        }
+    }
+
+    #[test]
+    fn every_scheme_round_trips() {
+        let epcs = [
+            Epc::sgtin("00614141000012", 12, 0, 0).unwrap(),
+            Epc::sgtin("12345670", 6, (1 << 38) - 1, 1).unwrap(),
+            Epc::sscc("106141412345678908", 7, 2).unwrap(),
+            Epc::grai("00614141123452", 7, 5678, 0).unwrap(),
+            Epc::giai("06141415678", 7, 3).unwrap(),
+            Epc::giai("123456999999999999999999", 6, 7).unwrap(),
+            Epc::Gid96 {
+                manager: 95_100_000,
+                object_class: 12_345,
+                serial: 400,
+            },
+        ];
+        for epc in epcs {
+            let bytes = epc.encode().unwrap();
+            assert_eq!(Epc::decode(&bytes).unwrap(), epc, "{}", epc.tag_uri());
+            assert_eq!(epc.tag_uri().parse::<Epc>().unwrap(), epc);
+        }
+    }
+
+    #[test]
+    fn uris_of_each_scheme() {
+        let sscc = Epc::sscc("106141412345678908", 7, 2).unwrap();
+        assert_eq!(sscc.tag_uri(), "urn:epc:tag:sscc-96:2.0614141.1234567890");
+        let returnable = Epc::grai("00614141123452", 7, 5678, 0).unwrap();
+        assert_eq!(
+            returnable.pure_identity_uri(),
+            "urn:epc:id:grai:0614141.12345.5678"
+        );
+        let individual = Epc::giai("06141415678", 7, 3).unwrap();
+        assert_eq!(individual.tag_uri(), "urn:epc:tag:giai-96:3.0614141.5678");
+        let gid = "urn:epc:tag:gid-96:95100000.12345.400"
+            .parse::<Epc>()
+            .unwrap();
+        assert_eq!(gid.pure_identity_uri(), "urn:epc:id:gid:95100000.12345.400");
+        assert_eq!(gid.filter(), None);
+    }
+
+    #[test]
+    fn keys_are_checked() {
+        assert_eq!(
+            Epc::sgtin("80614141123459", 7, 1, 0),
+            Err(EpcError::CheckDigit {
+                expected: 8,
+                found: 9
+            })
+        );
+        assert!(Epc::sgtin("8061414112345", 7, 1, 0).is_err());
+        assert!(Epc::sgtin("80614141123458", 5, 1, 0).is_err());
+        assert!(Epc::sgtin("80614141123458", 7, 1 << 38, 0).is_err());
+        assert!(Epc::sgtin("80614141123458", 7, 1, 8).is_err());
+        assert!(Epc::sscc("10614141234567890", 7, 0).is_err());
+        assert!(Epc::grai("10614141234565", 7, 1, 0).is_err());
+        assert!(Epc::giai("0614141", 7, 0).is_err());
+        assert!(Epc::giai("061414105678", 7, 0).is_err());
+        assert!(
+            "urn:epc:id:sgtin:0614141.812345.6789"
+                .parse::<Epc>()
+                .is_err()
+        );
+        assert!(
+            "urn:epc:tag:sgtin-96:3.0614141.812345.06789"
+                .parse::<Epc>()
+                .is_err()
+        );
+        assert!(
+            "urn:epc:tag:sgtin-96:3.0614141.81234.6789"
+                .parse::<Epc>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn foreign_epcs_do_not_decode() {
+        assert_eq!(
+            Epc::decode(&[0xE2; EPC_96_BYTES]),
+            Err(EpcError::UnsupportedHeader(0xE2))
+        );
+        assert_eq!(Epc::decode(&[0x30; 8]), Err(EpcError::InvalidLength(8)));
+        // Partition value 7 is not defined
+        let mut bytes = Epc::sgtin("80614141123458", 7, 6789, 3)
+            .unwrap()
+            .encode()
+            .unwrap();
+        bytes[1] |= 0b0001_1100;
+        assert_eq!(Epc::decode(&bytes), Err(EpcError::InvalidPartition(7)));
+    }
+}
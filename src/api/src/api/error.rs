@@ -82,13 +82,26 @@ pub enum RfidError {
     #[error("Protocol error: {0}")]
     Protocol(String),
 
+    /// Command-line argument or user input that cannot be turned into a request
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
     /// Device returned an invalid or unexpected response
     #[error("Invalid response: {0}")]
     InvalidResponse(String),
 
     /// Device reported a non-zero status code
-    #[error("Command failed with status: {0}")]
-    CommandFailed(u8),
+    #[error("Command failed with status 0x{status:02X}{}", describe_status(*status))]
+    CommandFailed {
+        /// Status byte
+        status: u8,
+        /// Raw reply frame carrying the status
+        frame: Vec<u8>,
+    },
+
+    /// A kill was refused by a safeguard before anything was sent to the tag
+    #[error("Kill refused: {0}")]
+    KillRefused(String),
 
     /// Operation timed out
     #[error("Timeout waiting for response")]
@@ -108,5 +121,42 @@ pub enum RfidError {
 
     /// Wrapped low-level protocol error
     #[error("UhfError library error: {0}")]
-    UhfError(#[from] UhfError),
+    UhfError(#[source] UhfError),
+}
+
+impl From<UhfError> for RfidError {
+    /// Reader status codes become `CommandFailed`, malformed replies `InvalidResponse` and
+    /// unanswered commands `Timeout`
+    fn from(error: UhfError) -> Self {
+        if let Some(status) = error.status() {
+            let frame = error.frame().unwrap_or_default().to_vec();
+            return RfidError::CommandFailed { status, frame };
+        }
+        match error {
+            UhfError::InvalidResponse(frame) if frame.is_empty() => {
+                RfidError::InvalidResponse("empty reply".to_string())
+            }
+            UhfError::InvalidResponse(frame) => {
+                RfidError::InvalidResponse(hex::encode_upper(frame))
+            }
+            UhfError::Fragmentation(message) => RfidError::FragmentationError(message),
+            UhfError::Timeout => RfidError::Timeout,
+            error => RfidError::UhfError(error),
+        }
+    }
+}
+
+/// Human-readable meaning of a reader status code, if the status is known
+#[must_use]
+pub fn status_description(status: u8) -> Option<String> {
+    // Decode through the protocol layer so both agree on the status table
+    match UhfError::from_status(status, Vec::new()) {
+        UhfError::DeviceStatus { .. } => None,
+        error => Some(error.to_string()),
+    }
+}
+
+/// `": <description>"` for a known status, nothing for an unknown one
+fn describe_status(status: u8) -> String {
+    status_description(status).map_or_else(String::new, |description| format!(": {description}"))
 }
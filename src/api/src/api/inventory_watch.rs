@@ -0,0 +1,292 @@
+//! Continuous inventory running on a background thread.
+//!
+//! [`InventoryWatch`] repeats [`UhfRfidApi::inventory`] rounds and turns the results into
+//! [`InventoryEvent`]s: a tag arrives the first time it is read, is seen again on later
+//! reads at most once per sighting interval, and departs once it has not been read for the
+//! configured absence timeout.
+
+use crate::api::error::RfidError;
+use crate::api::uhf_rfid_api::UhfRfidApi;
+use protocl::types::{InventoryResult, UsbIo};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tracing::{Span, info_span, trace};
+
+/// Timing of a continuous inventory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchConfig {
+    /// Pause between the end of one inventory round and the start of the next
+    pub round_interval: Duration,
+    /// How long a tag may go unread before it is reported as departed
+    pub absence_timeout: Duration,
+    /// Shortest time between two sightings reported for the same tag; `None` reports none
+    pub seen_interval: Option<Duration>,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            round_interval: Duration::from_millis(200),
+            absence_timeout: Duration::from_secs(2),
+            seen_interval: Some(Duration::from_secs(1)),
+        }
+    }
+}
+
+/// Tag tracked by a continuous inventory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagState {
+    /// Latest inventory result; `read_count` is cumulative since the tag arrived
+    pub tag: InventoryResult,
+    /// When the tag was first read
+    pub first_seen: Instant,
+    /// When the tag was last read
+    pub last_seen: Instant,
+}
+
+impl fmt::Display for TagState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.tag.fmt(f)
+    }
+}
+
+/// Change reported by a continuous inventory
+#[derive(Debug)]
+pub enum InventoryEvent {
+    /// A tag was read for the first time, or again after departing
+    TagArrived(TagState),
+    /// A tag that is already present was read again, reported at most once per sighting
+    /// interval
+    TagSeen(TagState),
+    /// A tag has not been read for the absence timeout
+    TagDeparted(TagState),
+    /// An inventory round failed; the watch keeps running
+    Error(RfidError),
+}
+
+/// Turns inventory rounds into arrival, sighting and departure events
+#[derive(Debug)]
+pub struct TagTracker {
+    present: Vec<TagState>,
+    seen_interval: Option<Duration>,
+    /// When each present tag, by EPC, was last reported arriving or seen
+    reported: HashMap<String, Instant>,
+}
+
+impl Default for TagTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TagTracker {
+    /// Create a tracker with no tags present that reports every sighting
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_seen_interval(Some(Duration::ZERO))
+    }
+
+    /// Create a tracker with no tags present that reports a sighting of the same tag at most
+    /// once per `seen_interval`, or never if it is `None`
+    #[must_use]
+    pub fn with_seen_interval(seen_interval: Option<Duration>) -> Self {
+        Self {
+            present: Vec::new(),
+            seen_interval,
+            reported: HashMap::new(),
+        }
+    }
+
+    /// Tags currently present, in order of arrival
+    #[must_use]
+    pub fn present(&self) -> &[TagState] {
+        &self.present
+    }
+
+    /// Record a tag read at `now`, returning the event to report, if any
+    pub fn observe(&mut self, result: InventoryResult, now: Instant) -> Option<InventoryEvent> {
+        if let Some(seen) = self
+            .present
+            .iter_mut()
+            .find(|seen| seen.tag.epc == result.epc)
+        {
+            let read_count = seen.tag.read_count.saturating_add(result.read_count);
+            seen.tag = InventoryResult {
+                read_count,
+                ..result
+            };
+            seen.last_seen = now;
+            let interval = self.seen_interval?;
+            let reported = self.reported.entry(seen.tag.epc.clone()).or_insert(now);
+            if now.saturating_duration_since(*reported) < interval {
+                return None;
+            }
+            *reported = now;
+            return Some(InventoryEvent::TagSeen(seen.clone()));
+        }
+        self.reported.insert(result.epc.clone(), now);
+        let state = TagState {
+            tag: result,
+            first_seen: now,
+            last_seen: now,
+        };
+        self.present.push(state.clone());
+        Some(InventoryEvent::TagArrived(state))
+    }
+
+    /// Remove tags not read within `absence_timeout` of `now`
+    pub fn expire(&mut self, now: Instant, absence_timeout: Duration) -> Vec<InventoryEvent> {
+        let (departed, present) = std::mem::take(&mut self.present)
+            .into_iter()
+            .partition(|state| now.duration_since(state.last_seen) >= absence_timeout);
+        self.present = present;
+        departed
+            .into_iter()
+            .map(|state: TagState| {
+                self.reported.remove(&state.tag.epc);
+                InventoryEvent::TagDeparted(state)
+            })
+            .collect()
+    }
+}
+
+/// Cancels a running [`InventoryWatch`] from another thread
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle(Arc<(Mutex<bool>, Condvar)>);
+
+impl CancelHandle {
+    /// Ask the watch to stop; a round in progress is finished first
+    pub fn cancel(&self) {
+        *self.cancelled() = true;
+        self.0.1.notify_all();
+    }
+
+    /// Whether cancellation was requested
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        *self.cancelled()
+    }
+
+    /// Wait up to `timeout` for cancellation, returning whether it was requested
+    fn wait(&self, timeout: Duration) -> bool {
+        let (cancelled, _) = self
+            .0
+            .1
+            .wait_timeout_while(self.cancelled(), timeout, |cancelled| !*cancelled)
+            .unwrap_or_else(PoisonError::into_inner);
+        *cancelled
+    }
+
+    fn cancelled(&self) -> MutexGuard<'_, bool> {
+        self.0.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Continuous inventory owning the device while it runs
+///
+/// Iterating the watch blocks until the next event and ends once the watch is cancelled.
+/// [`InventoryWatch::stop`] hands the device back.
+pub struct InventoryWatch<D> {
+    events: Receiver<InventoryEvent>,
+    cancel: CancelHandle,
+    worker: JoinHandle<D>,
+}
+
+impl<D: UsbIo + Send + 'static> InventoryWatch<D> {
+    /// Start running inventory rounds on `device` through `api` in a background thread
+    #[must_use]
+    pub fn start(api: UhfRfidApi, device: D, config: WatchConfig) -> Self {
+        let (sender, events) = mpsc::channel();
+        let cancel = CancelHandle::default();
+        let worker_cancel = cancel.clone();
+        // Spans do not follow the thread, so the caller's span becomes the parent explicitly
+        let span = info_span!(parent: Span::current(), "inventory_watch", ?config);
+        let worker = thread::spawn(move || {
+            let _entered = span.entered();
+            Self::run(&api, &device, config, &worker_cancel, &sender);
+            device
+        });
+        Self {
+            events,
+            cancel,
+            worker,
+        }
+    }
+
+    fn run(
+        api: &UhfRfidApi,
+        device: &D,
+        config: WatchConfig,
+        cancel: &CancelHandle,
+        sender: &Sender<InventoryEvent>,
+    ) {
+        let mut tracker = TagTracker::with_seen_interval(config.seen_interval);
+        while !cancel.is_cancelled() {
+            let mut events = match api.inventory(device) {
+                Ok(results) => {
+                    let now = Instant::now();
+                    results
+                        .into_iter()
+                        .filter_map(|result| tracker.observe(result, now))
+                        .collect()
+                }
+                Err(e) => vec![InventoryEvent::Error(e)],
+            };
+            events.extend(tracker.expire(Instant::now(), config.absence_timeout));
+            for event in events {
+                trace!(?event, "watch event");
+                if sender.send(event).is_err() {
+                    return; // Nobody is listening any more
+                }
+            }
+            if cancel.wait(config.round_interval) {
+                return;
+            }
+        }
+    }
+}
+
+impl<D> InventoryWatch<D> {
+    /// Handle that cancels the watch from elsewhere
+    #[must_use]
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+
+    /// Next event, waiting at most `timeout`
+    ///
+    /// # Errors
+    /// Returns `RecvTimeoutError::Timeout` if no event arrived in time, or
+    /// `RecvTimeoutError::Disconnected` once the watch has stopped.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<InventoryEvent, RecvTimeoutError> {
+        self.events.recv_timeout(timeout)
+    }
+
+    /// Events already waiting, without blocking
+    pub fn try_events(&self) -> impl Iterator<Item = InventoryEvent> + '_ {
+        self.events.try_iter()
+    }
+
+    /// Cancel the watch, wait for the current round to finish and return the device
+    ///
+    /// # Errors
+    /// Returns `RfidError::Protocol` if the background thread panicked.
+    pub fn stop(self) -> Result<D, RfidError> {
+        self.cancel.cancel();
+        self.worker
+            .join()
+            .map_err(|_| RfidError::Protocol("Inventory watch thread panicked".to_string()))
+    }
+}
+
+impl<D> Iterator for InventoryWatch<D> {
+    type Item = InventoryEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.events.recv().ok()
+    }
+}
@@ -1,11 +1,23 @@
 //! API surface for RFID operations (errors, helpers, and high-level UHF API).
 //! Modules:
+//! - `epc`: GS1 EPC Tag Data Standard encoding of 96-bit EPCs
 //! - `error`: error types used across the API
-//! - `lock_pattern_builder`: helpers for lock pattern construction
+//! - `inventory_watch`: continuous inventory with tag arrival/departure events
+//! - `template`: typed fields at fixed places in User memory
+//! - `tid`: TID bank decoding and known tag chips
 //! - `uhf_rfid_api`: high-level operations over the low-level protocol
+//! - `user_memory`: ISO/IEC 15962 data sets in User memory
+/// GS1 EPC Tag Data Standard encoding
+pub mod epc;
 /// Error types used across the API
 pub mod error;
-/// Helpers for lock pattern construction
-pub mod lock_pattern_builder;
+/// Continuous inventory on a background thread
+pub mod inventory_watch;
+/// Typed User memory fields
+pub mod template;
+/// TID decoding and known tag chips
+pub mod tid;
 /// High-level UHF RFID operations
 pub mod uhf_rfid_api;
+/// ISO/IEC 15962 User memory encoding
+pub mod user_memory;
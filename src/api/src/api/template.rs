@@ -0,0 +1,787 @@
+//! Templates of typed fields at fixed places in User memory.
+//!
+//! A [`Template`] names fields by their word offset, bit offset within that word (counted
+//! from the most significant bit) and width in bits. Fields may cross word boundaries.
+//! Each field has a type that turns its bits into a [`FieldValue`] and text back into bits:
+//!
+//! - `uint` and `int`: unsigned and two's complement integers of up to 64 bits
+//! - `ascii`: text padded with NUL bytes, a whole number of bytes wide
+//! - `bcd_date`: a date in BCD digits, `YYMMDD` in 24 bits or `YYYYMMDD` in 32 bits
+//! - `enum`: a number with names for its values
+//! - `flags`: named bits, the first name being the least significant bit
+//!
+//! Writing fields produces a [`TemplateUpdate`]; words that fields only partly cover are
+//! read first so that their other bits are kept.
+
+use protocl::codec::MAX_WORD_ADDRESS;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::ops::Range;
+use thiserror::Error;
+
+/// Bits in a memory word
+const WORD_BITS: u32 = 16;
+
+/// Widest integer, enum or flags field
+const MAX_NUMBER_BITS: u32 = 64;
+
+/// Errors checking a template or encoding field values
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// Two fields have the same name
+    #[error("Field '{0}' is defined more than once")]
+    DuplicateField(String),
+
+    /// No field has the name
+    #[error("The template has no field '{0}'")]
+    UnknownField(String),
+
+    /// The bit offset is not within the first word
+    #[error("Field '{field}' starts at bit {bit}; the bit offset must be 0-15")]
+    BitOffset {
+        /// Field name
+        field: String,
+        /// Bit offset within the word
+        bit: u32,
+    },
+
+    /// The field reaches past the last word the reader can address
+    #[error(
+        "Field '{field}' at word {word} reaches past word {}, the last the reader can address",
+        MAX_WORD_ADDRESS
+    )]
+    WordAddress {
+        /// Field name
+        field: String,
+        /// Word address of the field's first bit
+        word: u32,
+    },
+
+    /// The width does not suit the field type
+    #[error("Field '{field}' is {bits} bits wide, but {expected}")]
+    Width {
+        /// Field name
+        field: String,
+        /// Width in bits
+        bits: u32,
+        /// What the field type needs
+        expected: &'static str,
+    },
+
+    /// A value does not fit the field
+    #[error("Invalid value for field '{field}': {message}")]
+    Value {
+        /// Field name
+        field: String,
+        /// What is wrong with the value
+        message: String,
+    },
+}
+
+/// Fields at fixed places in User memory
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Template {
+    /// Fields in the order they are shown
+    pub fields: Vec<Field>,
+}
+
+/// Named, typed bits of User memory
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Field {
+    /// Name used to show and set the field
+    pub name: String,
+    /// Word address of the first bit
+    pub word: u32,
+    /// Bit offset of the first bit within its word, from the most significant bit
+    #[serde(default)]
+    pub bit: u32,
+    /// Width in bits
+    pub bits: u32,
+    /// How the bits are interpreted
+    #[serde(flatten)]
+    pub kind: FieldKind,
+}
+
+/// Type of a field
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FieldKind {
+    /// Unsigned integer
+    Uint,
+    /// Two's complement integer
+    Int,
+    /// ASCII text padded with NUL bytes
+    Ascii,
+    /// Date of BCD digits, `YYMMDD` or `YYYYMMDD`
+    BcdDate,
+    /// Number with named values
+    Enum {
+        /// Value of each name
+        values: BTreeMap<String, u64>,
+    },
+    /// Named bits, from the least significant bit up
+    Flags {
+        /// Name of each bit
+        flags: Vec<String>,
+    },
+}
+
+/// Decoded value of a field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldValue {
+    /// Unsigned integer
+    Uint(u64),
+    /// Signed integer
+    Int(i64),
+    /// Text without its NUL padding
+    Ascii(String),
+    /// Calendar date
+    Date {
+        /// Year
+        year: u16,
+        /// Month, 1-12
+        month: u8,
+        /// Day of the month
+        day: u8,
+    },
+    /// Enum value with its name, if it has one
+    Enum {
+        /// Number stored
+        value: u64,
+        /// Name of the number
+        name: Option<String>,
+    },
+    /// Names of the set bits; unnamed bits are named `bit<n>`
+    Flags(Vec<String>),
+    /// Bits that are not a valid value of the field type
+    Invalid(Vec<u8>),
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Uint(value) => write!(f, "{value}"),
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Ascii(text) => write!(f, "\"{text}\""),
+            Self::Date { year, month, day } => write!(f, "{year:04}-{month:02}-{day:02}"),
+            Self::Enum {
+                value,
+                name: Some(name),
+            } => write!(f, "{name} ({value})"),
+            Self::Enum { value, name: None } => write!(f, "unknown ({value})"),
+            Self::Flags(names) if names.is_empty() => f.write_str("none"),
+            Self::Flags(names) => f.write_str(&names.join(", ")),
+            Self::Invalid(bits) => write!(f, "invalid (0x{})", hex::encode_upper(bits)),
+        }
+    }
+}
+
+impl Template {
+    /// Check field names, bit offsets, word addresses and widths
+    ///
+    /// # Errors
+    /// Returns an error for the first field that is named twice, lies beyond the addressable
+    /// words or whose width does not suit its type.
+    pub fn validate(&self) -> Result<(), TemplateError> {
+        let mut names = HashSet::new();
+        for field in &self.fields {
+            if !names.insert(field.name.as_str()) {
+                return Err(TemplateError::DuplicateField(field.name.clone()));
+            }
+            field.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Field with the name
+    ///
+    /// # Errors
+    /// Returns an error if no field has the name.
+    pub fn field(&self, name: &str) -> Result<&Field, TemplateError> {
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+            .ok_or_else(|| TemplateError::UnknownField(name.to_string()))
+    }
+
+    /// First word address and word count covering all fields
+    #[must_use]
+    pub fn span(&self) -> (u32, u32) {
+        span(self.fields.iter())
+    }
+
+    /// Decode every field from words read from `first_word`
+    ///
+    /// Fields beyond the words read decode as empty [`FieldValue::Invalid`] values.
+    #[must_use]
+    pub fn decode(&self, data: &[u8], first_word: u32) -> Vec<(String, FieldValue)> {
+        self.fields
+            .iter()
+            .map(|field| {
+                let value = field
+                    .raw(data, first_word)
+                    .map_or(FieldValue::Invalid(Vec::new()), |raw| field.decode(&raw));
+                (field.name.clone(), value)
+            })
+            .collect()
+    }
+
+    /// Encode field values given as text, by field name
+    ///
+    /// # Errors
+    /// Returns an error if a name is not a field of the template or a value does not fit
+    /// its field.
+    pub fn encode<'a>(
+        &'a self,
+        values: &[(String, String)],
+    ) -> Result<TemplateUpdate<'a>, TemplateError> {
+        let fields = values
+            .iter()
+            .map(|(name, text)| {
+                let field = self.field(name)?;
+                Ok((field, field.encode(text)?))
+            })
+            .collect::<Result<Vec<_>, TemplateError>>()?;
+        let (first_word, words) = span(fields.iter().map(|(field, _)| *field));
+        Ok(TemplateUpdate {
+            first_word,
+            words,
+            fields,
+        })
+    }
+}
+
+impl Field {
+    /// Check the bit offset, the word addresses and that the width suits the type
+    ///
+    /// # Errors
+    /// Returns an error if the bit offset is beyond the first word, the field reaches past
+    /// the last addressable word or the width does not suit the type.
+    pub fn validate(&self) -> Result<(), TemplateError> {
+        if self.bit >= WORD_BITS {
+            return Err(TemplateError::BitOffset {
+                field: self.name.clone(),
+                bit: self.bit,
+            });
+        }
+        if self.words().end > u32::from(MAX_WORD_ADDRESS) + 1 {
+            return Err(TemplateError::WordAddress {
+                field: self.name.clone(),
+                word: self.word,
+            });
+        }
+        let bits = self.bits;
+        let expected = match &self.kind {
+            FieldKind::Uint | FieldKind::Int | FieldKind::Enum { .. }
+                if !(1..=MAX_NUMBER_BITS).contains(&bits) =>
+            {
+                "numbers are 1-64 bits wide"
+            }
+            FieldKind::Enum { values } if values.values().any(|&value| !fits(value, bits)) => {
+                "not all enum values fit"
+            }
+            FieldKind::Ascii if bits == 0 || !bits.is_multiple_of(8) => {
+                "text is a whole number of bytes wide"
+            }
+            FieldKind::BcdDate if bits != 24 && bits != 32 => "BCD dates are 24 or 32 bits wide",
+            FieldKind::Flags { flags }
+                if bits == 0 || bits > MAX_NUMBER_BITS || flags.len() > bits as usize =>
+            {
+                "flags need one bit each, up to 64"
+            }
+            _ => return Ok(()),
+        };
+        Err(TemplateError::Width {
+            field: self.name.clone(),
+            bits,
+            expected,
+        })
+    }
+
+    /// Word addresses the field's bits are in
+    #[must_use]
+    pub fn words(&self) -> Range<u32> {
+        // Widened so that fields of unchecked templates cannot overflow
+        let end_bit = u64::from(self.word) * u64::from(WORD_BITS)
+            + u64::from(self.bit)
+            + u64::from(self.bits);
+        let end = u32::try_from(end_bit.div_ceil(u64::from(WORD_BITS))).unwrap_or(u32::MAX);
+        self.word..end
+    }
+
+    /// Decode the field's bits, right-aligned in whole bytes
+    #[must_use]
+    pub fn decode(&self, raw: &[u8]) -> FieldValue {
+        let number = to_u64(raw);
+        match &self.kind {
+            FieldKind::Uint => FieldValue::Uint(number),
+            FieldKind::Int => {
+                let shift = MAX_NUMBER_BITS - self.bits;
+                // Sign-extend by moving the field's top bit to bit 63 and back
+                FieldValue::Int(((number << shift).cast_signed()) >> shift)
+            }
+            FieldKind::Ascii => {
+                let end = raw.iter().rposition(|&byte| byte != 0).map_or(0, |i| i + 1);
+                FieldValue::Ascii(raw[..end].escape_ascii().to_string())
+            }
+            FieldKind::BcdDate => {
+                decode_date(raw).unwrap_or_else(|| FieldValue::Invalid(raw.to_vec()))
+            }
+            FieldKind::Enum { values } => FieldValue::Enum {
+                value: number,
+                name: values
+                    .iter()
+                    .find(|&(_, &value)| value == number)
+                    .map(|(name, _)| name.clone()),
+            },
+            FieldKind::Flags { flags } => FieldValue::Flags(
+                (0..self.bits)
+                    .filter(|&bit| number & (1 << bit) != 0)
+                    .map(|bit| {
+                        flags
+                            .get(bit as usize)
+                            .cloned()
+                            .unwrap_or_else(|| format!("bit{bit}"))
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Encode a value given as text into the field's bits, right-aligned in whole bytes
+    ///
+    /// Integers and enum numbers may be decimal or `0x` hexadecimal. Dates are
+    /// `YYYY-MM-DD`, and flags are names joined with `|` or `,` (or `none`).
+    ///
+    /// # Errors
+    /// Returns an error if the text is not a value of the type or does not fit the width.
+    pub fn encode(&self, text: &str) -> Result<Vec<u8>, TemplateError> {
+        let invalid = |message: String| TemplateError::Value {
+            field: self.name.clone(),
+            message,
+        };
+        let bits = self.bits;
+        let number = match &self.kind {
+            FieldKind::Uint => parse_u64(text)
+                .ok_or_else(|| invalid(format!("'{text}' is not an unsigned integer")))?,
+            FieldKind::Int => {
+                let value: i64 = text
+                    .trim()
+                    .parse()
+                    .map_err(|_| invalid(format!("'{text}' is not an integer")))?;
+                let limit = 1i128 << (bits - 1);
+                if !(-limit..limit).contains(&i128::from(value)) {
+                    return Err(invalid(format!(
+                        "{value} does not fit in {bits} signed bits"
+                    )));
+                }
+                value.cast_unsigned() & mask(bits)
+            }
+            FieldKind::Ascii => {
+                let len = bits as usize / 8;
+                if !text.is_ascii() || text.len() > len {
+                    return Err(invalid(format!(
+                        "'{text}' is not ASCII text of at most {len} characters"
+                    )));
+                }
+                let mut raw = text.as_bytes().to_vec();
+                raw.resize(len, 0);
+                return Ok(raw);
+            }
+            FieldKind::BcdDate => encode_date(text, bits).ok_or_else(|| {
+                invalid(format!(
+                    "'{text}' is not a date (YYYY-MM-DD) this field can hold"
+                ))
+            })?,
+            FieldKind::Enum { values } => match values.get(text.trim()) {
+                Some(&value) => value,
+                None => parse_u64(text).ok_or_else(|| {
+                    let names: Vec<_> = values.keys().map(String::as_str).collect();
+                    invalid(format!("'{text}' is not one of {}", names.join(", ")))
+                })?,
+            },
+            FieldKind::Flags { flags } => {
+                let mut number = 0;
+                for name in text.split(['|', ',']).map(str::trim) {
+                    if name.is_empty() || name == "none" {
+                        continue;
+                    }
+                    let bit = flags.iter().position(|flag| flag == name).ok_or_else(|| {
+                        invalid(format!("'{name}' is not one of {}", flags.join(", ")))
+                    })?;
+                    number |= 1 << bit;
+                }
+                number
+            }
+        };
+        if !fits(number, bits) {
+            return Err(invalid(format!("{number} does not fit in {bits} bits")));
+        }
+        let bytes = (bits as usize).div_ceil(8);
+        Ok(number.to_be_bytes()[8 - bytes..].to_vec())
+    }
+
+    /// The field's bits from words read from `first_word`, if the words cover it
+    fn raw(&self, data: &[u8], first_word: u32) -> Option<Vec<u8>> {
+        let start = self.start_bit(first_word)?;
+        let len = self.bits as usize;
+        (start + len <= data.len() * 8).then(|| get_bits(data, start, len))
+    }
+
+    /// Offset of the field's first bit in words read from `first_word`
+    fn start_bit(&self, first_word: u32) -> Option<usize> {
+        let word = self.word.checked_sub(first_word)?;
+        let bit = word.checked_mul(WORD_BITS)?.checked_add(self.bit)?;
+        Some(bit as usize)
+    }
+}
+
+/// Encoded field values and the words they are written to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateUpdate<'a> {
+    /// Word address of the first word written
+    pub first_word: u32,
+    /// Words from the first to the last word written
+    pub words: u32,
+    /// Fields with their encoded bits
+    fields: Vec<(&'a Field, Vec<u8>)>,
+}
+
+impl TemplateUpdate<'_> {
+    /// Whether a word is only partly covered by the fields, so it must be read first
+    #[must_use]
+    pub fn needs_read(&self) -> bool {
+        self.coverage()
+            .iter()
+            .any(|&mask| mask != 0 && mask != u16::MAX)
+    }
+
+    /// Place the encoded fields into words read from [`Self::first_word`]
+    pub fn apply(&self, data: &mut [u8]) {
+        for (field, raw) in &self.fields {
+            if let Some(start) = field.start_bit(self.first_word) {
+                set_bits(data, start, field.bits as usize, raw);
+            }
+        }
+    }
+
+    /// Runs of consecutive words the fields touch: their word address and byte range
+    #[must_use]
+    pub fn runs(&self) -> Vec<(u32, Range<usize>)> {
+        let mut runs: Vec<(u32, Range<usize>)> = Vec::new();
+        let words = (self.first_word..).zip(self.coverage().into_iter().enumerate());
+        for (word, (index, _)) in words.filter(|(_, (_, mask))| *mask != 0) {
+            let bytes = index * 2..index * 2 + 2;
+            match runs.last_mut() {
+                Some((_, run)) if run.end == bytes.start => run.end = bytes.end,
+                _ => runs.push((word, bytes)),
+            }
+        }
+        runs
+    }
+
+    /// Bits of each word covered by the fields, most significant bit first
+    fn coverage(&self) -> Vec<u16> {
+        let mut masks = vec![0u16; self.words as usize];
+        for (field, _) in &self.fields {
+            let Some(start) = field.start_bit(self.first_word) else {
+                continue;
+            };
+            for bit in start..start + field.bits as usize {
+                masks[bit / 16] |= 0x8000 >> (bit % 16);
+            }
+        }
+        masks
+    }
+}
+
+/// First word address and word count covering the fields
+fn span<'a>(fields: impl Iterator<Item = &'a Field>) -> (u32, u32) {
+    fields
+        .map(Field::words)
+        .reduce(|a, b| a.start.min(b.start)..a.end.max(b.end))
+        .map_or((0, 0), |words| (words.start, words.end - words.start))
+}
+
+/// Whether a number fits in `bits` bits
+fn fits(number: u64, bits: u32) -> bool {
+    bits >= MAX_NUMBER_BITS || number >> bits == 0
+}
+
+/// The lowest `bits` bits set
+fn mask(bits: u32) -> u64 {
+    u64::MAX >> (MAX_NUMBER_BITS - bits)
+}
+
+/// Decimal or `0x` hexadecimal unsigned integer
+fn parse_u64(text: &str) -> Option<u64> {
+    let text = text.trim();
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+/// Big-endian bytes as a number, keeping the last eight
+fn to_u64(raw: &[u8]) -> u64 {
+    raw.iter()
+        .fold(0, |number, &byte| (number << 8) | u64::from(byte))
+}
+
+/// Decode `YYMMDD` or `YYYYMMDD` BCD digits
+#[allow(clippy::cast_possible_truncation)]
+fn decode_date(raw: &[u8]) -> Option<FieldValue> {
+    let mut digits = Vec::new();
+    for &byte in raw {
+        for digit in [byte >> 4, byte & 0x0F] {
+            if digit > 9 {
+                return None;
+            }
+            digits.push(u16::from(digit));
+        }
+    }
+    if digits.len() < 6 {
+        return None;
+    }
+    let number = |digits: &[u16]| digits.iter().fold(0, |n, &d| n * 10 + d);
+    let (year, rest) = digits.split_at(digits.len() - 4);
+    let year = if year.len() == 2 {
+        2000 + number(year)
+    } else {
+        number(year)
+    };
+    // Two digits each, so the values fit
+    let month = number(&rest[..2]) as u8;
+    let day = number(&rest[2..]) as u8;
+    is_date(year, month, day).then_some(FieldValue::Date { year, month, day })
+}
+
+/// Encode a `YYYY-MM-DD` date as BCD digits, `YYMMDD` for 24-bit fields
+fn encode_date(text: &str, bits: u32) -> Option<u64> {
+    let mut parts = text.trim().splitn(3, '-');
+    let year: u16 = parts.next()?.parse().ok()?;
+    let month: u8 = parts.next()?.parse().ok()?;
+    let day: u8 = parts.next()?.parse().ok()?;
+    if !is_date(year, month, day) || (bits == 24 && !(2000..2100).contains(&year)) {
+        return None;
+    }
+    let digits = if bits == 24 {
+        format!("{:02}{month:02}{day:02}", year % 100)
+    } else {
+        format!("{year:04}{month:02}{day:02}")
+    };
+    // BCD digits read as hexadecimal are the BCD bytes
+    u64::from_str_radix(&digits, 16).ok()
+}
+
+/// Whether the day exists in the month of the year
+fn is_date(year: u16, month: u8, day: u8) -> bool {
+    let leap = year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days).contains(&day)
+}
+
+/// Bits `start..start + len` of `data`, most significant bit first, right-aligned in whole
+/// bytes
+fn get_bits(data: &[u8], start: usize, len: usize) -> Vec<u8> {
+    let mut raw = vec![0u8; len.div_ceil(8)];
+    let pad = raw.len() * 8 - len;
+    for i in 0..len {
+        let bit = (data[(start + i) / 8] >> (7 - (start + i) % 8)) & 1;
+        raw[(pad + i) / 8] |= bit << (7 - (pad + i) % 8);
+    }
+    raw
+}
+
+/// Set bits `start..start + len` of `data` from bits right-aligned in whole bytes
+fn set_bits(data: &mut [u8], start: usize, len: usize, raw: &[u8]) {
+    let pad = raw.len() * 8 - len;
+    for i in 0..len {
+        let bit = (raw[(pad + i) / 8] >> (7 - (pad + i) % 8)) & 1;
+        let byte = &mut data[(start + i) / 8];
+        let shift = 7 - (start + i) % 8;
+        *byte = (*byte & !(1 << shift)) | (bit << shift);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template() -> Template {
+        let field = |name: &str, word, bit, bits, kind| Field {
+            name: name.to_string(),
+            word,
+            bit,
+            bits,
+            kind,
+        };
+        Template {
+            fields: vec![
+                field("asset_id", 0, 0, 32, FieldKind::Uint),
+                field("installed", 2, 0, 32, FieldKind::BcdDate),
+                field("inspections", 4, 0, 10, FieldKind::Uint),
+                field(
+                    "status",
+                    4,
+                    10,
+                    2,
+                    FieldKind::Enum {
+                        values: BTreeMap::from([
+                            ("active".to_string(), 1),
+                            ("retired".to_string(), 2),
+                        ]),
+                    },
+                ),
+                field(
+                    "alarms",
+                    4,
+                    12,
+                    4,
+                    FieldKind::Flags {
+                        flags: vec!["tamper".to_string(), "battery".to_string()],
+                    },
+                ),
+                field("offset", 5, 0, 8, FieldKind::Int),
+                field("site", 5, 8, 32, FieldKind::Ascii),
+            ],
+        }
+    }
+
+    #[test]
+    fn fields_decode_from_their_bits() {
+        let template = template();
+        template.validate().unwrap();
+        assert_eq!(template.span(), (0, 8));
+        let data = [
+            0x00, 0x01, 0xE2, 0x40, // asset_id 123456
+            0x20, 0x26, 0x10, 0x17, // installed
+            0x07, 0x11, // inspections 28, status active, alarms tamper
+            0xFE, b'N', b'O', b'R', 0x00, 0x00, // offset -2, site "NOR"
+        ];
+        let values: Vec<String> = template
+            .decode(&data, 0)
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect();
+        assert_eq!(
+            values,
+            [
+                "asset_id=123456",
+                "installed=2026-10-17",
+                "inspections=28",
+                "status=active (1)",
+                "alarms=tamper",
+                "offset=-2",
+                "site=\"NOR\""
+            ]
+        );
+    }
+
+    #[test]
+    fn values_encode_to_their_bits_and_back() {
+        let template = template();
+        let cases = [
+            ("asset_id", "0xCAFE", "51966"),
+            ("installed", "2024-02-29", "2024-02-29"),
+            ("inspections", "1023", "1023"),
+            ("status", "retired", "retired (2)"),
+            ("alarms", "tamper|battery", "tamper, battery"),
+            ("offset", "-128", "-128"),
+            ("site", "NE12", "\"NE12\""),
+        ];
+        for (name, text, shown) in cases {
+            let field = template.field(name).unwrap();
+            let raw = field.encode(text).unwrap();
+            assert_eq!(field.decode(&raw).to_string(), shown, "{name}");
+        }
+        let invalid = [
+            ("inspections", "1024"),
+            ("installed", "2023-02-29"),
+            ("status", "lost"),
+            ("alarms", "fire"),
+            ("offset", "128"),
+            ("site", "NORTH"),
+            ("serial", "1"),
+        ];
+        for (name, text) in invalid {
+            let result = template.field(name).and_then(|field| field.encode(text));
+            assert!(result.is_err(), "{name}={text}");
+        }
+    }
+
+    #[test]
+    fn updates_read_only_partly_covered_words() {
+        let template = template();
+        let whole = template
+            .encode(&[("asset_id".to_string(), "7".to_string())])
+            .unwrap();
+        assert!(!whole.needs_read());
+        assert_eq!(whole.runs(), [(0, 0..4)]);
+
+        let values = [
+            ("status".to_string(), "retired".to_string()),
+            ("site".to_string(), "AB".to_string()),
+        ];
+        let update = template.encode(&values).unwrap();
+        assert!(update.needs_read());
+        assert_eq!((update.first_word, update.words), (4, 4));
+        assert_eq!(update.runs(), [(4, 0..8)]);
+        let mut data = [0xFF; 8];
+        update.apply(&mut data);
+        assert_eq!(data, [0xFF, 0xEF, 0xFF, b'A', b'B', 0x00, 0x00, 0xFF]);
+    }
+
+    #[test]
+    fn validation_rejects_bad_widths_and_names() {
+        let mut template = template();
+        template.fields[1].bits = 16;
+        assert!(matches!(
+            template.validate(),
+            Err(TemplateError::Width { bits: 16, .. })
+        ));
+        let mut template = self::template();
+        template.fields[6].name = "asset_id".to_string();
+        assert_eq!(
+            template.validate(),
+            Err(TemplateError::DuplicateField("asset_id".to_string()))
+        );
+    }
+
+    #[test]
+    fn validation_rejects_fields_past_the_last_word() {
+        let mut template = template();
+        // Text crossing into a third word may end at the last address, but not beyond
+        template.fields[6].word = 253;
+        template.validate().unwrap();
+        template.fields[6].word = 254;
+        assert_eq!(
+            template.validate(),
+            Err(TemplateError::WordAddress {
+                field: "site".to_string(),
+                word: 254,
+            })
+        );
+        let mut template = self::template();
+        template.fields[0].word = u32::MAX;
+        assert_eq!(
+            template.validate(),
+            Err(TemplateError::WordAddress {
+                field: "asset_id".to_string(),
+                word: u32::MAX,
+            })
+        );
+        assert_eq!(
+            template.decode(&[0; 16], 0)[0].1,
+            FieldValue::Invalid(Vec::new())
+        );
+    }
+}
@@ -0,0 +1,511 @@
+//! Decoding of the Gen2 TID bank and a table of known tag chips.
+//!
+//! The first two TID words name the chip: the class identifier (0xE2 for Gen2 tags), the
+//! XTID, security and file indicators, the mask designer ID (MDID) and the tag model
+//! number (TMN). Tags with the XTID indicator follow them with an XTID header and usually
+//! a unique serial number.
+//!
+//! [`Chip`] maps the MDID and TMN to a known chip with its memory sizes and features. The
+//! table covers common chips and is not exhaustive; tags with other chips are reported by
+//! manufacturer only.
+
+use protocl::types::MemoryBank;
+use std::fmt;
+use thiserror::Error;
+
+/// Class identifier of tags with an `EPCglobal` mask designer ID
+pub const GEN2_CLASS: u8 = 0xE2;
+
+/// Words holding the class identifier, MDID and TMN
+pub const TID_HEADER_WORDS: u32 = 2;
+
+const TID_HEADER_BYTES: u32 = TID_HEADER_WORDS * 2;
+
+/// Word address of the XTID header
+pub const XTID_HEADER_ADDRESS: u32 = 2;
+
+/// Words of the Reserved bank: kill and access passwords
+const RESERVED_WORDS: u32 = 4;
+
+/// Words before the EPC in the EPC bank: `StoredCRC` and PC
+const EPC_HEADER_WORDS: u32 = 2;
+
+/// Errors decoding the TID bank or checking an address against a chip
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TidError {
+    /// Fewer bytes than the class identifier, MDID and TMN need
+    #[error("The TID needs at least {TID_HEADER_BYTES} bytes, got {0}")]
+    TooShort(usize),
+
+    /// The class identifier is not the Gen2 one
+    #[error("TID class 0x{0:02X} has no mask designer ID and model number")]
+    UnsupportedClass(u8),
+
+    /// A memory range ends beyond the bank of the chip
+    #[error("The {chip} has {words} words of {bank:?} memory, but the range ends at word {end}")]
+    BeyondBank {
+        /// Chip name
+        chip: &'static str,
+        /// Memory bank of the range
+        bank: MemoryBank,
+        /// Words in the bank
+        words: u32,
+        /// First word after the range
+        end: u32,
+    },
+}
+
+/// Decoded start of a Gen2 TID bank
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tid {
+    /// Extended TID indicator: an XTID header follows the model number
+    pub xtid: bool,
+    /// Security indicator: the tag supports Authenticate or Challenge
+    pub security: bool,
+    /// File indicator: the tag supports the file commands
+    pub file: bool,
+    /// Mask designer ID, the chip manufacturer
+    pub mdid: u16,
+    /// Tag model number assigned by the manufacturer
+    pub tmn: u16,
+    /// XTID header, if the tag has one and it was read
+    pub xtid_header: Option<XtidHeader>,
+    /// Unique serial number from the XTID, if it was read
+    pub serial: Option<Vec<u8>>,
+}
+
+/// Header word describing the segments of an extended TID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct XtidHeader {
+    /// Serial number length: 0 for none, otherwise 48 + 16 * (n - 1) bits
+    pub serialization: u8,
+    /// Optional command support segment present
+    pub optional_commands: bool,
+    /// `BlockWrite` and `BlockErase` segment present
+    pub block_write: bool,
+    /// User memory and `BlockPermaLock` segment present
+    pub user_memory: bool,
+    /// Lock bit segment present
+    pub lock_bits: bool,
+    /// A further header word follows
+    pub extended_header: bool,
+}
+
+impl XtidHeader {
+    /// Decode the XTID header word
+    #[must_use]
+    pub fn from_word(word: u16) -> Self {
+        let bit = |n: u16| word & (1 << n) != 0;
+        Self {
+            // Three bits, so the value fits
+            serialization: (word >> 13) as u8,
+            optional_commands: bit(12),
+            block_write: bit(11),
+            user_memory: bit(10),
+            lock_bits: bit(9),
+            extended_header: bit(0),
+        }
+    }
+
+    /// Words of the serial number following the header
+    #[must_use]
+    pub fn serial_words(&self) -> u32 {
+        match self.serialization {
+            0 => 0,
+            n => 3 + u32::from(n - 1),
+        }
+    }
+}
+
+impl Tid {
+    /// Decode the start of a TID bank read from word 0
+    ///
+    /// The XTID header and serial number are decoded when `bytes` include them.
+    ///
+    /// # Errors
+    /// Returns an error if `bytes` are shorter than two words or not of the Gen2 class.
+    pub fn decode(bytes: &[u8]) -> Result<Self, TidError> {
+        let word = |index: usize| {
+            bytes
+                .get(index * 2..index * 2 + 2)
+                .map(|word| u16::from_be_bytes([word[0], word[1]]))
+        };
+        let (Some(first), Some(second)) = (word(0), word(1)) else {
+            return Err(TidError::TooShort(bytes.len()));
+        };
+        let class = first.to_be_bytes()[0];
+        if class != GEN2_CLASS {
+            return Err(TidError::UnsupportedClass(class));
+        }
+        let designer = ((u32::from(first) & 0xFF) << 16) | u32::from(second);
+        let bit = |n: u32| designer & (1 << n) != 0;
+        let xtid = bit(23);
+        let xtid_header = word(2).filter(|_| xtid).map(XtidHeader::from_word);
+        let serial = xtid_header.and_then(|header| {
+            let start = usize::try_from(XTID_HEADER_ADDRESS + 1).ok()? * 2;
+            let len = usize::try_from(header.serial_words()).ok()? * 2;
+            (len > 0)
+                .then(|| bytes.get(start..start + len))?
+                .map(<[u8]>::to_vec)
+        });
+        Ok(Self {
+            xtid,
+            security: bit(22),
+            file: bit(21),
+            // Nine and twelve bits, so the values fit
+            mdid: ((designer >> 12) & 0x1FF) as u16,
+            tmn: (designer & 0xFFF) as u16,
+            xtid_header,
+            serial,
+        })
+    }
+
+    /// Name of the chip manufacturer, if the MDID is known
+    #[must_use]
+    pub fn manufacturer(&self) -> Option<&'static str> {
+        manufacturer(self.mdid)
+    }
+
+    /// Known chip with this MDID and TMN
+    #[must_use]
+    pub fn chip(&self) -> Option<&'static Chip> {
+        Chip::lookup(self.mdid, self.tmn)
+    }
+
+    /// Labelled lines describing the TID, for display
+    #[must_use]
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let yes_no = |flag: bool| if flag { "yes" } else { "no" };
+        let mut fields = vec![
+            (
+                "Manufacturer",
+                format!(
+                    "{} (MDID 0x{:03X})",
+                    self.manufacturer().unwrap_or("Unknown"),
+                    self.mdid
+                ),
+            ),
+            (
+                "Model",
+                format!(
+                    "{} (TMN 0x{:03X})",
+                    self.chip().map_or("Unknown", |chip| chip.name),
+                    self.tmn
+                ),
+            ),
+            (
+                "Indicators",
+                format!(
+                    "XTID {}, security {}, file {}",
+                    yes_no(self.xtid),
+                    yes_no(self.security),
+                    yes_no(self.file)
+                ),
+            ),
+        ];
+        if let Some(header) = &self.xtid_header {
+            let mut segments = vec![format!("{}-word serial", header.serial_words())];
+            for (present, name) in [
+                (header.optional_commands, "optional commands"),
+                (header.block_write, "BlockWrite/BlockErase"),
+                (header.user_memory, "User memory/BlockPermaLock"),
+                (header.lock_bits, "lock bits"),
+                (header.extended_header, "extended header"),
+            ] {
+                if present {
+                    segments.push(name.to_string());
+                }
+            }
+            fields.push(("XTID", segments.join(", ")));
+        }
+        if let Some(serial) = &self.serial {
+            fields.push(("Serial", hex::encode_upper(serial)));
+        }
+        if let Some(chip) = self.chip() {
+            fields.push((
+                "Memory",
+                format!("{}-bit EPC, {}-bit User", chip.epc_bits, chip.user_bits),
+            ));
+            if !chip.features.is_empty() {
+                let features: Vec<String> = chip.features.iter().map(ToString::to_string).collect();
+                fields.push(("Features", features.join(", ")));
+            }
+        }
+        fields
+    }
+}
+
+impl fmt::Display for Tid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (label, value)) in self.fields().iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{label}: {value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Optional chip feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChipFeature {
+    /// `BlockWrite` of several words in one command
+    BlockWrite,
+    /// `BlockPermalock` of User memory blocks
+    BlockPermalock,
+    /// Impinj `FastID`: the TID is backscattered with the EPC
+    FastId,
+    /// Impinj `TagFocus`: tags stay quiet once inventoried
+    TagFocus,
+    /// Impinj QT: public and private memory profiles
+    QtPrivacy,
+}
+
+impl fmt::Display for ChipFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ChipFeature::BlockWrite => "BlockWrite",
+            ChipFeature::BlockPermalock => "BlockPermalock",
+            ChipFeature::FastId => "FastID",
+            ChipFeature::TagFocus => "TagFocus",
+            ChipFeature::QtPrivacy => "QT privacy",
+        })
+    }
+}
+
+/// Known tag chip
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chip {
+    /// Mask designer ID
+    pub mdid: u16,
+    /// Tag model number
+    pub tmn: u16,
+    /// Chip name
+    pub name: &'static str,
+    /// Longest EPC the chip stores, in bits
+    pub epc_bits: u16,
+    /// Size of User memory in bits
+    pub user_bits: u16,
+    /// Optional features
+    pub features: &'static [ChipFeature],
+}
+
+const IMPINJ: u16 = 0x001;
+const ALIEN: u16 = 0x003;
+const NXP: u16 = 0x006;
+const EM: u16 = 0x00B;
+
+const MONZA_4: &[ChipFeature] = &[
+    ChipFeature::BlockWrite,
+    ChipFeature::BlockPermalock,
+    ChipFeature::FastId,
+    ChipFeature::TagFocus,
+];
+const MONZA_4QT: &[ChipFeature] = &[
+    ChipFeature::BlockWrite,
+    ChipFeature::BlockPermalock,
+    ChipFeature::FastId,
+    ChipFeature::TagFocus,
+    ChipFeature::QtPrivacy,
+];
+const IMPINJ_CURRENT: &[ChipFeature] = &[ChipFeature::FastId, ChipFeature::TagFocus];
+
+const fn chip(
+    mdid: u16,
+    tmn: u16,
+    name: &'static str,
+    epc_bits: u16,
+    user_bits: u16,
+    features: &'static [ChipFeature],
+) -> Chip {
+    Chip {
+        mdid,
+        tmn,
+        name,
+        epc_bits,
+        user_bits,
+        features,
+    }
+}
+
+/// Known chips by MDID and TMN
+pub const CHIPS: &[Chip] = &[
+    chip(IMPINJ, 0x100, "Impinj Monza 4D", 128, 32, MONZA_4),
+    chip(IMPINJ, 0x105, "Impinj Monza 4QT", 128, 512, MONZA_4QT),
+    chip(IMPINJ, 0x10C, "Impinj Monza 4E", 496, 128, MONZA_4),
+    chip(IMPINJ, 0x130, "Impinj Monza 5", 128, 32, MONZA_4),
+    chip(IMPINJ, 0x160, "Impinj Monza R6", 96, 0, IMPINJ_CURRENT),
+    chip(IMPINJ, 0x170, "Impinj Monza R6-P", 128, 64, IMPINJ_CURRENT),
+    chip(IMPINJ, 0x190, "Impinj M750", 96, 32, IMPINJ_CURRENT),
+    chip(IMPINJ, 0x191, "Impinj M730", 128, 0, IMPINJ_CURRENT),
+    chip(
+        NXP,
+        0x003,
+        "NXP UCODE G2XM",
+        240,
+        512,
+        &[ChipFeature::BlockWrite],
+    ),
+    chip(
+        NXP,
+        0x004,
+        "NXP UCODE G2XL",
+        240,
+        0,
+        &[ChipFeature::BlockWrite],
+    ),
+    chip(NXP, 0x906, "NXP UCODE G2iL", 128, 0, &[]),
+    chip(NXP, 0x907, "NXP UCODE G2iL+", 128, 0, &[]),
+    chip(NXP, 0x890, "NXP UCODE 7", 128, 0, &[]),
+    chip(NXP, 0x894, "NXP UCODE 8", 128, 0, &[]),
+    chip(NXP, 0x994, "NXP UCODE 8m", 128, 32, &[]),
+    chip(NXP, 0x915, "NXP UCODE 9", 96, 0, &[]),
+    chip(
+        ALIEN,
+        0x412,
+        "Alien Higgs-3",
+        480,
+        512,
+        &[ChipFeature::BlockPermalock],
+    ),
+    chip(ALIEN, 0x414, "Alien Higgs-4", 128, 128, &[]),
+    chip(EM, 0x040, "EM4124", 128, 0, &[]),
+    chip(EM, 0x041, "EM4126", 208, 0, &[]),
+];
+
+/// Chip manufacturers by MDID
+const MANUFACTURERS: &[(u16, &str)] = &[
+    (IMPINJ, "Impinj"),
+    (0x002, "Texas Instruments"),
+    (ALIEN, "Alien Technology"),
+    (0x004, "Intelleflex"),
+    (0x005, "Atmel"),
+    (NXP, "NXP Semiconductors"),
+    (0x007, "STMicroelectronics"),
+    (0x008, "EP Microelectronics"),
+    (0x009, "Motorola"),
+    (0x00A, "Sentech"),
+    (EM, "EM Microelectronic"),
+    (0x00C, "Renesas"),
+    (0x00D, "Mstar"),
+    (0x00E, "Tyco International"),
+    (0x00F, "Quanray Electronics"),
+    (0x010, "Fujitsu"),
+];
+
+/// Name of the chip manufacturer with this MDID, if known
+#[must_use]
+pub fn manufacturer(mdid: u16) -> Option<&'static str> {
+    MANUFACTURERS
+        .iter()
+        .find(|(id, _)| *id == mdid)
+        .map(|(_, name)| *name)
+}
+
+impl Chip {
+    /// Known chip with this MDID and TMN
+    #[must_use]
+    pub fn lookup(mdid: u16, tmn: u16) -> Option<&'static Chip> {
+        CHIPS
+            .iter()
+            .find(|chip| chip.mdid == mdid && chip.tmn == tmn)
+    }
+
+    /// Words in `bank`, or `None` if the chip does not say (the TID bank)
+    #[must_use]
+    pub fn bank_words(&self, bank: MemoryBank) -> Option<u32> {
+        match bank {
+            MemoryBank::Reserved => Some(RESERVED_WORDS),
+            MemoryBank::Epc => Some(EPC_HEADER_WORDS + u32::from(self.epc_bits) / 16),
+            MemoryBank::Tid => None,
+            MemoryBank::User => Some(u32::from(self.user_bits) / 16),
+        }
+    }
+
+    /// Check that `words` words from `address` lie within `bank`
+    ///
+    /// # Errors
+    /// Returns `TidError::BeyondBank` if the range ends beyond the bank.
+    pub fn check_range(&self, bank: MemoryBank, address: u32, words: u32) -> Result<(), TidError> {
+        let end = address.saturating_add(words);
+        match self.bank_words(bank) {
+            Some(bank_words) if end > bank_words => Err(TidError::BeyondBank {
+                chip: self.name,
+                bank,
+                words: bank_words,
+                end,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_a_monza_r6_with_its_serial() {
+        let bytes = hex::decode("E2801160200012345678ABCD").unwrap();
+        let tid = Tid::decode(&bytes).unwrap();
+        assert!(tid.xtid);
+        assert_eq!((tid.mdid, tid.tmn), (IMPINJ, 0x160));
+        assert_eq!(tid.manufacturer(), Some("Impinj"));
+        assert_eq!(tid.chip().unwrap().name, "Impinj Monza R6");
+        let header = tid.xtid_header.unwrap();
+        assert_eq!(header.serialization, 1);
+        assert_eq!(header.serial_words(), 3);
+        assert_eq!(tid.serial.as_deref(), Some(&bytes[6..12]));
+    }
+
+    #[test]
+    fn decodes_tids_without_an_xtid() {
+        let tid = Tid::decode(&hex::decode("E2003412013F").unwrap()).unwrap();
+        assert!(!tid.xtid);
+        assert_eq!((tid.mdid, tid.tmn), (ALIEN, 0x412));
+        assert_eq!(tid.xtid_header, None);
+        assert_eq!(tid.serial, None);
+        assert_eq!(tid.chip().unwrap().name, "Alien Higgs-3");
+
+        // An unknown model of a known manufacturer
+        let tid = Tid::decode(&hex::decode("E2806FFF").unwrap()).unwrap();
+        assert_eq!(tid.manufacturer(), Some("NXP Semiconductors"));
+        assert_eq!(tid.chip(), None);
+        assert!(tid.to_string().contains("Unknown (TMN 0xFFF)"));
+    }
+
+    #[test]
+    fn short_serials_and_other_classes_are_handled() {
+        // The XTID header promises a serial that was not read
+        let tid = Tid::decode(&hex::decode("E28011602000").unwrap()).unwrap();
+        assert_eq!(tid.xtid_header.unwrap().serial_words(), 3);
+        assert_eq!(tid.serial, None);
+        assert_eq!(Tid::decode(&[0xE2, 0x80]), Err(TidError::TooShort(2)));
+        assert_eq!(
+            Tid::decode(&[0xE0, 0x04, 0x01, 0x02]),
+            Err(TidError::UnsupportedClass(0xE0))
+        );
+    }
+
+    #[test]
+    fn ranges_are_checked_against_the_chip() {
+        let r6 = Chip::lookup(IMPINJ, 0x160).unwrap();
+        assert_eq!(r6.check_range(MemoryBank::Epc, 2, 6), Ok(()));
+        assert_eq!(
+            r6.check_range(MemoryBank::Epc, 2, 8),
+            Err(TidError::BeyondBank {
+                chip: "Impinj Monza R6",
+                bank: MemoryBank::Epc,
+                words: 8,
+                end: 10
+            })
+        );
+        assert!(r6.check_range(MemoryBank::User, 0, 1).is_err());
+        assert_eq!(r6.check_range(MemoryBank::Tid, 0, 100), Ok(()));
+        let m750 = Chip::lookup(IMPINJ, 0x190).unwrap();
+        assert_eq!(m750.check_range(MemoryBank::User, 0, 2), Ok(()));
+        assert!(m750.check_range(MemoryBank::Reserved, 2, 3).is_err());
+    }
+}
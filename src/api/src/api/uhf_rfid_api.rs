@@ -1,18 +1,66 @@
 use crate::api::error::RfidError;
-use crate::api::lock_pattern_builder::LockPatternBuilder;
-use crate::rfid_device::usb_device::UsbDevice;
-use protocl::interface::Interface;
+use crate::api::template::{FieldValue, Template};
+use crate::api::tid::{TID_HEADER_WORDS, Tid, XTID_HEADER_ADDRESS};
+use crate::api::user_memory::{UserData, UserDataError};
+use protocl::codec::{MAX_WORD_ADDRESS, MAX_WORDS_PER_COMMAND, STATUS_MEMORY_OVERRUN};
+use protocl::epc_bank::{
+    EPC_ADDRESS, EpcMemory, MAX_EPC_WORDS, PC_ADDRESS, ProtocolControl, XPC_W1_ADDRESS, XpcW1,
+};
+use protocl::interface::{Interface, RawOptions, RawReply, RetryPolicy};
 use protocl::types::{
-    DeviceAction, InventoryResult, LockAction, LockableMemoryBank, MemoryBank, PasswordLockAction,
+    DeviceAction, InventoryResult, LockAction, LockPayload, LockableMemoryBank, MemoryBank,
+    PasswordLockAction, ReaderIdentity, ReaderSettings, TagFilter, UsbIo,
 };
+use tracing::instrument;
+
+/// Words read per command while looking for the end of the User memory data sets
+pub const USER_DATA_BLOCK_WORDS: u32 = 8;
 
 /// High-level UHF RFID operations built on top of the protocol layer.
-pub struct UhfRfidApi {}
+///
+/// Commands are sent with the reply timeouts and retries of the API's [`RetryPolicy`];
+/// [`UhfRfidApi::default`] uses the default policy.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UhfRfidApi {
+    interface: Interface,
+}
+
+/// Progress of a memory transfer split across several reader commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferProgress {
+    /// Words transferred so far
+    pub words_done: u32,
+    /// Words in the whole transfer
+    pub words_total: u32,
+}
+
+/// Which tag an operation acts on and how it authenticates
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagAccess {
+    /// Only the tags matching this filter answer; the first tag in the field otherwise
+    pub target: Option<TagFilter>,
+    /// Access password that moves the tag to the Secured state before the operation
+    pub password: Option<u32>,
+}
 
 impl UhfRfidApi {
-    fn get_interface(usb_device: &UsbDevice) -> Result<Interface, RfidError> {
+    /// API sending commands with the given reply timeouts and retries
+    #[must_use]
+    pub fn new(policy: RetryPolicy) -> Self {
+        Self {
+            interface: Interface { policy },
+        }
+    }
+
+    /// Reply timeouts and retries the API sends commands with
+    #[must_use]
+    pub fn policy(&self) -> RetryPolicy {
+        self.interface.policy
+    }
+
+    fn get_interface(&self, usb_device: &impl UsbIo) -> Result<&Interface, RfidError> {
         if usb_device.is_connected() {
-            Ok(Interface { debug_mode: false })
+            Ok(&self.interface)
         } else {
             Err(RfidError::NotConnected)
         }
@@ -25,123 +73,425 @@ impl UhfRfidApi {
     ///
     /// # Errors
     /// Returns an error if the device is not connected or USB communication fails.
+    #[instrument(skip_all, fields(?actions, time), err(level = "debug"))]
     pub fn device_action(
-        usb_device: &UsbDevice,
+        &self,
+        usb_device: &impl UsbIo,
         actions: &[DeviceAction],
         time: u8,
     ) -> Result<(), RfidError> {
-        let interface = Self::get_interface(usb_device)?;
+        let interface = self.get_interface(usb_device)?;
         let action_byte = DeviceAction::combine(actions);
         if !usb_device.is_connected() {
             return Err(RfidError::NotConnected);
         }
-        // Pass the UsbDevice itself (implements UsbIo) to the protocol interface
+        // Pass the transport itself (implements UsbIo) to the protocol interface
         interface.action(usb_device, action_byte, time)?;
         Ok(())
     }
 
     /// Read data from a memory bank
-    /// Read data from a memory bank
+    ///
+    /// The data is returned as raw bytes, two per word, decoded from the reader's ASCII hex
+    /// words. Before the reader emulator was added, the ASCII hex itself was returned.
+    ///
+    /// Ranges longer than one reader command are split into several reads. A word count of
+    /// zero reads to the end of the bank in a single command.
     ///
     /// # Errors
-    /// Returns an error if the device is not connected or USB communication fails.
+    /// Returns an error if the range is not addressable, the device is not connected, or USB communication fails.
     pub fn read(
-        usb_device: &UsbDevice,
+        &self,
+        usb_device: &impl UsbIo,
         bank: MemoryBank,
         address: u32,
         word_count: u32,
     ) -> Result<Vec<u8>, RfidError> {
-        let interface = Self::get_interface(usb_device)?;
-        let address = u8::try_from(address)
-            .map_err(|_| RfidError::Protocol("Address out of range".to_owned()))?;
-        let word_count = u8::try_from(word_count)
-            .map_err(|_| RfidError::Protocol("Word count out of range".to_owned()))?;
+        self.read_with_progress(usb_device, bank, address, word_count, |_| {})
+    }
+
+    /// Read data from a memory bank, reporting progress after every reader command
+    ///
+    /// # Errors
+    /// Returns an error if the range is not addressable, the device is not connected, or USB communication fails.
+    #[instrument(skip_all, fields(?bank, address, word_count), err(level = "debug"))]
+    pub fn read_with_progress(
+        &self,
+        usb_device: &impl UsbIo,
+        bank: MemoryBank,
+        address: u32,
+        word_count: u32,
+        mut progress: impl FnMut(TransferProgress),
+    ) -> Result<Vec<u8>, RfidError> {
+        let interface = self.get_interface(usb_device)?;
+        if word_count == 0 {
+            let address = u8::try_from(address)
+                .map_err(|_| RfidError::Protocol("Address out of range".to_owned()))?;
+            return Ok(interface.read(usb_device, bank, address, 0)?);
+        }
+        let chunks = Self::word_chunks(address, word_count, MAX_WORDS_PER_COMMAND)?;
         if !usb_device.is_connected() {
             return Err(RfidError::NotConnected);
         }
-        let data = interface.read(usb_device, bank, address, word_count)?;
+        let mut data = Vec::new();
+        let mut words_done = 0;
+        for (start, words) in chunks {
+            data.extend(interface.read(usb_device, bank, start, words)?);
+            words_done += u32::from(words);
+            progress(TransferProgress {
+                words_done,
+                words_total: word_count,
+            });
+        }
         Ok(data)
     }
 
+    /// Read and decode the EPC bank: `StoredCRC`, PC word, EPC and any XPC words
+    ///
+    /// The EPC is read to the length the PC word gives, so the returned `StoredCRC` can be
+    /// checked with [`EpcMemory::crc_matches`].
+    ///
+    /// # Errors
+    /// Returns an error if the device is not connected or USB communication fails.
+    #[instrument(skip_all, err(level = "debug"))]
+    pub fn read_epc_memory(&self, usb_device: &impl UsbIo) -> Result<EpcMemory, RfidError> {
+        let decode = |bytes: &[u8]| {
+            EpcMemory::decode(bytes).ok_or_else(|| {
+                RfidError::InvalidResponse("EPC bank read returned less than two words".into())
+            })
+        };
+        let mut bytes = self.read(usb_device, MemoryBank::Epc, 0, EPC_ADDRESS)?;
+        let pc = decode(&bytes)?.pc;
+        if pc.epc_words > 0 {
+            bytes.extend(self.read(
+                usb_device,
+                MemoryBank::Epc,
+                EPC_ADDRESS,
+                u32::from(pc.epc_words),
+            )?);
+        }
+        if pc.xi {
+            bytes.resize(usize::try_from(XPC_W1_ADDRESS).unwrap_or_default() * 2, 0);
+            let xpc_w1 = self.read(usb_device, MemoryBank::Epc, XPC_W1_ADDRESS, 1)?;
+            let extended = xpc_w1
+                .get(..2)
+                .is_some_and(|word| XpcW1(u16::from_be_bytes([word[0], word[1]])).has(XpcW1::XEB));
+            bytes.extend(xpc_w1);
+            if extended {
+                bytes.extend(self.read(usb_device, MemoryBank::Epc, XPC_W1_ADDRESS + 1, 1)?);
+            }
+        }
+        decode(&bytes)
+    }
+
+    /// Write a new EPC together with a PC word whose length field matches it
+    ///
+    /// The UMI, XI, toggle and attribute bits of the current PC word are kept. The PC word
+    /// and EPC are written from word 1 in one go, and the tag recomputes `StoredCRC`.
+    ///
+    /// # Errors
+    /// Returns an error if the EPC is not a whole number of words or longer than 31 words,
+    /// the device is not connected, or USB communication fails.
+    #[instrument(skip_all, fields(epc = %hex::encode_upper(epc)), err(level = "debug"))]
+    pub fn write_epc(
+        &self,
+        usb_device: &impl UsbIo,
+        epc: &[u8],
+    ) -> Result<ProtocolControl, RfidError> {
+        let words = u8::try_from(epc.len() / 2)
+            .ok()
+            .filter(|words| epc.len().is_multiple_of(2) && *words <= MAX_EPC_WORDS)
+            .ok_or_else(|| {
+                RfidError::Protocol(format!(
+                    "An EPC must be a whole number of words, at most {MAX_EPC_WORDS}"
+                ))
+            })?;
+        let current = self.read(usb_device, MemoryBank::Epc, PC_ADDRESS, 1)?;
+        let current = current
+            .get(..2)
+            .map(|word| u16::from_be_bytes([word[0], word[1]]))
+            .ok_or_else(|| RfidError::InvalidResponse("PC word read returned no data".into()))?;
+        let pc = ProtocolControl::from_word(current).with_epc_words(words);
+        let mut data = pc.to_word().to_be_bytes().to_vec();
+        data.extend_from_slice(epc);
+        self.write(usb_device, MemoryBank::Epc, PC_ADDRESS, &data)?;
+        Ok(pc)
+    }
+
+    /// Read and decode the start of the TID bank
+    ///
+    /// The class identifier, MDID and TMN are read first. If the tag has an XTID, its header
+    /// and serial number are read with it.
+    ///
+    /// # Errors
+    /// Returns an error if the device is not connected, USB communication fails, or the TID is
+    /// not of the Gen2 class.
+    #[instrument(skip_all, err(level = "debug"))]
+    pub fn read_tid(&self, usb_device: &impl UsbIo) -> Result<Tid, RfidError> {
+        let decode = |bytes: &[u8]| {
+            Tid::decode(bytes).map_err(|e| RfidError::InvalidResponse(e.to_string()))
+        };
+        let mut bytes = self.read(usb_device, MemoryBank::Tid, 0, TID_HEADER_WORDS)?;
+        let tid = decode(&bytes)?;
+        if !tid.xtid {
+            return Ok(tid);
+        }
+        bytes.extend(self.read(usb_device, MemoryBank::Tid, XTID_HEADER_ADDRESS, 1)?);
+        let serial_words = decode(&bytes)?
+            .xtid_header
+            .map_or(0, |header| header.serial_words());
+        if serial_words > 0 {
+            bytes.extend(self.read(
+                usb_device,
+                MemoryBank::Tid,
+                XTID_HEADER_ADDRESS + 1,
+                serial_words,
+            )?);
+        }
+        decode(&bytes)
+    }
+
+    /// Read and decode the ISO/IEC 15962 data sets in User memory
+    ///
+    /// User memory is read [`USER_DATA_BLOCK_WORDS`] words at a time until the terminating
+    /// precursor. Near the end of the bank, the words left are read in one command.
+    ///
+    /// # Errors
+    /// Returns an error if the device is not connected, USB communication fails, or User
+    /// memory is not formatted to ISO/IEC 15962 or ends before the terminating precursor.
+    #[instrument(skip_all, err(level = "debug"))]
+    pub fn read_user_data(&self, usb_device: &impl UsbIo) -> Result<UserData, RfidError> {
+        let invalid = |e: UserDataError| RfidError::InvalidResponse(e.to_string());
+        let mut bytes = self.read(usb_device, MemoryBank::User, 0, USER_DATA_BLOCK_WORDS)?;
+        loop {
+            match UserData::decode(&bytes) {
+                Err(UserDataError::Truncated(_)) => {}
+                decoded => return decoded.map_err(invalid),
+            }
+            let address = u32::try_from(bytes.len() / 2).unwrap_or(u32::MAX);
+            match self.read(usb_device, MemoryBank::User, address, USER_DATA_BLOCK_WORDS) {
+                Ok(block) => bytes.extend(block),
+                Err(RfidError::CommandFailed {
+                    status: STATUS_MEMORY_OVERRUN,
+                    ..
+                }) => {
+                    // Fewer words than a block are left
+                    if let Ok(rest) = self.read(usb_device, MemoryBank::User, address, 0) {
+                        bytes.extend(rest);
+                    }
+                    return UserData::decode(&bytes).map_err(invalid);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Encode ISO/IEC 15962 data sets and write them to User memory from word 0
+    ///
+    /// Words after the terminating precursor are left as they are.
+    ///
+    /// # Errors
+    /// Returns an error if the data sets do not encode, the device is not connected, or USB
+    /// communication fails.
+    #[instrument(skip_all, err(level = "debug"))]
+    pub fn write_user_data(
+        &self,
+        usb_device: &impl UsbIo,
+        data: &UserData,
+    ) -> Result<(), RfidError> {
+        let bytes = data
+            .encode()
+            .map_err(|e| RfidError::Protocol(e.to_string()))?;
+        self.write(usb_device, MemoryBank::User, 0, &bytes)
+    }
+
+    /// Read User memory and decode the fields of a template
+    ///
+    /// # Errors
+    /// Returns an error if the fields are beyond User memory, the device is not connected,
+    /// or USB communication fails.
+    #[instrument(skip_all, err(level = "debug"))]
+    pub fn read_template(
+        &self,
+        usb_device: &impl UsbIo,
+        template: &Template,
+    ) -> Result<Vec<(String, FieldValue)>, RfidError> {
+        let (first_word, words) = template.span();
+        let data = self.read(usb_device, MemoryBank::User, first_word, words)?;
+        Ok(template.decode(&data, first_word))
+    }
+
+    /// Encode field values given as text and write them to User memory
+    ///
+    /// Words the fields only partly cover are read first and written back with their other
+    /// bits unchanged. Only the words the fields touch are written.
+    ///
+    /// # Errors
+    /// Returns an error if a field is unknown or a value does not fit it, the device is not
+    /// connected, or USB communication fails.
+    #[instrument(skip_all, err(level = "debug"))]
+    pub fn write_template(
+        &self,
+        usb_device: &impl UsbIo,
+        template: &Template,
+        values: &[(String, String)],
+    ) -> Result<(), RfidError> {
+        let update = template
+            .encode(values)
+            .map_err(|e| RfidError::Protocol(e.to_string()))?;
+        let mut data = if update.needs_read() {
+            self.read(
+                usb_device,
+                MemoryBank::User,
+                update.first_word,
+                update.words,
+            )?
+        } else {
+            vec![0; usize::try_from(update.words).unwrap_or_default() * 2]
+        };
+        update.apply(&mut data);
+        for (address, bytes) in update.runs() {
+            self.write(usb_device, MemoryBank::User, address, &data[bytes])?;
+        }
+        Ok(())
+    }
+
     /// Write data to a memory bank
-    /// Write data to a memory bank
+    ///
+    /// `data` is raw bytes, two per word, and is encoded into the reader's ASCII hex words.
+    /// Before the reader emulator was added, the caller had to pass the ASCII hex itself.
+    ///
+    /// Data longer than one reader command is split into several writes.
     ///
     /// # Errors
     /// Returns an error if parameters are invalid, the device is not connected, or USB communication fails.
     pub fn write(
-        usb_device: &UsbDevice,
+        &self,
+        usb_device: &impl UsbIo,
+        bank: MemoryBank,
+        address: u32,
+        data: &[u8],
+    ) -> Result<(), RfidError> {
+        self.write_with_progress(usb_device, bank, address, data, |_| {})
+    }
+
+    /// Write data to a memory bank, reporting progress after every reader command
+    ///
+    /// The whole range is validated before the first write. If a later write fails, the
+    /// words reported through `progress` so far have already been written to the tag.
+    ///
+    /// # Errors
+    /// Returns an error if parameters are invalid, the device is not connected, or USB communication fails.
+    #[instrument(skip_all, fields(?bank, address, words = data.len() / 2), err(level = "debug"))]
+    pub fn write_with_progress(
+        &self,
+        usb_device: &impl UsbIo,
         bank: MemoryBank,
         address: u32,
         data: &[u8],
+        mut progress: impl FnMut(TransferProgress),
     ) -> Result<(), RfidError> {
-        if !data.len().is_multiple_of(4) {
+        if data.is_empty() || !data.len().is_multiple_of(2) {
             return Err(RfidError::Protocol(
-                "Data length must be a multiple of 4".to_string(),
+                "Data length must be a whole number of words".to_string(),
             ));
         }
-        let interface = Self::get_interface(usb_device)?;
+        let interface = self.get_interface(usb_device)?;
         if !usb_device.is_connected() {
             return Err(RfidError::NotConnected);
         }
-        let address = u8::try_from(address)
-            .map_err(|_| RfidError::Protocol("Address out of range".to_owned()))?;
-        interface.write(usb_device, bank, address, data)?;
+        let words_total = u32::try_from(data.len() / 2)
+            .map_err(|_| RfidError::Protocol("Data too large".to_owned()))?;
+        let chunks = Self::word_chunks(address, words_total, MAX_WORDS_PER_COMMAND)?;
+        let mut words_done = 0;
+        for (start, words) in chunks {
+            let offset = usize::try_from(words_done).unwrap_or(usize::MAX) * 2;
+            let chunk = &data[offset..offset + usize::from(words) * 2];
+            interface.write(usb_device, bank, start, chunk)?;
+            words_done += u32::from(words);
+            progress(TransferProgress {
+                words_done,
+                words_total,
+            });
+        }
         Ok(())
     }
 
+    /// Split a word range into `(address, words)` pairs of at most `max_words` words each
+    fn word_chunks(
+        address: u32,
+        word_count: u32,
+        max_words: u8,
+    ) -> Result<Vec<(u8, u8)>, RfidError> {
+        let end = address
+            .checked_add(word_count)
+            .ok_or_else(|| RfidError::Protocol("Word range out of range".to_owned()))?;
+        let mut chunks = Vec::new();
+        let mut next = address;
+        while next < end {
+            let start = u8::try_from(next).map_err(|_| {
+                RfidError::Protocol(format!(
+                    "Word address {next} is beyond the reader's addressable range (0-{MAX_WORD_ADDRESS})"
+                ))
+            })?;
+            let words = u8::try_from((end - next).min(u32::from(max_words))).unwrap_or(max_words);
+            chunks.push((start, words));
+            next += u32::from(words);
+        }
+        Ok(chunks)
+    }
+
     /// Lock a memory bank with the specified action
     /// Lock a memory bank with the specified action
     ///
     /// # Errors
     /// Returns an error if the device is not connected or USB communication fails.
     pub fn lock_memory_bank(
-        usb_device: &UsbDevice,
+        &self,
+        usb_device: &impl UsbIo,
         bank: LockableMemoryBank,
         action: LockAction,
     ) -> Result<(), RfidError> {
-        Self::lock_memory_raw(
-            usb_device,
-            LockPatternBuilder::memory_bank(bank, action, true),
-        )
+        self.lock(usb_device, LockPayload::new().with_action(bank, action))
     }
 
-    /// Lock memory using a raw lock pattern string
-    /// Lock memory using a raw lock pattern string
+    /// Apply a lock payload, changing every area it covers in one command
+    /// Apply a lock payload, changing every area it covers in one command
     ///
     /// # Errors
-    /// Returns an error if the device is not connected or USB communication fails.
-    pub fn lock_memory_raw(usb_device: &UsbDevice, pattern: u16) -> Result<(), RfidError> {
-        let interface = Self::get_interface(usb_device)?;
-        let pattern_str = format!("{pattern:04X}");
-        let pattern_bytes: [u8; 6] = pattern_str
-            .as_bytes()
-            .try_into()
-            .map_err(|_| RfidError::Protocol("Invalid lock pattern".to_string()))?;
+    /// Returns an error if the payload changes nothing, the device is not connected or USB
+    /// communication fails.
+    #[instrument(skip_all, fields(%payload), err(level = "debug"))]
+    pub fn lock(&self, usb_device: &impl UsbIo, payload: LockPayload) -> Result<(), RfidError> {
+        let interface = self.get_interface(usb_device)?;
+        if payload.is_empty() {
+            return Err(RfidError::Protocol(
+                "Lock payload does not change any memory area".to_string(),
+            ));
+        }
         if !usb_device.is_connected() {
             return Err(RfidError::NotConnected);
         }
-        interface.lock_memory(usb_device, &pattern_bytes)?;
+        interface.lock_memory(usb_device, payload)?;
         Ok(())
     }
 
     /// Perform an inventory operation to find tags
     /// Perform an inventory operation to find tags
     ///
+    /// Tags answering several times in the round are merged into one result with their
+    /// read count.
+    ///
     /// # Errors
     /// Returns an error if the device is not connected or USB communication fails.
-    pub fn inventory(usb_device: &UsbDevice) -> Result<Vec<InventoryResult>, RfidError> {
-        let interface = Self::get_interface(usb_device)?;
+    #[instrument(skip_all, err(level = "debug"))]
+    pub fn inventory(&self, usb_device: &impl UsbIo) -> Result<Vec<InventoryResult>, RfidError> {
+        let interface = self.get_interface(usb_device)?;
         if !usb_device.is_connected() {
             return Err(RfidError::NotConnected);
         }
-        let epc_list = interface.get_epc_list_raw(usb_device)?;
-        let read_count = u8::try_from(epc_list.len())
-            .map_err(|_| RfidError::Protocol("Too many tags in one inventory".to_owned()))?;
-        let tags = epc_list
-            .into_iter()
-            .map(|epc| InventoryResult { epc, read_count })
-            .collect();
-        Ok(tags)
+        let reports = interface.inventory(usb_device)?;
+        Ok(InventoryResult::collect(reports))
     }
 
     /// Set the access password for secured operations
@@ -149,33 +499,252 @@ impl UhfRfidApi {
     ///
     /// # Errors
     /// Returns an error if the device is not connected or USB communication fails.
-    pub fn set_access_password(usb_device: &UsbDevice, password: u32) -> Result<(), RfidError> {
-        let interface = Self::get_interface(usb_device)?;
-        let password_bytes = password.to_be_bytes();
-        let full_password: [u8; 8] = [
-            password_bytes[0],
-            password_bytes[1],
-            password_bytes[2],
-            password_bytes[3],
-            0,
-            0,
-            0,
-            0, // Second password set to zeros
-        ];
+    #[instrument(skip_all, err(level = "debug"))]
+    pub fn set_access_password(
+        &self,
+        usb_device: &impl UsbIo,
+        password: u32,
+    ) -> Result<(), RfidError> {
+        let interface = self.get_interface(usb_device)?;
         if !usb_device.is_connected() {
             return Err(RfidError::NotConnected);
         }
-        interface.set_access_password(usb_device, &full_password)?;
+        interface.set_access_password(usb_device, password)?;
+        Ok(())
+    }
+
+    /// Store the kill password in Reserved words 0-1
+    ///
+    /// # Errors
+    /// Returns an error if the device is not connected or USB communication fails.
+    pub fn set_kill_password(
+        &self,
+        usb_device: &impl UsbIo,
+        password: u32,
+    ) -> Result<(), RfidError> {
+        self.write(usb_device, MemoryBank::Reserved, 0, &password.to_be_bytes())
+    }
+
+    /// Permanently kill the tag with the given EPC
+    ///
+    /// Before the kill is sent, the tag answering the EPC select must read back that EPC
+    /// and, if `tid` is given, a TID starting with it. An all-zero kill password is refused,
+    /// since tags ignore kills with it. Killing needs the `unverified-commands` feature.
+    ///
+    /// # Errors
+    /// Returns `RfidError::KillRefused` if a safeguard fails, or an error if the device is not
+    /// connected, the tag rejects the password, USB communication fails, or the crate was
+    /// built without the `unverified-commands` feature.
+    #[instrument(skip_all, fields(epc = %hex::encode_upper(epc)), err(level = "debug"))]
+    pub fn kill(
+        &self,
+        usb_device: &impl UsbIo,
+        epc: &[u8],
+        tid: Option<&[u8]>,
+        kill_password: u32,
+    ) -> Result<(), RfidError> {
+        if kill_password == 0 {
+            return Err(RfidError::KillRefused(
+                "the kill password is all zeros".to_string(),
+            ));
+        }
+        if epc.is_empty() || !epc.len().is_multiple_of(2) {
+            return Err(RfidError::KillRefused(
+                "the EPC must be a non-zero number of words".to_string(),
+            ));
+        }
+        if tid.is_some_and(|tid| tid.is_empty() || !tid.len().is_multiple_of(2)) {
+            return Err(RfidError::KillRefused(
+                "the TID must be a non-zero number of words".to_string(),
+            ));
+        }
+        let interface = self.get_interface(usb_device)?;
+        let words = |bytes: &[u8]| u32::try_from(bytes.len() / 2).unwrap_or(u32::MAX);
+
+        self.with_target(usb_device, Some(&TagFilter::epc(epc)), |device| {
+            let found = self.read(device, MemoryBank::Epc, 2, words(epc))?;
+            if found != epc {
+                return Err(RfidError::KillRefused(format!(
+                    "the tag answered with EPC {}",
+                    Self::hex_to_ascii(&found)
+                )));
+            }
+            if let Some(tid) = tid {
+                let found = self.read(device, MemoryBank::Tid, 0, words(tid))?;
+                if found != tid {
+                    return Err(RfidError::KillRefused(format!(
+                        "the tag has TID {}",
+                        Self::hex_to_ascii(&found)
+                    )));
+                }
+            }
+            interface.kill(device, kill_password)?;
+            Ok(())
+        })
+    }
+
+    /// Target later read, write, lock and password operations at the tags matching `filter`
+    ///
+    /// The filter stays set on the reader until replaced or cleared; see [`Self::with_target`]
+    /// for a scoped variant. Selecting needs the `unverified-commands` feature.
+    ///
+    /// # Errors
+    /// Returns an error if the filter is invalid, the device is not connected, USB
+    /// communication fails, or the crate was built without the `unverified-commands` feature.
+    #[instrument(skip_all, fields(?filter), err(level = "debug"))]
+    pub fn select(&self, usb_device: &impl UsbIo, filter: &TagFilter) -> Result<(), RfidError> {
+        let interface = self.get_interface(usb_device)?;
+        interface.select(usb_device, filter)?;
+        Ok(())
+    }
+
+    /// Clear the select filter so operations act on whichever tag answers first
+    ///
+    /// # Errors
+    /// Returns an error if the device is not connected, USB communication fails, or the crate
+    /// was built without the `unverified-commands` feature.
+    #[instrument(skip_all, err(level = "debug"))]
+    pub fn clear_select(&self, usb_device: &impl UsbIo) -> Result<(), RfidError> {
+        let interface = self.get_interface(usb_device)?;
+        interface.clear_select(usb_device)?;
         Ok(())
     }
 
+    /// Read the reader's RF and Gen2 settings; needs the `unverified-commands` feature
+    ///
+    /// # Errors
+    /// Returns an error if the device is not connected, USB communication fails, or the crate
+    /// was built without the `unverified-commands` feature.
+    #[instrument(skip_all, err(level = "debug"))]
+    pub fn get_config(&self, usb_device: &impl UsbIo) -> Result<ReaderSettings, RfidError> {
+        let interface = self.get_interface(usb_device)?;
+        Ok(interface.get_config(usb_device)?)
+    }
+
+    /// Apply RF and Gen2 settings; with `persist` the reader also keeps them across power cycles
+    ///
+    /// Returns whether the reader stored the settings. Readers without settings storage
+    /// apply them until they are switched off. Applying settings needs the
+    /// `unverified-commands` feature.
+    ///
+    /// # Errors
+    /// Returns an error if a setting is out of range, the device is not connected, USB
+    /// communication fails, or the crate was built without the `unverified-commands` feature.
+    #[instrument(skip_all, fields(?settings, persist), err(level = "debug"))]
+    pub fn set_config(
+        &self,
+        usb_device: &impl UsbIo,
+        settings: ReaderSettings,
+        persist: bool,
+    ) -> Result<bool, RfidError> {
+        let interface = self.get_interface(usb_device)?;
+        Ok(interface.set_config(usb_device, settings, persist)?)
+    }
+
+    /// Query the reader's firmware version, hardware revision and supported capabilities
+    ///
+    /// Identifying needs the `unverified-commands` feature.
+    ///
+    /// # Errors
+    /// Returns an error if the device is not connected, USB communication fails, the
+    /// firmware does not implement the identify command, or the crate was built without the
+    /// `unverified-commands` feature.
+    #[instrument(skip_all, err(level = "debug"))]
+    pub fn identify(&self, usb_device: &impl UsbIo) -> Result<ReaderIdentity, RfidError> {
+        let interface = self.get_interface(usb_device)?;
+        Ok(interface.identify(usb_device)?)
+    }
+
+    /// Send bytes entered by hand and collect the reports the reader sends back
+    ///
+    /// The reply is not decoded; see [`Interface::raw_transaction`] for the options.
+    ///
+    /// # Errors
+    /// Returns an error if the device is not connected, the bytes do not fit the chosen
+    /// framing, or USB communication fails.
+    #[instrument(skip_all, err(level = "debug"))]
+    pub fn raw_transaction(
+        &self,
+        usb_device: &impl UsbIo,
+        data: &[u8],
+        options: &RawOptions,
+    ) -> Result<RawReply, RfidError> {
+        let interface = self.get_interface(usb_device)?;
+        Ok(interface.raw_transaction(usb_device, data, options)?)
+    }
+
+    /// Run `operation` with the reader targeted at the tags matching `filter`
+    ///
+    /// Without a filter the operation runs untargeted. The filter is cleared afterwards, even
+    /// if the operation failed.
+    ///
+    /// # Errors
+    /// Returns the operation's error, or an error if selecting or clearing the filter fails.
+    pub fn with_target<D: UsbIo, T>(
+        &self,
+        usb_device: &D,
+        filter: Option<&TagFilter>,
+        operation: impl FnOnce(&D) -> Result<T, RfidError>,
+    ) -> Result<T, RfidError> {
+        let Some(filter) = filter else {
+            return operation(usb_device);
+        };
+        self.select(usb_device, filter)?;
+        let result = operation(usb_device);
+        let cleared = self.clear_select(usb_device);
+        let value = result?;
+        cleared?;
+        Ok(value)
+    }
+
+    /// Move the tag to the Secured state with its access password
+    ///
+    /// The tag stays Secured until the next inventory round, so run it right before the
+    /// operations it unlocks; see [`Self::with_access`]. Authenticating needs the
+    /// `unverified-commands` feature.
+    ///
+    /// # Errors
+    /// Returns `RfidError::CommandFailed` with the access-denied status if the password is
+    /// wrong, or an error if the device is not connected, USB communication fails, or the
+    /// crate was built without the `unverified-commands` feature.
+    #[instrument(skip_all, err(level = "debug"))]
+    pub fn authenticate(&self, usb_device: &impl UsbIo, password: u32) -> Result<(), RfidError> {
+        let interface = self.get_interface(usb_device)?;
+        interface.authenticate(usb_device, password)?;
+        Ok(())
+    }
+
+    /// Run `operation` on the tag described by `access`
+    ///
+    /// The target filter is applied as in [`Self::with_target`], and the access password, if
+    /// any, authenticates the tag before the operation runs. Banks locked with
+    /// `LockAction::SecureWriteable` can only be changed this way once the tag has a non-zero
+    /// access password.
+    ///
+    /// # Errors
+    /// Returns the operation's error, or an error if targeting or authenticating fails.
+    pub fn with_access<D: UsbIo, T>(
+        &self,
+        usb_device: &D,
+        access: &TagAccess,
+        operation: impl FnOnce(&D) -> Result<T, RfidError>,
+    ) -> Result<T, RfidError> {
+        self.with_target(usb_device, access.target.as_ref(), |device| {
+            if let Some(password) = access.password {
+                self.authenticate(device, password)?;
+            }
+            operation(device)
+        })
+    }
+
     /// Lock a password bank with the specified action
     /// Lock a password bank with the specified action
     ///
     /// # Errors
     /// Returns an error if the bank is not a password bank or USB communication fails.
     pub fn lock_password(
-        usb_device: &UsbDevice,
+        &self,
+        usb_device: &impl UsbIo,
         bank: LockableMemoryBank,
         action: PasswordLockAction,
     ) -> Result<(), RfidError> {
@@ -184,7 +753,10 @@ impl UhfRfidApi {
                 "Only Access and Kill passwords can be locked with this method".to_string(),
             ));
         }
-        Self::lock_memory_raw(usb_device, LockPatternBuilder::password(bank, action, true))
+        self.lock(
+            usb_device,
+            LockPayload::new().with_password_action(bank, action)?,
+        )
     }
 
     /// Utility function to convert ASCII hex string to bytes
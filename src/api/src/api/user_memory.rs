@@ -0,0 +1,756 @@
+//! ISO/IEC 15962 structured data in User memory.
+//!
+//! User memory formatted to ISO/IEC 15962 starts with a data storage format identifier
+//! (DSFID) byte naming the access method and the data format. With the no-directory
+//! access method, data sets follow it back to back, each made of a precursor byte (the
+//! compaction scheme and relative OID), the length of the compacted object and the object
+//! itself. A zero precursor byte ends the data.
+//!
+//! The relative OID of a data set is an arc under the root OID of the data format,
+//! `1.0.15961.<data format>`. Objects are compacted with the smallest scheme that holds
+//! them: integer or numeric for digits, 5-, 6- or 7-bit codes for upper-case and ASCII
+//! text, and octets for anything else.
+//!
+//! The packed-objects, directory and tag data profile access methods, and the data formats
+//! that carry full OIDs (1 and 2), are not supported.
+
+use std::fmt;
+use thiserror::Error;
+
+/// Precursor byte that ends the data sets
+pub const TERMINATOR: u8 = 0x00;
+
+/// Largest relative OID held in the precursor byte itself
+const INLINE_RELATIVE_OIDS: u32 = 14;
+
+/// Precursor value saying the relative OID follows in one or two bytes
+const EXTENDED_RELATIVE_OID: u8 = 0x0F;
+
+/// First relative OID that needs two extension bytes
+const TWO_BYTE_RELATIVE_OID: u32 = 15 + 0x80;
+
+/// Largest relative OID that can be encoded
+pub const MAX_RELATIVE_OID: u32 = TWO_BYTE_RELATIVE_OID + 0x3FFF;
+
+/// Largest compacted object, the most a two-byte length holds
+pub const MAX_OBJECT_BYTES: usize = 0x3FFF;
+
+/// Precursor flag saying an offset byte follows: pad bytes after the object
+const OFFSET_FLAG: u8 = 0x80;
+
+/// Data formats 1 and 2 carry full OIDs instead of a root OID
+const FIRST_ROOT_OID_FORMAT: u8 = 3;
+
+/// Largest data format the five DSFID bits hold
+const MAX_DATA_FORMAT: u8 = 0x1F;
+
+/// Errors encoding or decoding ISO/IEC 15962 User memory
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum UserDataError {
+    /// The DSFID is zero: User memory holds no ISO/IEC 15962 data
+    #[error("User memory is not formatted to ISO/IEC 15962 (DSFID 0x00)")]
+    NotFormatted,
+
+    /// The DSFID names an access method other than no-directory
+    #[error("The {0} access method is not supported")]
+    UnsupportedAccessMethod(AccessMethod),
+
+    /// The DSFID sets the extended syntax indicator
+    #[error("The extended DSFID syntax is not supported")]
+    ExtendedSyntax,
+
+    /// The data format has no root OID or does not fit the DSFID
+    #[error("Data format {0} is not supported; use {FIRST_ROOT_OID_FORMAT}-{MAX_DATA_FORMAT}")]
+    UnsupportedDataFormat(u8),
+
+    /// A precursor uses the reserved compaction code
+    #[error("Compaction code {0} is reserved")]
+    ReservedCompaction(u8),
+
+    /// A relative OID is zero or too large to encode
+    #[error("Relative OID {0} is outside 1-{MAX_RELATIVE_OID}")]
+    RelativeOidOutOfRange(u32),
+
+    /// A value does not fit a compaction scheme
+    #[error("{compaction} compaction cannot hold \"{value}\"")]
+    Unencodable {
+        /// Compaction scheme
+        compaction: Compaction,
+        /// Value as text, with any other bytes escaped
+        value: String,
+    },
+
+    /// A compacted object is longer than a two-byte length holds
+    #[error("A compacted object of {0} bytes is longer than {MAX_OBJECT_BYTES}")]
+    TooLong(usize),
+
+    /// The bytes end before the terminating precursor
+    #[error("The ISO/IEC 15962 data continues past the {0} bytes read")]
+    Truncated(usize),
+}
+
+/// How the data sets following the DSFID are organised
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessMethod {
+    /// Data sets one after the other
+    NoDirectory,
+    /// Data sets with a directory of their addresses
+    Directory,
+    /// Packed objects
+    PackedObjects,
+    /// Tag data profile
+    TagDataProfile,
+}
+
+impl fmt::Display for AccessMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::NoDirectory => "no-directory",
+            Self::Directory => "directory",
+            Self::PackedObjects => "packed objects",
+            Self::TagDataProfile => "tag data profile",
+        })
+    }
+}
+
+/// Data storage format identifier, the first byte of formatted User memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dsfid {
+    /// Organisation of the data sets
+    pub access_method: AccessMethod,
+    /// Further DSFID bytes follow
+    pub extended_syntax: bool,
+    /// Registered data format, the last arc of the root OID
+    pub data_format: u8,
+}
+
+impl Dsfid {
+    /// DSFID of data sets without a directory in the given data format
+    #[must_use]
+    pub fn no_directory(data_format: u8) -> Self {
+        Self {
+            access_method: AccessMethod::NoDirectory,
+            extended_syntax: false,
+            data_format,
+        }
+    }
+
+    /// Decode the DSFID byte
+    #[must_use]
+    pub fn from_byte(byte: u8) -> Self {
+        Self {
+            access_method: match byte >> 6 {
+                0 => AccessMethod::NoDirectory,
+                1 => AccessMethod::Directory,
+                2 => AccessMethod::PackedObjects,
+                _ => AccessMethod::TagDataProfile,
+            },
+            extended_syntax: byte & 0x20 != 0,
+            data_format: byte & MAX_DATA_FORMAT,
+        }
+    }
+
+    /// Encode the DSFID byte
+    #[must_use]
+    pub fn to_byte(self) -> u8 {
+        let access_method = match self.access_method {
+            AccessMethod::NoDirectory => 0,
+            AccessMethod::Directory => 1,
+            AccessMethod::PackedObjects => 2,
+            AccessMethod::TagDataProfile => 3,
+        };
+        (access_method << 6)
+            | (u8::from(self.extended_syntax) << 5)
+            | (self.data_format & MAX_DATA_FORMAT)
+    }
+
+    /// Root OID the relative OIDs of the data sets extend
+    #[must_use]
+    pub fn root_oid(&self) -> String {
+        format!("1.0.15961.{}", self.data_format)
+    }
+
+    /// Check that data sets in this format can be encoded and decoded
+    fn check(self) -> Result<(), UserDataError> {
+        if self.extended_syntax {
+            return Err(UserDataError::ExtendedSyntax);
+        }
+        if self.access_method != AccessMethod::NoDirectory {
+            return Err(UserDataError::UnsupportedAccessMethod(self.access_method));
+        }
+        if !(FIRST_ROOT_OID_FORMAT..=MAX_DATA_FORMAT).contains(&self.data_format) {
+            return Err(UserDataError::UnsupportedDataFormat(self.data_format));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Dsfid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "0x{:02X} ({}, data format {})",
+            self.to_byte(),
+            self.access_method,
+            self.data_format
+        )
+    }
+}
+
+/// Scheme a data set's object is compacted with
+///
+/// The 5-, 6- and 7-bit codes pad the last byte with one bits, so the all-ones code of
+/// each (`_`, `?` and DEL) is left out of its character set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compaction {
+    /// Bytes whose meaning the application defines
+    ApplicationDefined,
+    /// Decimal digits without leading zeros, as a binary number
+    Integer,
+    /// Decimal digits, as a binary number after a leading 1
+    Numeric,
+    /// Upper-case letters and `@[\]^`, five bits each
+    FiveBit,
+    /// Upper-case letters, digits, space and punctuation, six bits each
+    SixBit,
+    /// Printable ASCII, seven bits each
+    SevenBit,
+    /// Bytes as they are
+    Octet,
+}
+
+impl Compaction {
+    /// Schemes from the most to the least compact
+    const BY_SIZE: [Self; 6] = [
+        Self::Integer,
+        Self::Numeric,
+        Self::FiveBit,
+        Self::SixBit,
+        Self::SevenBit,
+        Self::Octet,
+    ];
+
+    /// Decode the compaction code of a precursor byte
+    ///
+    /// # Errors
+    /// Returns an error for the reserved code 7.
+    pub fn from_code(code: u8) -> Result<Self, UserDataError> {
+        Ok(match code {
+            0 => Self::ApplicationDefined,
+            1 => Self::Integer,
+            2 => Self::Numeric,
+            3 => Self::FiveBit,
+            4 => Self::SixBit,
+            5 => Self::SevenBit,
+            6 => Self::Octet,
+            code => return Err(UserDataError::ReservedCompaction(code)),
+        })
+    }
+
+    /// Compaction code of the precursor byte
+    #[must_use]
+    pub fn code(self) -> u8 {
+        match self {
+            Self::ApplicationDefined => 0,
+            Self::Integer => 1,
+            Self::Numeric => 2,
+            Self::FiveBit => 3,
+            Self::SixBit => 4,
+            Self::SevenBit => 5,
+            Self::Octet => 6,
+        }
+    }
+
+    /// Most compact scheme that holds the value
+    #[must_use]
+    pub fn for_value(value: &[u8]) -> Self {
+        Self::BY_SIZE
+            .into_iter()
+            .find(|compaction| compaction.holds(value))
+            .unwrap_or(Self::Octet)
+    }
+
+    /// Whether the scheme can hold the value
+    #[must_use]
+    pub fn holds(self, value: &[u8]) -> bool {
+        let digits = !value.is_empty() && value.iter().all(u8::is_ascii_digit);
+        match self {
+            Self::ApplicationDefined | Self::Octet => true,
+            Self::Integer => digits && (value[0] != b'0' || value.len() == 1),
+            Self::Numeric => digits,
+            Self::FiveBit | Self::SixBit | Self::SevenBit => {
+                let width = self.width();
+                value.iter().all(|&byte| {
+                    Self::to_code(byte, width).is_some_and(|code| code != (1 << width) - 1)
+                })
+            }
+        }
+    }
+
+    /// Compact a value
+    ///
+    /// # Errors
+    /// Returns an error if the scheme cannot hold the value.
+    pub fn compact(self, value: &[u8]) -> Result<Vec<u8>, UserDataError> {
+        if !self.holds(value) {
+            return Err(UserDataError::Unencodable {
+                compaction: self,
+                value: value.escape_ascii().to_string(),
+            });
+        }
+        Ok(match self {
+            Self::ApplicationDefined | Self::Octet => value.to_vec(),
+            Self::Integer => digits_to_binary(value),
+            Self::Numeric => {
+                let mut digits = vec![b'1'];
+                digits.extend_from_slice(value);
+                digits_to_binary(&digits)
+            }
+            Self::FiveBit | Self::SixBit | Self::SevenBit => {
+                let width = self.width();
+                pack(
+                    value.iter().filter_map(|&byte| Self::to_code(byte, width)),
+                    width,
+                )
+            }
+        })
+    }
+
+    /// Expand a compacted object back to its value
+    #[must_use]
+    pub fn expand(self, object: &[u8]) -> Vec<u8> {
+        match self {
+            Self::ApplicationDefined | Self::Octet => object.to_vec(),
+            Self::Integer => binary_to_digits(object),
+            Self::Numeric => binary_to_digits(object).split_off(1),
+            Self::FiveBit | Self::SixBit | Self::SevenBit => {
+                let width = self.width();
+                unpack(object, width)
+                    .into_iter()
+                    .map(|code| Self::from_code_point(code, width))
+                    .collect()
+            }
+        }
+    }
+
+    /// Bits per character of the character codes
+    fn width(self) -> u32 {
+        match self {
+            Self::FiveBit => 5,
+            Self::SixBit => 6,
+            _ => 7,
+        }
+    }
+
+    /// Code of an ASCII character in a character code of the given width
+    fn to_code(byte: u8, width: u32) -> Option<u8> {
+        match (width, byte) {
+            (5, 0x40..=0x5F) => Some(byte - 0x40),
+            (6, 0x20..=0x5F) => Some(byte & 0x3F),
+            (7, 0x20..=0x7E) => Some(byte),
+            _ => None,
+        }
+    }
+
+    /// ASCII character of a code in a character code of the given width
+    fn from_code_point(code: u8, width: u32) -> u8 {
+        match width {
+            5 => code | 0x40,
+            6 if code < 0x20 => code | 0x40,
+            _ => code,
+        }
+    }
+}
+
+impl fmt::Display for Compaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::ApplicationDefined => "application-defined",
+            Self::Integer => "integer",
+            Self::Numeric => "numeric",
+            Self::FiveBit => "5-bit",
+            Self::SixBit => "6-bit",
+            Self::SevenBit => "7-bit",
+            Self::Octet => "octet",
+        })
+    }
+}
+
+/// One object of User memory, named by its OID relative to the data format's root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataSet {
+    /// Last arc of the object's OID
+    pub relative_oid: u32,
+    /// Scheme the object is compacted with
+    pub compaction: Compaction,
+    /// Object value
+    pub value: Vec<u8>,
+}
+
+impl DataSet {
+    /// Data set holding the value, compacted with the most compact scheme
+    #[must_use]
+    pub fn new(relative_oid: u32, value: &[u8]) -> Self {
+        Self {
+            relative_oid,
+            compaction: Compaction::for_value(value),
+            value: value.to_vec(),
+        }
+    }
+
+    /// Value as text if it is printable ASCII, otherwise as hex
+    #[must_use]
+    pub fn value_text(&self) -> String {
+        if self.value.iter().all(|byte| (0x20..=0x7E).contains(byte)) {
+            String::from_utf8_lossy(&self.value).into_owned()
+        } else {
+            format!("0x{}", hex::encode_upper(&self.value))
+        }
+    }
+
+    /// Append the precursor, relative OID, length and compacted object
+    #[allow(clippy::cast_possible_truncation)]
+    fn encode_into(&self, bytes: &mut Vec<u8>) -> Result<(), UserDataError> {
+        let object = self.compaction.compact(&self.value)?;
+        let relative_oid = self.relative_oid;
+        let inline = match relative_oid {
+            1..=INLINE_RELATIVE_OIDS => relative_oid as u8,
+            _ if relative_oid <= MAX_RELATIVE_OID => EXTENDED_RELATIVE_OID,
+            _ => return Err(UserDataError::RelativeOidOutOfRange(relative_oid)),
+        };
+        bytes.push((self.compaction.code() << 4) | inline);
+        if relative_oid > INLINE_RELATIVE_OIDS {
+            if relative_oid < TWO_BYTE_RELATIVE_OID {
+                bytes.push((relative_oid - 15) as u8);
+            } else {
+                let extension = 0x8000 | (relative_oid - TWO_BYTE_RELATIVE_OID) as u16;
+                bytes.extend(extension.to_be_bytes());
+            }
+        }
+        match object.len() {
+            len @ 0..0x80 => bytes.push(len as u8),
+            len @ 0x80..=MAX_OBJECT_BYTES => bytes.extend((0x8000 | len as u16).to_be_bytes()),
+            len => return Err(UserDataError::TooLong(len)),
+        }
+        bytes.extend(object);
+        Ok(())
+    }
+}
+
+/// ISO/IEC 15962 data sets of User memory, with the DSFID before them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserData {
+    /// Data storage format identifier
+    pub dsfid: Dsfid,
+    /// Data sets in memory order
+    pub data_sets: Vec<DataSet>,
+}
+
+impl UserData {
+    /// Data sets without a directory in the given data format
+    #[must_use]
+    pub fn new(data_format: u8, data_sets: Vec<DataSet>) -> Self {
+        Self {
+            dsfid: Dsfid::no_directory(data_format),
+            data_sets,
+        }
+    }
+
+    /// Decode User memory read from word 0
+    ///
+    /// Bytes after the terminating precursor are ignored.
+    ///
+    /// # Errors
+    /// Returns an error if the memory is not formatted, uses an unsupported DSFID, or ends
+    /// before the terminating precursor.
+    pub fn decode(bytes: &[u8]) -> Result<Self, UserDataError> {
+        let truncated = || UserDataError::Truncated(bytes.len());
+        let (&dsfid, mut rest) = bytes.split_first().ok_or_else(truncated)?;
+        if dsfid == 0 {
+            return Err(UserDataError::NotFormatted);
+        }
+        let dsfid = Dsfid::from_byte(dsfid);
+        dsfid.check()?;
+        let mut take = |count: usize| {
+            let (taken, remaining) = rest.split_at_checked(count).ok_or_else(truncated)?;
+            rest = remaining;
+            Ok::<_, UserDataError>(taken)
+        };
+        let mut data_sets = Vec::new();
+        loop {
+            let precursor = take(1)?[0];
+            if precursor == TERMINATOR {
+                break;
+            }
+            let offset = if precursor & OFFSET_FLAG == 0 {
+                0
+            } else {
+                take(1)?[0]
+            };
+            let compaction = Compaction::from_code((precursor >> 4) & 0x07)?;
+            let relative_oid = match precursor & 0x0F {
+                EXTENDED_RELATIVE_OID => {
+                    let first = take(1)?[0];
+                    if first & 0x80 == 0 {
+                        15 + u32::from(first)
+                    } else {
+                        let second = take(1)?[0];
+                        TWO_BYTE_RELATIVE_OID
+                            + u32::from(u16::from_be_bytes([first & 0x7F, second]))
+                    }
+                }
+                0 => return Err(UserDataError::RelativeOidOutOfRange(0)),
+                inline => u32::from(inline),
+            };
+            let first = take(1)?[0];
+            let len = if first & 0x80 == 0 {
+                usize::from(first)
+            } else {
+                usize::from(u16::from_be_bytes([first & 0x7F, take(1)?[0]]))
+            };
+            let object = take(len)?;
+            take(usize::from(offset))?;
+            data_sets.push(DataSet {
+                relative_oid,
+                compaction,
+                value: compaction.expand(object),
+            });
+        }
+        Ok(Self { dsfid, data_sets })
+    }
+
+    /// Encode the DSFID, data sets and terminating precursor, padded to a whole word
+    ///
+    /// # Errors
+    /// Returns an error if the DSFID is not supported, a relative OID is out of range, or a
+    /// value does not fit its compaction scheme.
+    pub fn encode(&self) -> Result<Vec<u8>, UserDataError> {
+        self.dsfid.check()?;
+        let mut bytes = vec![self.dsfid.to_byte()];
+        for data_set in &self.data_sets {
+            data_set.encode_into(&mut bytes)?;
+        }
+        bytes.push(TERMINATOR);
+        if !bytes.len().is_multiple_of(2) {
+            bytes.push(0);
+        }
+        Ok(bytes)
+    }
+
+    /// Full OID of a data set
+    #[must_use]
+    pub fn oid(&self, data_set: &DataSet) -> String {
+        format!("{}.{}", self.dsfid.root_oid(), data_set.relative_oid)
+    }
+
+    /// Labelled lines describing the DSFID and data sets, for display
+    #[must_use]
+    pub fn fields(&self) -> Vec<(String, String)> {
+        let mut fields = vec![("DSFID".to_string(), self.dsfid.to_string())];
+        fields.extend(self.data_sets.iter().map(|data_set| {
+            (
+                self.oid(data_set),
+                format!("{} ({})", data_set.value_text(), data_set.compaction),
+            )
+        }));
+        fields
+    }
+}
+
+impl fmt::Display for UserData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (label, value)) in self.fields().iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{label}: {value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Convert decimal digits to a big-endian binary number of as few bytes as possible
+#[allow(clippy::cast_possible_truncation)]
+fn digits_to_binary(digits: &[u8]) -> Vec<u8> {
+    let mut number = vec![0u8];
+    for digit in digits {
+        let mut carry = u32::from(digit - b'0');
+        for byte in number.iter_mut().rev() {
+            let product = u32::from(*byte) * 10 + carry;
+            // The low byte, so it fits
+            *byte = product as u8;
+            carry = product >> 8;
+        }
+        if carry > 0 {
+            number.insert(0, carry as u8);
+        }
+    }
+    number
+}
+
+/// Convert a big-endian binary number to decimal digits
+#[allow(clippy::cast_possible_truncation)]
+fn binary_to_digits(number: &[u8]) -> Vec<u8> {
+    let mut number = number.to_vec();
+    let mut digits = Vec::new();
+    loop {
+        let mut remainder = 0u32;
+        for byte in &mut number {
+            let value = (remainder << 8) | u32::from(*byte);
+            // At most 25, as the remainder is below 10
+            *byte = (value / 10) as u8;
+            remainder = value % 10;
+        }
+        digits.push(b'0' + remainder as u8);
+        if number.iter().all(|&byte| byte == 0) {
+            break;
+        }
+    }
+    digits.reverse();
+    digits
+}
+
+/// Pack codes of `width` bits, most significant bit first, padding with one bits
+#[allow(clippy::cast_possible_truncation)]
+fn pack(codes: impl Iterator<Item = u8>, width: u32) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for code in codes {
+        buffer = (buffer << width) | u32::from(code);
+        bits += width;
+        while bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    if bits > 0 {
+        let pad = 8 - bits;
+        bytes.push(((buffer << pad) | ((1 << pad) - 1)) as u8);
+    }
+    bytes
+}
+
+/// Unpack codes of `width` bits, stopping at an all-ones code of padding
+#[allow(clippy::cast_possible_truncation)]
+fn unpack(bytes: &[u8], width: u32) -> Vec<u8> {
+    let mask = (1u32 << width) - 1;
+    let mut codes = Vec::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= width {
+            bits -= width;
+            let code = (buffer >> bits) & mask;
+            if code == mask {
+                return codes;
+            }
+            codes.push(code as u8);
+        }
+        buffer &= (1 << bits) - 1;
+    }
+    codes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_sets_encode_to_the_no_directory_layout() {
+        let data = UserData::new(13, vec![DataSet::new(1, b"12345"), DataSet::new(3, b"ABC")]);
+        let bytes = data.encode().unwrap();
+        assert_eq!(hex::encode_upper(&bytes), "0D110230393302088700");
+        assert_eq!(UserData::decode(&bytes).unwrap(), data);
+        assert_eq!(
+            data.to_string(),
+            "DSFID: 0x0D (no-directory, data format 13)\n\
+             1.0.15961.13.1: 12345 (integer)\n\
+             1.0.15961.13.3: ABC (5-bit)"
+        );
+    }
+
+    #[test]
+    fn values_use_the_most_compact_scheme() {
+        let cases: [(&[u8], Compaction); 7] = [
+            (b"907", Compaction::Integer),
+            (b"0", Compaction::Integer),
+            (b"00417", Compaction::Numeric),
+            (b"SITE@A", Compaction::FiveBit),
+            (b"BAY 12-B", Compaction::SixBit),
+            (b"Pump #3?", Compaction::SevenBit),
+            (&[0x00, 0xFF], Compaction::Octet),
+        ];
+        for (value, compaction) in cases {
+            assert_eq!(Compaction::for_value(value), compaction, "{value:?}");
+            let object = compaction.compact(value).unwrap();
+            assert_eq!(compaction.expand(&object), value, "{compaction}");
+        }
+        assert_eq!(Compaction::Numeric.compact(b"0042").unwrap(), [0x27, 0x3A]);
+        // 26 digits need a number wider than 64 bits
+        let digits = b"12345678901234567890123456";
+        let object = Compaction::Integer.compact(digits).unwrap();
+        assert_eq!(object.len(), 11);
+        assert_eq!(Compaction::Integer.expand(&object), digits);
+    }
+
+    #[test]
+    fn long_relative_oids_and_objects_round_trip() {
+        let text = vec![b'x'; 200];
+        let data = UserData::new(
+            31,
+            vec![
+                DataSet::new(14, b"A"),
+                DataSet::new(15, b"B"),
+                DataSet::new(142, b"C"),
+                DataSet::new(143, b"D"),
+                DataSet::new(MAX_RELATIVE_OID, &text),
+            ],
+        );
+        let bytes = data.encode().unwrap();
+        assert_eq!(UserData::decode(&bytes).unwrap(), data);
+        assert_eq!(
+            UserData::new(31, vec![DataSet::new(MAX_RELATIVE_OID + 1, b"E")]).encode(),
+            Err(UserDataError::RelativeOidOutOfRange(MAX_RELATIVE_OID + 1))
+        );
+        // An offset byte of 2 skips the pad bytes after the object
+        let padded = [0x0D, 0xE1, 0x02, 0x01, 0x41, 0x00, 0x00, 0x00];
+        let decoded = UserData::decode(&padded).unwrap();
+        assert_eq!(
+            decoded.data_sets,
+            [DataSet {
+                relative_oid: 1,
+                compaction: Compaction::Octet,
+                value: b"A".to_vec(),
+            }]
+        );
+    }
+
+    #[test]
+    fn decoding_reports_unformatted_and_unsupported_memory() {
+        assert_eq!(
+            UserData::decode(&[0x00, 0x00]),
+            Err(UserDataError::NotFormatted)
+        );
+        assert_eq!(
+            UserData::decode(&[0x89, 0x00]),
+            Err(UserDataError::UnsupportedAccessMethod(
+                AccessMethod::PackedObjects
+            ))
+        );
+        assert_eq!(
+            UserData::decode(&[0x01, 0x00]),
+            Err(UserDataError::UnsupportedDataFormat(1))
+        );
+        assert_eq!(
+            UserData::decode(&[0x0D, 0x71, 0x00]),
+            Err(UserDataError::ReservedCompaction(7))
+        );
+        assert_eq!(
+            UserData::decode(&[0x0D, 0x11, 0x02, 0x30]),
+            Err(UserDataError::Truncated(4))
+        );
+    }
+}
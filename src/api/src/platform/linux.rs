@@ -1,10 +1,13 @@
 //! Linux-specific USB access helpers
 
-use crate::rfid_device::usb_device::UsbError;
+use crate::api::error::UsbError;
 use std::fs;
 use std::path::Path;
 
 /// Check if the current user has enough permissions to access USB devices
+///
+/// # Errors
+/// Returns `UsbError::PermissionDenied` if no root, group membership, or udev rule grants access.
 pub fn check_usb_permissions() -> Result<(), UsbError> {
     // Check if the user is root
     if unsafe { libc::geteuid() } == 0 {
@@ -34,11 +37,11 @@ fn is_user_in_group(group_name: &str) -> bool {
     // Try to use the "groups" command to check group membership
     let output = Command::new("groups").output().ok();
 
-    if let Some(output) = output {
-        if output.status.success() {
-            let groups = String::from_utf8_lossy(&output.stdout);
-            return groups.contains(group_name);
-        }
+    if let Some(output) = output
+        && output.status.success()
+    {
+        let groups = String::from_utf8_lossy(&output.stdout);
+        return groups.contains(group_name);
     }
 
     false
@@ -2,9 +2,10 @@ use std::fmt;
 use std::time::Duration;
 
 use hidapi::{HidApi, HidDevice};
-use protocl::interface::{UsbIo, ENDPOINT_IN, ENDPOINT_OUT};
+use protocl::interface::{ENDPOINT_IN, ENDPOINT_OUT, Interface, UsbIo};
 use protocl::interface::{PRODUCT_ID, VENDOR_ID};
-use protocl::types::UhfError;
+use protocl::types::{ReaderIdentity, UhfError};
+use tracing::{field, info};
 
 use crate::api::error::UsbError;
 
@@ -21,6 +22,8 @@ pub struct DeviceInfo {
     pub product: String,
     /// Serial number string
     pub serial_number: String,
+    /// Firmware version and capabilities reported by the reader, if it answered the query
+    pub identity: Option<ReaderIdentity>,
 }
 
 /// High-level wrapper for interacting with the RFID USB device
@@ -38,7 +41,14 @@ impl fmt::Display for DeviceInfo {
             f,
             "Vendor ID: {}\nProduct ID: {}\nManufacturer: {}\nProduct: {}\nSerial Number: {}",
             self.vendor_id, self.product_id, self.manufacturer, self.product, self.serial_number
-        )
+        )?;
+        match &self.identity {
+            Some(identity) => write!(f, "\n{identity}"),
+            None => write!(
+                f,
+                "\nFirmware: unknown (reader did not report its identity)"
+            ),
+        }
     }
 }
 
@@ -57,13 +67,11 @@ macro_rules! hidapi_str {
 impl UsbDevice {
     /// Discover and open the RFID device.
     ///
-    /// For now, this constructor prepares a lightweight handle without
-    /// binding to a specific backend handle. The actual USB I/O is
-    /// performed by backend-specific implementations (nusb in this
-    /// project) that will be wired in later steps.
+    /// The reader is queried for its firmware version and capabilities once opened;
+    /// `info.identity` stays `None` if it does not answer.
     ///
     /// # Errors
-    /// Returns an error if the device cannot be found (placeholder behavior).
+    /// Returns an error if the device cannot be found or opened.
     pub fn new() -> Result<Self, UsbError> {
         let api = match HidApi::new() {
             Ok(api) => api,
@@ -80,13 +88,40 @@ impl UsbDevice {
             manufacturer: hidapi_str!(device.get_manufacturer_string()),
             product: hidapi_str!(device.get_product_string()),
             serial_number: hidapi_str!(device.get_serial_number_string()),
+            identity: None,
         };
 
-        Ok(Self {
+        let mut device = Self {
             info,
             device,
             connected: true,
-        })
+        };
+        // Readers with firmware that predates the identify command are still usable
+        if let Err(e) = device.refresh_identity() {
+            info!(error = %e, "reader did not report its identity");
+        }
+        let firmware = device
+            .info
+            .identity
+            .as_ref()
+            .map(|identity| identity.firmware);
+        info!(
+            product = %device.info.product,
+            serial_number = %device.info.serial_number,
+            firmware = firmware.map(field::display),
+            "connected to reader"
+        );
+        Ok(device)
+    }
+
+    /// Query the reader's firmware version and capabilities again and store them in `info`
+    ///
+    /// # Errors
+    /// Returns an error if the device is not connected, does not answer, or rejects the query,
+    /// or `UhfError::Unverified` without the `unverified-commands` feature.
+    pub fn refresh_identity(&mut self) -> Result<&ReaderIdentity, UhfError> {
+        let identity = Interface::default().identify(self)?;
+        Ok(self.info.identity.insert(identity))
     }
 
     /// Read using the bulk IN endpoint
@@ -124,7 +159,8 @@ impl UsbDevice {
         let mut buf = vec![0u8];
         buf.extend_from_slice(data);
         match self.device.write(&buf) {
-            Ok(len) => Ok(len),
+            // hidapi counts the report ID byte prepended above
+            Ok(len) => Ok(len.saturating_sub(1)),
             Err(e) => Err(UsbError::Usb(e.to_string())),
         }
     }
@@ -183,6 +219,7 @@ impl UsbDevice {
     /// # Errors
     /// Returns a USB error only if internal USB operations fail releasing interface.
     pub fn disconnect(&mut self) -> Result<(), UsbError> {
+        info!("disconnected from reader");
         self.connected = false;
         Ok(())
     }
@@ -204,12 +241,20 @@ impl UsbIo for UsbDevice {
         buf: &mut [u8],
         timeout: Duration,
     ) -> Result<usize, UhfError> {
-        self.read_bulk_impl(buf, timeout).map_err(map_usb_to_uhf)
+        // hidapi reports a timeout as a read of zero bytes
+        match self.read_bulk_impl(buf, timeout).map_err(map_usb_to_uhf)? {
+            0 => Err(UhfError::Timeout),
+            len => Ok(len),
+        }
     }
 
     fn write_bulk(&self, _endpoint: u8, data: &[u8], timeout: Duration) -> Result<usize, UhfError> {
         self.write_bulk_impl(data, timeout).map_err(map_usb_to_uhf)
     }
+
+    fn is_connected(&self) -> bool {
+        self.connected
+    }
 }
 
 impl Drop for UsbDevice {
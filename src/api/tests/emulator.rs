@@ -0,0 +1,701 @@
+//! End-to-end tests of `UhfRfidApi` against the in-memory reader emulator.
+
+use api::api::error::RfidError;
+use api::api::inventory_watch::{InventoryEvent, InventoryWatch, TagTracker, WatchConfig};
+use api::api::template::{Field, FieldKind, FieldValue, Template};
+use api::api::uhf_rfid_api::{TagAccess, TransferProgress, UhfRfidApi};
+use api::api::user_memory::{DataSet, UserData};
+use protocl::codec::{
+    Command, IDENTIFY_OPCODE, PROTOCOL_MARKER, Response, STATUS_ACCESS_DENIED,
+    STATUS_MEMORY_LOCKED, STATUS_MEMORY_OVERRUN, STATUS_NO_TAG,
+};
+use protocl::emulator::{ReaderEmulator, VirtualTag};
+use protocl::epc_bank::{MAX_EPC_WORDS, PC_ADDRESS, XPC_W1_ADDRESS, XpcW1};
+use protocl::interface::{RawOptions, RetryPolicy};
+use protocl::types::{
+    DeviceAction, InventoryTarget, LockAction, LockPayload, LockableMemoryBank, MemoryBank,
+    PasswordLockAction, ReaderFeature, Region, Session, TagFilter, UhfError,
+};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const EPC: [u8; 12] = [
+    0xE2, 0x00, 0x00, 0x17, 0x22, 0x0B, 0x01, 0x23, 0x45, 0x67, 0x89, 0xAB,
+];
+
+fn reader() -> ReaderEmulator {
+    ReaderEmulator::with_tags(vec![VirtualTag::new(&EPC)])
+}
+
+fn api() -> UhfRfidApi {
+    UhfRfidApi::default()
+}
+
+#[test]
+fn inventory_finds_tags() {
+    let reader = reader();
+    reader.add_tag(VirtualTag::new(&[0x11; 12]));
+    let tags = api().inventory(&reader).unwrap();
+    assert_eq!(tags.len(), 2);
+    assert!(tags[0].epc.contains(&UhfRfidApi::hex_to_ascii(&EPC)));
+}
+
+#[test]
+fn inventory_counts_repeated_reads_per_epc() {
+    let reader = ReaderEmulator::with_tags(vec![
+        VirtualTag::new(&EPC).with_reads_per_round(3),
+        VirtualTag::new(&[0x11; 12]),
+    ]);
+    let tags = api().inventory(&reader).unwrap();
+    assert_eq!(tags.len(), 2);
+    assert_eq!(tags[0].epc, UhfRfidApi::hex_to_ascii(&EPC));
+    assert_eq!(tags[0].read_count, 3);
+    assert_eq!(tags[0].pc, 0x3000);
+    assert_eq!(tags[0].antenna, Some(1));
+    assert_eq!(tags[1].read_count, 1);
+}
+
+#[test]
+fn read_returns_binary_words() {
+    let data = api().read(&reader(), MemoryBank::Epc, 2, 6).unwrap();
+    assert_eq!(data, EPC);
+}
+
+#[test]
+fn write_epc_updates_the_pc_length_and_crc() {
+    let reader = ReaderEmulator::with_tags(vec![
+        VirtualTag::new(&EPC).with_epc_words(MAX_EPC_WORDS.into()),
+    ]);
+    // Keep the User memory indicator across EPC changes
+    api()
+        .write(&reader, MemoryBank::Epc, PC_ADDRESS, &[0x34, 0x00])
+        .unwrap();
+
+    let longer = [0xAB; 16];
+    let pc = api().write_epc(&reader, &longer).unwrap();
+    assert_eq!(pc.to_word(), 0x4400);
+    assert_eq!(reader.tag(0).unwrap().epc_bytes(), longer);
+    let tags = api().inventory(&reader).unwrap();
+    assert_eq!(tags[0].epc, UhfRfidApi::hex_to_ascii(&longer));
+
+    let memory = api().read_epc_memory(&reader).unwrap();
+    assert_eq!(memory.epc, longer);
+    assert!(memory.pc.umi);
+    assert_eq!(memory.crc_matches(), Some(true));
+
+    api().write_epc(&reader, &EPC[..4]).unwrap();
+    let memory = api().read_epc_memory(&reader).unwrap();
+    assert_eq!(memory.epc, EPC[..4]);
+    assert_eq!(memory.crc_matches(), Some(true));
+
+    assert!(api().write_epc(&reader, &[0xAB; 3]).is_err());
+    assert!(api().write_epc(&reader, &[0xAB; 64]).is_err());
+}
+
+#[test]
+fn read_epc_memory_follows_the_xpc_words() {
+    let reader = ReaderEmulator::with_tags(vec![VirtualTag::new(&EPC).with_epc_words(33)]);
+    let xpc = XpcW1::XEB | XpcW1::U;
+    let mut data = xpc.to_be_bytes().to_vec();
+    data.extend_from_slice(&[0x00, 0x42]);
+    api()
+        .write(&reader, MemoryBank::Epc, XPC_W1_ADDRESS, &data)
+        .unwrap();
+    api()
+        .write(&reader, MemoryBank::Epc, PC_ADDRESS, &[0x32, 0x00])
+        .unwrap();
+
+    let memory = api().read_epc_memory(&reader).unwrap();
+    assert_eq!(memory.epc, EPC);
+    assert_eq!(memory.xpc_w1, Some(XpcW1(xpc)));
+    assert_eq!(memory.xpc_w2, Some(0x0042));
+    assert_eq!(memory.crc_matches(), Some(true));
+}
+
+#[test]
+fn read_tid_identifies_the_chip_and_serial() {
+    let tid = api().read_tid(&reader()).unwrap();
+    assert_eq!(tid.chip().unwrap().name, "Impinj Monza 4QT");
+    assert_eq!(tid.serial.as_deref(), Some(&EPC[6..]));
+
+    // Without an XTID only the first two words are read
+    let reader = ReaderEmulator::with_tags(vec![
+        VirtualTag::new(&EPC).with_tid(&[0xE2, 0x00, 0x34, 0x12]),
+    ]);
+    let tid = api().read_tid(&reader).unwrap();
+    assert_eq!(tid.chip().unwrap().name, "Alien Higgs-3");
+    assert_eq!(tid.serial, None);
+
+    let reader = ReaderEmulator::with_tags(vec![
+        VirtualTag::new(&EPC).with_tid(&[0xE0, 0x04, 0x01, 0x02]),
+    ]);
+    assert!(matches!(
+        api().read_tid(&reader),
+        Err(RfidError::InvalidResponse(_))
+    ));
+}
+
+#[test]
+fn user_data_round_trips_past_the_last_whole_block() {
+    // 20 words: two blocks of eight, then four words left
+    let reader = ReaderEmulator::with_tags(vec![VirtualTag::new(&EPC).with_user_words(20)]);
+    assert!(matches!(
+        api().read_user_data(&reader),
+        Err(RfidError::InvalidResponse(_))
+    ));
+
+    let data = UserData::new(
+        13,
+        vec![
+            DataSet::new(1, b"4000123456"),
+            DataSet::new(2, b"Site North-East / Bay 12"),
+            DataSet::new(20, b"R7"),
+        ],
+    );
+    api().write_user_data(&reader, &data).unwrap();
+    assert!(
+        reader.tag(0).unwrap().user[36..]
+            .iter()
+            .all(|&byte| byte == 0)
+    );
+    assert_eq!(api().read_user_data(&reader).unwrap(), data);
+}
+
+#[test]
+fn template_writes_keep_the_other_bits_of_shared_words() {
+    let template = Template {
+        fields: vec![
+            Field {
+                name: "count".to_string(),
+                word: 1,
+                bit: 0,
+                bits: 12,
+                kind: FieldKind::Uint,
+            },
+            Field {
+                name: "site".to_string(),
+                word: 3,
+                bit: 0,
+                bits: 32,
+                kind: FieldKind::Ascii,
+            },
+        ],
+    };
+    let reader = reader();
+    api()
+        .write(&reader, MemoryBank::User, 0, &[0xAA; 12])
+        .unwrap();
+    let values = [
+        ("count".to_string(), "291".to_string()),
+        ("site".to_string(), "N7".to_string()),
+    ];
+    api().write_template(&reader, &template, &values).unwrap();
+    assert_eq!(
+        &reader.tag(0).unwrap().user[..12],
+        &[
+            0xAA, 0xAA, 0x12, 0x3A, 0xAA, 0xAA, b'N', b'7', 0x00, 0x00, 0xAA, 0xAA
+        ]
+    );
+    let fields = api().read_template(&reader, &template).unwrap();
+    assert_eq!(
+        fields,
+        [
+            ("count".to_string(), FieldValue::Uint(291)),
+            ("site".to_string(), FieldValue::Ascii("N7".to_string())),
+        ]
+    );
+}
+
+#[test]
+fn write_round_trips_through_user_memory() {
+    let reader = reader();
+    api()
+        .write(&reader, MemoryBank::User, 4, &[0xDE, 0xAD, 0xBE, 0xEF])
+        .unwrap();
+    assert_eq!(
+        &reader.tag(0).unwrap().user[8..12],
+        &[0xDE, 0xAD, 0xBE, 0xEF]
+    );
+    let data = api().read(&reader, MemoryBank::User, 4, 2).unwrap();
+    assert_eq!(data, [0xDE, 0xAD, 0xBE, 0xEF]);
+}
+
+#[test]
+fn reader_status_maps_to_command_failed() {
+    let error = api().read(&reader(), MemoryBank::Tid, 8, 4).unwrap_err();
+    let RfidError::CommandFailed { status, frame } = &error else {
+        panic!("expected CommandFailed, got {error:?}");
+    };
+    assert_eq!(*status, STATUS_MEMORY_OVERRUN);
+    assert!(frame.contains(&STATUS_MEMORY_OVERRUN));
+    assert!(error.to_string().contains("Memory overrun"));
+    let error = api()
+        .read(&ReaderEmulator::new(), MemoryBank::Epc, 2, 6)
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        RfidError::CommandFailed {
+            status: STATUS_NO_TAG,
+            ..
+        }
+    ));
+    let error = RfidError::from(UhfError::from_status(0x7E, Vec::new()));
+    assert_eq!(error.to_string(), "Command failed with status 0x7E");
+}
+
+#[test]
+fn large_transfers_are_split_into_commands() {
+    let reader = ReaderEmulator::with_tags(vec![VirtualTag::new(&EPC).with_user_words(64)]);
+    let data: Vec<u8> = (0..80).collect();
+    let mut updates = Vec::new();
+    api()
+        .write_with_progress(&reader, MemoryBank::User, 2, &data, |p| updates.push(p))
+        .unwrap();
+    assert_eq!(reader.received_frames().len(), 3);
+    assert_eq!(
+        updates.last(),
+        Some(&TransferProgress {
+            words_done: 40,
+            words_total: 40
+        })
+    );
+    assert_eq!(&reader.tag(0).unwrap().user[4..84], data.as_slice());
+
+    let mut calls = 0;
+    let read = api()
+        .read_with_progress(&reader, MemoryBank::User, 2, 40, |_| calls += 1)
+        .unwrap();
+    assert_eq!(read, data);
+    assert_eq!(calls, 3);
+}
+
+#[test]
+fn full_size_commands_span_several_reports() {
+    let reader = ReaderEmulator::with_tags(vec![VirtualTag::new(&EPC).with_user_words(32)]);
+    // Fifteen words of data ending in zeros, which must not be taken for report padding
+    let mut data: Vec<u8> = (1..=30).collect();
+    data[26..].fill(0);
+    api().write(&reader, MemoryBank::User, 0, &data).unwrap();
+    let frame = reader.received_frames().pop().unwrap();
+    assert!(frame.len() > 63);
+    assert!(frame.ends_with(b"0000"));
+
+    // The reply to a fifteen-word read does not fit a single report either
+    assert_eq!(api().read(&reader, MemoryBank::User, 0, 15).unwrap(), data);
+    assert_eq!(reader.received_frames().len(), 2);
+}
+
+#[test]
+fn raw_transactions_frame_the_data_like_a_command() {
+    let reader = reader();
+    let identify = [PROTOCOL_MARKER, IDENTIFY_OPCODE];
+    let reply = api()
+        .raw_transaction(&reader, &identify, &RawOptions::default())
+        .unwrap();
+    assert_eq!(reply.reports.len(), 1);
+    let frame = reply.frame.unwrap();
+    assert!(matches!(
+        Response::decode(&frame),
+        Ok(Response::Identity(_))
+    ));
+
+    // The same frame sent as it is, length byte included
+    let unframed = RawOptions {
+        framed: false,
+        ..RawOptions::default()
+    };
+    let request = [2, PROTOCOL_MARKER, IDENTIFY_OPCODE];
+    let reply = api().raw_transaction(&reader, &request, &unframed).unwrap();
+    assert_eq!(reply.frame, Some(frame));
+    assert_eq!(reader.received_frames(), vec![identify.to_vec(); 2]);
+}
+
+#[test]
+fn raw_replies_are_collected_across_reports() {
+    let reader = ReaderEmulator::with_tags(vec![VirtualTag::new(&EPC).with_user_words(32)]);
+    let read = Command::Read {
+        bank: MemoryBank::User,
+        address: 0,
+        words: 15,
+    }
+    .encode()
+    .unwrap();
+    let reply = api()
+        .raw_transaction(&reader, &read[1..], &RawOptions::default())
+        .unwrap();
+    assert_eq!(reply.reports.len(), 2);
+    let frame = reply.frame.unwrap();
+    assert!(matches!(Response::decode(&frame), Ok(Response::Read { data }) if data.len() == 30));
+
+    let first_report = RawOptions {
+        collect_continuations: false,
+        ..RawOptions::default()
+    };
+    let reply = api()
+        .raw_transaction(&reader, &read[1..], &first_report)
+        .unwrap();
+    assert_eq!(reply.reports.len(), 1);
+    assert_eq!(reply.frame, None);
+
+    // The rest of that reply is drained before the next command
+    let reply = api()
+        .raw_transaction(&reader, &read[1..], &RawOptions::default())
+        .unwrap();
+    assert_eq!(reply.frame, Some(frame));
+}
+
+#[test]
+fn raw_data_must_fit_the_framing() {
+    let reader = reader();
+    let unframed = RawOptions {
+        framed: false,
+        ..RawOptions::default()
+    };
+    assert!(api().raw_transaction(&reader, &[0; 65], &unframed).is_err());
+    assert!(
+        api()
+            .raw_transaction(&reader, &[0; 256], &RawOptions::default())
+            .is_err()
+    );
+    assert!(reader.received_frames().is_empty());
+}
+
+#[test]
+fn ranges_beyond_addressable_memory_are_rejected() {
+    let reader = reader();
+    assert!(api().read(&reader, MemoryBank::User, 250, 20).is_err());
+    assert!(
+        api()
+            .write(&reader, MemoryBank::User, 256, &[0, 1])
+            .is_err()
+    );
+    // Nothing reaches the reader when the range is invalid
+    assert!(reader.received_frames().is_empty());
+}
+
+#[test]
+fn write_rejects_partial_words() {
+    assert!(
+        api()
+            .write(&reader(), MemoryBank::User, 0, &[0x01, 0x02, 0x03])
+            .is_err()
+    );
+}
+
+#[test]
+fn set_access_password_updates_reserved_bank() {
+    let reader = reader();
+    api().set_access_password(&reader, 0x8765_4321).unwrap();
+    assert_eq!(reader.tag(0).unwrap().access_password(), 0x8765_4321);
+}
+
+#[test]
+fn targeted_operations_only_touch_the_selected_tag() {
+    let reader = reader();
+    reader.add_tag(VirtualTag::new(&[0x11; 12]));
+    let target = TagFilter::epc(&[0x11; 12]);
+    api()
+        .with_target(&reader, Some(&target), |device| {
+            api().write(device, MemoryBank::User, 0, &[0xCA, 0xFE])
+        })
+        .unwrap();
+    assert_eq!(&reader.tag(1).unwrap().user[..2], &[0xCA, 0xFE]);
+    assert_eq!(&reader.tag(0).unwrap().user[..2], &[0, 0]);
+    assert_eq!(reader.received_frames().last().unwrap(), b"\x02AS0");
+
+    // The filter is cleared even when the operation fails
+    let absent = TagFilter::epc(&[0x22; 12]);
+    let result = api().with_target(&reader, Some(&absent), |device| {
+        api().read(device, MemoryBank::Epc, 2, 6)
+    });
+    assert!(matches!(
+        result,
+        Err(RfidError::CommandFailed {
+            status: STATUS_NO_TAG,
+            ..
+        })
+    ));
+    assert_eq!(api().read(&reader, MemoryBank::Epc, 2, 6).unwrap(), EPC);
+}
+
+#[test]
+fn access_password_unlocks_secure_writeable_banks() {
+    let tag = VirtualTag::new(&EPC)
+        .with_passwords(0, 0xCAFE_F00D)
+        .with_lock(LockableMemoryBank::User, LockAction::SecureWriteable);
+    let reader = ReaderEmulator::with_tags(vec![VirtualTag::new(&[0x11; 12]), tag]);
+    let mut access = TagAccess {
+        target: Some(TagFilter::epc(&EPC)),
+        password: None,
+    };
+    let write = |access: &TagAccess| {
+        api().with_access(&reader, access, |device| {
+            api().write(device, MemoryBank::User, 0, &[0xBE, 0xEF])
+        })
+    };
+
+    assert!(matches!(
+        write(&access),
+        Err(RfidError::CommandFailed {
+            status: STATUS_MEMORY_LOCKED,
+            ..
+        })
+    ));
+    access.password = Some(0x1234_5678);
+    assert!(matches!(
+        write(&access),
+        Err(RfidError::CommandFailed {
+            status: STATUS_ACCESS_DENIED,
+            ..
+        })
+    ));
+    access.password = Some(0xCAFE_F00D);
+    write(&access).unwrap();
+    assert_eq!(&reader.tag(1).unwrap().user[..2], &[0xBE, 0xEF]);
+}
+
+#[test]
+fn one_lock_command_changes_several_banks() {
+    let reader = reader();
+    let payload = LockPayload::new()
+        .with_action(LockableMemoryBank::Epc, LockAction::SecureWriteable)
+        .with_action(LockableMemoryBank::User, LockAction::NotWriteable)
+        .with_password_action(
+            LockableMemoryBank::AccessPassword,
+            PasswordLockAction::SecureReadWriteable,
+        )
+        .unwrap();
+    api().lock(&reader, payload).unwrap();
+    assert_eq!(reader.received_frames().len(), 1);
+
+    let tag = reader.tag(0).unwrap();
+    assert_eq!(
+        tag.lock_action(LockableMemoryBank::Epc),
+        LockAction::SecureWriteable
+    );
+    assert_eq!(
+        tag.lock_action(LockableMemoryBank::User),
+        LockAction::NotWriteable
+    );
+    assert_eq!(
+        tag.lock_action(LockableMemoryBank::AccessPassword),
+        LockAction::SecureWriteable
+    );
+    assert_eq!(
+        tag.lock_action(LockableMemoryBank::Tid),
+        LockAction::Writeable
+    );
+    assert!(api().lock(&reader, LockPayload::new()).is_err());
+    assert!(
+        api()
+            .lock_password(
+                &reader,
+                LockableMemoryBank::Epc,
+                PasswordLockAction::ReadWriteable
+            )
+            .is_err()
+    );
+}
+
+#[test]
+fn reader_settings_round_trip_and_persist() {
+    let reader = reader();
+    let mut settings = api().get_config(&reader).unwrap();
+    settings.rf.tx_power_dbm = 22;
+    settings.rf.region = Region::Etsi;
+    settings.gen2.session = Session::S2;
+    settings.gen2.target = InventoryTarget::AB;
+
+    assert!(api().set_config(&reader, settings, true).unwrap());
+    reader.restart();
+    assert_eq!(api().get_config(&reader).unwrap(), settings);
+
+    settings.rf.channel = Region::Etsi.channel_count();
+    let frames = reader.received_frames().len();
+    assert!(api().set_config(&reader, settings, true).is_err());
+    assert_eq!(reader.received_frames().len(), frames);
+}
+
+#[test]
+fn identity_reports_reader_capabilities() {
+    let reader = reader();
+    let identity = api().identify(&reader).unwrap();
+    assert!(identity.supports(ReaderFeature::Config));
+    assert!(identity.supports_region(Region::Japan));
+    assert!(identity.max_tx_power_dbm >= api().get_config(&reader).unwrap().rf.tx_power_dbm);
+
+    reader.set_identity(None);
+    assert!(api().identify(&reader).is_err());
+}
+
+#[test]
+fn kill_checks_safeguards_before_killing() {
+    let reader = ReaderEmulator::with_tags(vec![
+        VirtualTag::new(&[0x11; 12]),
+        VirtualTag::new(&EPC).with_passwords(0x0BAD_F00D, 0),
+    ]);
+    let tid = reader.tag(1).unwrap().tid;
+
+    assert!(matches!(
+        api().kill(&reader, &EPC, None, 0),
+        Err(RfidError::KillRefused(_))
+    ));
+    assert!(matches!(
+        api().kill(&reader, &EPC, Some(&[0xE2, 0x00, 0x00, 0x00]), 0x0BAD_F00D),
+        Err(RfidError::KillRefused(_))
+    ));
+    assert!(matches!(
+        api().kill(&reader, &[0x22; 12], None, 0x0BAD_F00D),
+        Err(RfidError::CommandFailed {
+            status: STATUS_NO_TAG,
+            ..
+        })
+    ));
+    assert_eq!(reader.tag_count(), 2);
+
+    api()
+        .kill(&reader, &EPC, Some(&tid[..4]), 0x0BAD_F00D)
+        .unwrap();
+    assert_eq!(reader.tag_count(), 1);
+    assert_eq!(reader.tag(0).unwrap().epc_bytes(), [0x11; 12]);
+}
+
+#[test]
+fn set_kill_password_updates_reserved_bank() {
+    let reader = reader();
+    api().set_kill_password(&reader, 0x1234_5678).unwrap();
+    assert_eq!(reader.tag(0).unwrap().kill_password(), 0x1234_5678);
+}
+
+#[test]
+fn device_action_combines_flags() {
+    let reader = reader();
+    api()
+        .device_action(&reader, &[DeviceAction::Beep, DeviceAction::GreenLed], 50)
+        .unwrap();
+    assert_eq!(reader.last_action(), Some((0x05, 50)));
+}
+
+#[test]
+fn tag_tracker_reports_arrivals_sightings_and_departures() {
+    let reader = reader();
+    let start = Instant::now();
+    let mut tracker = TagTracker::new();
+    let result = api().inventory(&reader).unwrap().remove(0);
+
+    let arrived = tracker.observe(result.clone(), start);
+    assert!(
+        matches!(arrived, Some(InventoryEvent::TagArrived(ref state)) if state.first_seen == start)
+    );
+
+    let later = start + Duration::from_secs(1);
+    let Some(InventoryEvent::TagSeen(seen)) = tracker.observe(result, later) else {
+        panic!("expected a sighting");
+    };
+    assert_eq!(seen.tag.read_count, 2);
+    assert_eq!((seen.first_seen, seen.last_seen), (start, later));
+
+    assert!(
+        tracker
+            .expire(later + Duration::from_millis(500), Duration::from_secs(1))
+            .is_empty()
+    );
+    let departed = tracker.expire(later + Duration::from_secs(1), Duration::from_secs(1));
+    assert!(matches!(
+        departed.as_slice(),
+        [InventoryEvent::TagDeparted(_)]
+    ));
+    assert!(tracker.present().is_empty());
+}
+
+#[test]
+fn tag_tracker_limits_how_often_sightings_are_reported() {
+    let reader = reader();
+    let start = Instant::now();
+    let result = api().inventory(&reader).unwrap().remove(0);
+    let at = |ms| start + Duration::from_millis(ms);
+
+    let mut tracker = TagTracker::with_seen_interval(Some(Duration::from_secs(1)));
+    assert!(tracker.observe(result.clone(), at(0)).is_some());
+    assert!(tracker.observe(result.clone(), at(500)).is_none());
+    let Some(InventoryEvent::TagSeen(seen)) = tracker.observe(result.clone(), at(1000)) else {
+        panic!("expected a sighting once the interval has passed");
+    };
+    assert_eq!(seen.tag.read_count, 3);
+    assert!(tracker.observe(result.clone(), at(1500)).is_none());
+
+    let mut tracker = TagTracker::with_seen_interval(None);
+    assert!(tracker.observe(result.clone(), at(0)).is_some());
+    assert!(tracker.observe(result, at(5000)).is_none());
+    assert_eq!(tracker.present()[0].last_seen, at(5000));
+}
+
+#[test]
+fn inventory_watch_reports_tags_leaving_the_field() {
+    let reader = Arc::new(reader());
+    let config = WatchConfig {
+        round_interval: Duration::from_millis(5),
+        absence_timeout: Duration::from_millis(50),
+        seen_interval: None,
+    };
+    let watch = InventoryWatch::start(api(), Arc::clone(&reader), config);
+    let next_event = || watch.recv_timeout(Duration::from_secs(5)).unwrap();
+
+    let InventoryEvent::TagArrived(arrived) = next_event() else {
+        panic!("expected the tag to arrive first");
+    };
+    assert_eq!(arrived.tag.epc, UhfRfidApi::hex_to_ascii(&EPC));
+
+    reader.remove_tag(0).unwrap();
+    let InventoryEvent::TagDeparted(departed) = next_event() else {
+        panic!("expected the tag to depart without sightings in between");
+    };
+    assert_eq!(departed.tag.epc, arrived.tag.epc);
+
+    let returned = watch.stop().unwrap();
+    assert!(Arc::ptr_eq(&returned, &reader));
+}
+
+#[test]
+fn stopping_a_watch_does_not_wait_out_the_round_interval() {
+    let config = WatchConfig {
+        round_interval: Duration::from_secs(30),
+        ..WatchConfig::default()
+    };
+    let watch = InventoryWatch::start(api(), reader(), config);
+    assert!(matches!(
+        watch.recv_timeout(Duration::from_secs(5)),
+        Ok(InventoryEvent::TagArrived(_))
+    ));
+    let started = Instant::now();
+    watch.stop().unwrap();
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn reads_are_retried_under_the_api_policy() {
+    let reader = reader();
+    let once = UhfRfidApi::new(RetryPolicy::no_retries());
+    reader.lose_replies(1);
+    let error = once.read(&reader, MemoryBank::Epc, 2, 6).unwrap_err();
+    assert!(matches!(error, RfidError::Timeout));
+
+    let api = UhfRfidApi::new(RetryPolicy::default().with_backoff(Duration::ZERO));
+    reader.lose_replies(1);
+    assert_eq!(api.read(&reader, MemoryBank::Epc, 2, 6).unwrap(), EPC);
+}
+
+#[test]
+fn late_replies_are_not_taken_for_the_next_answer() {
+    let reader = reader();
+    let once = UhfRfidApi::new(RetryPolicy::no_retries());
+    reader.delay_replies(1);
+    let error = once.read(&reader, MemoryBank::Epc, 2, 6).unwrap_err();
+    assert!(matches!(error, RfidError::Timeout));
+
+    // The EPC read's reply turns up during the write and must not be its answer
+    once.write(&reader, MemoryBank::User, 0, &[0xAB, 0xCD])
+        .unwrap();
+    assert_eq!(reader.tag(0).unwrap().user[..2], [0xAB, 0xCD]);
+    assert_eq!(once.read(&reader, MemoryBank::Epc, 2, 6).unwrap(), EPC);
+}
@@ -2,18 +2,20 @@
 
 use crate::cli::menu::{Menu, MenuOption};
 use api::api::error::RfidError;
+use api::api::tid::Tid;
 use api::api::uhf_rfid_api::UhfRfidApi;
 use api::rfid_device::usb_device::UsbDevice;
+use protocl::interface::{RawOptions, RetryPolicy};
 use protocl::types::{DeviceAction, MemoryBank};
 use std::io::{self, Write};
 
-pub fn run_interactive_app() -> Result<(), RfidError> {
+pub fn run_interactive_app(policy: RetryPolicy) -> Result<(), RfidError> {
     let mut menu = Menu::new();
 
     println!("UHF RFID Programmer - Interactive Mode");
     println!("Connecting to RFID device...");
 
-    // Create a new RFID device with debug mode-turn-off for cleaner output.
+    let api = UhfRfidApi::new(policy);
     let mut device = UsbDevice::new()?;
     println!("Connected to {} successfully!", device.get_info());
 
@@ -23,18 +25,18 @@ pub fn run_interactive_app() -> Result<(), RfidError> {
         let choice = menu.get_choice();
 
         match choice {
-            MenuOption::Inventory => handle_inventory(&device, &menu)?,
-            MenuOption::ReadTag => handle_read_tag(&device, &menu)?,
-            MenuOption::WriteTag => handle_write_tag(&device, &menu)?,
-            MenuOption::LockTag => handle_lock_tag(&device, &menu)?,
-            MenuOption::SetPassword => handle_set_password(&device, &menu)?,
+            MenuOption::Inventory => handle_inventory(&api, &device, &menu)?,
+            MenuOption::ReadTag => handle_read_tag(&api, &device, &menu)?,
+            MenuOption::WriteTag => handle_write_tag(&api, &device, &menu)?,
+            MenuOption::LockTag => handle_lock_tag(&api, &device, &menu)?,
+            MenuOption::SetPassword => handle_set_password(&api, &device, &menu)?,
             MenuOption::AdvancedOptions => unsafe {
                 std::ptr::write(&raw mut menu.in_advanced_menu, true);
             },
             MenuOption::ShowDeviceInfo => handle_device_info(&device, &menu),
-            MenuOption::ManualCommand => handle_manual_command(&device, &menu)?,
-            MenuOption::DeviceAction => handle_device_action(&device, &menu)?,
-            MenuOption::RunTest => handle_run_test(&device, &menu)?,
+            MenuOption::ManualCommand => handle_manual_command(&api, &device, &menu)?,
+            MenuOption::DeviceAction => handle_device_action(&api, &device, &menu)?,
+            MenuOption::RunTest => handle_run_test(&api, &device, &menu)?,
             MenuOption::BackToMain => unsafe {
                 std::ptr::write(&raw mut menu.in_advanced_menu, false);
             },
@@ -50,9 +52,9 @@ pub fn run_interactive_app() -> Result<(), RfidError> {
     Ok(())
 }
 
-fn handle_inventory(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError> {
+fn handle_inventory(api: &UhfRfidApi, device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError> {
     println!("\nPerforming inventory (scanning for tags)...");
-    match UhfRfidApi::inventory(device) {
+    match api.inventory(device) {
         Ok(tags) => {
             if tags.is_empty() {
                 println!("No tags found.");
@@ -72,10 +74,10 @@ fn handle_inventory(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError> {
     }
 }
 
-fn handle_read_tag(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError> {
+fn handle_read_tag(api: &UhfRfidApi, device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError> {
     // First, do an inventory to check if tags are in range.
     println!("\nChecking for tags in range...");
-    match UhfRfidApi::inventory(device) {
+    match api.inventory(device) {
         Err(e) => Err(e),
         Ok(tags) => {
             if tags.is_empty() {
@@ -92,7 +94,7 @@ fn handle_read_tag(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError> {
             let word_count = Menu::prompt_for_word_count();
 
             println!("\nReading {word_count} words from {bank:?} memory at address {address}...");
-            match UhfRfidApi::read(device, bank, address, word_count) {
+            match api.read(device, bank, address, word_count) {
                 Ok(data) => {
                     println!("Read successful!");
                     println!("Data (hex): {}", UhfRfidApi::hex_to_ascii(&data));
@@ -121,10 +123,10 @@ fn handle_read_tag(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError> {
     }
 }
 
-fn handle_write_tag(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError> {
+fn handle_write_tag(api: &UhfRfidApi, device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError> {
     // First, do an inventory to check if tags are in range
     println!("\nChecking for tags in range...");
-    let tags = UhfRfidApi::inventory(device)?;
+    let tags = api.inventory(device)?;
 
     if tags.is_empty() {
         println!("No tags found in range. Please place a tag near the reader.");
@@ -160,7 +162,7 @@ fn handle_write_tag(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError> {
         bank,
         address
     );
-    match UhfRfidApi::write(device, bank, address, &data) {
+    match api.write(device, bank, address, &data) {
         Ok(()) => println!("Write successful!"),
         Err(e) => println!("Write failed: {e}"),
     }
@@ -169,10 +171,10 @@ fn handle_write_tag(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError> {
     Ok(())
 }
 
-fn handle_lock_tag(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError> {
+fn handle_lock_tag(api: &UhfRfidApi, device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError> {
     // First, do an inventory to check if tags are in range
     println!("\nChecking for tags in range...");
-    match UhfRfidApi::inventory(device) {
+    match api.inventory(device) {
         Err(e) => Err(e),
         Ok(tags) => {
             if tags.is_empty() {
@@ -219,7 +221,7 @@ fn handle_lock_tag(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError> {
             let action = Menu::prompt_for_lock_action();
 
             println!("\nLocking {bank:?} memory with {action:?} action...");
-            match UhfRfidApi::lock_memory_bank(device, bank, action) {
+            match api.lock_memory_bank(device, bank, action) {
                 Ok(()) => {
                     println!("Lock operation successful!");
                     Menu::prompt_to_continue();
@@ -234,10 +236,14 @@ fn handle_lock_tag(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError> {
     }
 }
 
-fn handle_set_password(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError> {
+fn handle_set_password(
+    api: &UhfRfidApi,
+    device: &UsbDevice,
+    _menu: &Menu,
+) -> Result<(), RfidError> {
     // First, do an inventory to check if tags are in range
     println!("\nChecking for tags in range...");
-    match UhfRfidApi::inventory(device) {
+    match api.inventory(device) {
         Err(e) => Err(e),
         Ok(tags) => {
             if tags.is_empty() {
@@ -269,7 +275,7 @@ fn handle_set_password(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError
             let password = Menu::prompt_for_password();
 
             println!("\nSetting access password to {password:08X}...");
-            match UhfRfidApi::set_access_password(device, password) {
+            match api.set_access_password(device, password) {
                 Ok(()) => {
                     println!("Password set successfully!");
                     println!("⚠️  IMPORTANT: Make sure to write down this password! ⚠️");
@@ -298,15 +304,14 @@ fn handle_device_info(device: &UsbDevice, _menu: &Menu) {
     );
     println!("Is connected: {}", device.is_connected());
 
-    // Try to print device capabilities or version if available
-    println!("\nChecking device capabilities...");
-    // This would depend on your specific device's capabilities
-    // Implement if your device supports this
-
     Menu::prompt_to_continue();
 }
 
-fn handle_manual_command(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError> {
+fn handle_manual_command(
+    api: &UhfRfidApi,
+    device: &UsbDevice,
+    _menu: &Menu,
+) -> Result<(), RfidError> {
     println!("\nManual Command Mode");
     println!("------------------");
     println!("This mode allows sending raw commands to the device.");
@@ -330,23 +335,37 @@ fn handle_manual_command(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidErr
 
     // Get the command data
     let data = Menu::prompt_for_hex_data();
+    print("Add the length byte and split the data into reports? (y/n): ");
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .expect("Failed to read input");
+    let options = RawOptions {
+        framed: input.trim().eq_ignore_ascii_case("y"),
+        ..RawOptions::default()
+    };
 
     println!("\nSending command: {}", UhfRfidApi::hex_to_ascii(&data));
-    match device.write(&data) {
-        Ok(bytes_written) => {
-            println!("Sent {bytes_written} bytes. Waiting for response...");
-
-            // Read response
-            let mut buffer = [0u8; 256]; // Adjust buffer size as needed
-            match device.read(&mut buffer) {
-                Ok(bytes_read) if bytes_read > 0 => {
-                    let response = &buffer[0..bytes_read];
-                    println!("Received {bytes_read} bytes.");
-                    println!("Response (hex): {}", UhfRfidApi::hex_to_ascii(response));
+    println!("Waiting for response...");
+    match api.raw_transaction(device, &data, &options) {
+        Ok(reply) if reply.reports.is_empty() => println!("No response received (timeout)."),
+        Ok(reply) => {
+            for (index, report) in reply.reports.iter().enumerate() {
+                println!(
+                    "Report {} ({} bytes): {}",
+                    index + 1,
+                    report.len(),
+                    UhfRfidApi::hex_to_ascii(report)
+                );
+            }
+            match &reply.frame {
+                Some(frame) => {
+                    println!("Response (hex): {}", UhfRfidApi::hex_to_ascii(frame));
 
                     // Try to display as ASCII if possible
                     print!("Response (ASCII): ");
-                    for b in response {
+                    for b in frame {
                         if b.is_ascii() && !b.is_ascii_control() {
                             print!("{}", *b as char);
                         } else {
@@ -355,8 +374,7 @@ fn handle_manual_command(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidErr
                     }
                     println!();
                 }
-                Ok(_) => println!("No response received (timeout)."),
-                Err(e) => println!("Error reading response: {e}"),
+                None => println!("The reports do not form a complete reply frame."),
             }
         }
         Err(e) => println!("Error sending command: {e}"),
@@ -366,7 +384,11 @@ fn handle_manual_command(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidErr
     Ok(())
 }
 
-fn handle_device_action(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError> {
+fn handle_device_action(
+    api: &UhfRfidApi,
+    device: &UsbDevice,
+    _menu: &Menu,
+) -> Result<(), RfidError> {
     println!("\nDevice Action (LED/Beep)");
     println!("Enter actions separated by '+', options: beep, red, green, yellow");
     print("Actions: ");
@@ -406,7 +428,7 @@ fn handle_device_action(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidErro
         .expect("Failed to read input");
     let time = input.trim().parse::<u8>().unwrap_or(50);
 
-    match UhfRfidApi::device_action(device, &actions, time) {
+    match api.device_action(device, &actions, time) {
         Ok(()) => println!("Action performed successfully!"),
         Err(e) => println!("Action failed: {e}"),
     }
@@ -414,28 +436,31 @@ fn handle_device_action(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidErro
     Ok(())
 }
 
-fn handle_run_test(device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError> {
+fn handle_run_test(api: &UhfRfidApi, device: &UsbDevice, _menu: &Menu) -> Result<(), RfidError> {
     println!("\nRunning basic UHF test...");
     // EPC
     println!("\nReading EPC memory bank:");
-    let epc = UhfRfidApi::read(device, MemoryBank::Epc, 0, 8)?;
+    let epc = api.read(device, MemoryBank::Epc, 0, 8)?;
     println!("EPC: {}", UhfRfidApi::hex_to_ascii(&epc));
     // TID
     println!("\nReading TID memory bank:");
-    let tid = UhfRfidApi::read(device, MemoryBank::Tid, 0, 8)?;
+    let tid = api.read(device, MemoryBank::Tid, 0, 8)?;
     println!("TID: {}", UhfRfidApi::hex_to_ascii(&tid));
+    if let Ok(tid) = Tid::decode(&tid) {
+        println!("{tid}");
+    }
     // USER
     println!("\nReading USER memory bank:");
-    let user = UhfRfidApi::read(device, MemoryBank::User, 0, 8)?;
+    let user = api.read(device, MemoryBank::User, 0, 8)?;
     println!("USER: {}", UhfRfidApi::hex_to_ascii(&user));
     // Reserved
     println!("\nReading Reserved memory bank:");
-    let res = UhfRfidApi::read(device, MemoryBank::Reserved, 0, 8)?;
+    let res = api.read(device, MemoryBank::Reserved, 0, 8)?;
     println!("Reserved: {}", UhfRfidApi::hex_to_ascii(&res));
 
     // Action
     println!("\nPerforming beep + green LED action...");
-    UhfRfidApi::device_action(device, &[DeviceAction::Beep, DeviceAction::GreenLed], 50)?;
+    api.device_action(device, &[DeviceAction::Beep, DeviceAction::GreenLed], 50)?;
     println!("Test completed successfully.");
     Menu::prompt_to_continue();
     Ok(())
@@ -1,17 +1,59 @@
 //! Command definitions for the RFID CLI application
 
+use crate::template;
+use api::api::epc::{Epc, EpcError};
+use api::api::template::Template;
 use api::api::uhf_rfid_api::UhfRfidApi;
-use clap::{Args, Parser, Subcommand};
-use protocl::types::{LockAction, LockableMemoryBank, MemoryBank};
+use api::api::user_memory::{DataSet, UserData};
+use clap::{ArgAction, Args, Parser, Subcommand};
+use protocl::codec::MAX_SELECT_MASK_BYTES;
+use protocl::interface::RawOptions;
+use protocl::types::{
+    Gen2Settings, InventoryTarget, LockAction, LockPayload, LockableMemoryBank, MemoryBank,
+    ReaderSettings, Region, RfSettings, Session, TagFilter,
+};
+use std::path::PathBuf;
+use std::time::Duration;
+use strum::IntoStaticStr;
+
+/// Bytes given as one hexadecimal argument
+///
+/// An alias rather than `Vec<u8>`, which clap would parse as a list of separate byte values.
+pub type HexBytes = Vec<u8>;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 #[command(propagate_version = true)]
 pub struct CliArguments {
-    /// Enable debug output
+    /// Enable debug output (same as -vv)
     #[arg(short, long)]
     pub debug: bool,
 
+    /// Log more detail: -v info, -vv debug, -vvv trace with raw reports (`RUST_LOG` overrides)
+    #[arg(short, long, action = ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Also write logs to this file as JSON lines; the TUI only logs to a file
+    #[arg(long, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// Configuration file with timeouts and retries (default: ~/.config/uhf-rfid/config.toml)
+    #[arg(long, value_name = "PATH")]
+    pub config_file: Option<PathBuf>,
+
+    /// Reply timeout of every reader command in milliseconds, replacing all timeouts of the
+    /// configuration file
+    #[arg(long, value_name = "MS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub timeout_ms: Option<u64>,
+
+    /// Extra attempts when a command times out or the transfer fails
+    #[arg(long, value_name = "COUNT")]
+    pub retries: Option<u8>,
+
+    /// Pause before the first retry in milliseconds, doubled for each further retry
+    #[arg(long, value_name = "MS")]
+    pub backoff_ms: Option<u64>,
+
     /// Use command-line mode instead of TUI
     #[arg(long)]
     pub cli: bool,
@@ -20,16 +62,17 @@ pub struct CliArguments {
     pub command: Option<Commands>,
 }
 
-#[derive(Subcommand)]
+#[derive(Subcommand, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
 pub enum Commands {
     /// Scan for RFID tags in range
-    Inventory,
+    Inventory(InventoryArgs),
 
     /// Read data from a tag
     Read(ReadArgs),
 
     /// Write data to a tag
-    Write(WriteArgs),
+    Write(Box<WriteArgs>),
 
     /// Lock a memory bank on a tag
     Lock(LockArgs),
@@ -37,12 +80,21 @@ pub enum Commands {
     /// Set an access password for a tag
     Password(PasswordArgs),
 
+    /// Permanently kill a tag (irreversible)
+    Kill(KillArgs),
+
     /// Get device information
     DeviceInfo,
 
+    /// Show or change the reader's RF and Gen2 settings
+    Config(ConfigArgs),
+
     /// Send a raw command to the device (advanced)
     RawCommand(RawCommandArgs),
 
+    /// Break a command or response frame down field by field (no device needed)
+    Decode(DecodeArgs),
+
     /// Run the application in legacy interactive menu mode
     Interactive,
 
@@ -53,73 +105,493 @@ pub enum Commands {
     Test,
 }
 
+#[derive(Args)]
+pub struct InventoryArgs {
+    /// Keep scanning and report tags as they arrive and depart
+    #[arg(short, long)]
+    pub watch: bool,
+
+    /// Milliseconds between inventory rounds in watch mode
+    #[arg(long, default_value = "200")]
+    pub interval_ms: u64,
+
+    /// Milliseconds a tag may go unread before it is reported as departed
+    #[arg(long, default_value = "2000")]
+    pub absence_ms: u64,
+
+    /// Stop watching after this many seconds (default: run until interrupted)
+    #[arg(long)]
+    pub duration: Option<u64>,
+}
+
 #[derive(Args)]
 pub struct ReadArgs {
     /// Memory bank to read from (reserved, epc, tid, user)
-    #[arg(short, long, value_parser = parse_memory_bank)]
-    pub bank: MemoryBank,
+    #[arg(
+        short,
+        long,
+        value_parser = parse_memory_bank,
+        required_unless_present = "template",
+        conflicts_with = "template"
+    )]
+    pub bank: Option<MemoryBank>,
 
     /// Starting address for read operation
-    #[arg(short, long, default_value = "0")]
+    #[arg(short, long, default_value = "0", conflicts_with = "template")]
     pub address: u32,
 
     /// Number of words to read (1 word = 2 bytes)
-    #[arg(short, long, default_value = "4")]
+    #[arg(short, long, default_value = "4", conflicts_with = "template")]
     pub words: u32,
+
+    /// Template file of typed User memory fields to read and decode (TOML, or JSON ending
+    /// in .json)
+    #[arg(long)]
+    pub template: Option<PathBuf>,
+
+    /// Only read from the tag with this EPC (hexadecimal)
+    #[arg(long, value_parser = parse_epc_filter)]
+    pub epc: Option<TagFilter>,
+
+    /// Access password to authenticate with before reading (hexadecimal, 8 characters)
+    #[arg(long, value_parser = parse_password)]
+    pub password: Option<u32>,
+}
+
+impl ReadArgs {
+    /// What to read: `--words` of a bank, or the fields of a `--template`
+    ///
+    /// # Errors
+    /// Returns a message if the template file cannot be read or is not valid.
+    pub fn request(&self) -> Result<ReadRequest, String> {
+        if let Some(path) = &self.template {
+            return Ok(ReadRequest::Fields(template::load(path)?));
+        }
+        match self.bank {
+            Some(bank) => Ok(ReadRequest::Words {
+                bank,
+                address: self.address,
+                words: self.words,
+            }),
+            None => Err("Give --bank or --template".to_string()),
+        }
+    }
+}
+
+/// What a `read` command reads
+pub enum ReadRequest {
+    /// Words shown as they are
+    Words {
+        /// Memory bank
+        bank: MemoryBank,
+        /// Word address of the first word
+        address: u32,
+        /// Number of words
+        words: u32,
+    },
+    /// Fields of a template, decoded from User memory
+    Fields(Template),
 }
 
 #[derive(Args)]
 pub struct WriteArgs {
     /// Memory bank to write to (reserved, epc, tid, user)
-    #[arg(short, long, value_parser = parse_memory_bank)]
-    pub bank: MemoryBank,
+    #[arg(
+        short,
+        long,
+        value_parser = parse_memory_bank,
+        required_unless_present_any = ["new_epc", "user_data", "template"],
+        conflicts_with_all = ["new_epc", "user_data", "template"]
+    )]
+    pub bank: Option<MemoryBank>,
 
     /// Starting address for write operation
-    #[arg(short, long, default_value = "0")]
+    #[arg(short, long, default_value = "0", conflicts_with_all = ["new_epc", "user_data", "template"])]
     pub address: u32,
 
     /// Data to write (hexadecimal string, e.g., 01020304)
-    #[arg(short, long, value_parser = parse_hex_data)]
-    pub data: Vec<u8>,
+    #[arg(
+        short,
+        long,
+        value_parser = parse_hex_data,
+        required_unless_present_any = ["new_epc", "user_data", "template"],
+        conflicts_with_all = ["new_epc", "user_data", "template"]
+    )]
+    pub data: Option<HexBytes>,
+
+    #[command(flatten)]
+    pub new_epc: NewEpcArgs,
+
+    /// ISO/IEC 15962 data set to write to User memory as `RELATIVE_OID=VALUE`, e.g. 1=4000123;
+    /// repeat for several data sets
+    #[arg(
+        long,
+        value_parser = parse_data_set,
+        requires = "data_format",
+        conflicts_with = "new_epc"
+    )]
+    pub user_data: Vec<DataSet>,
+
+    /// ISO/IEC 15962 data format of the `--user-data` sets (3-31); their OIDs are
+    /// 1.0.15961.<format>.<relative OID>
+    #[arg(long, requires = "user_data")]
+    pub data_format: Option<u8>,
+
+    /// Template file of typed User memory fields to write with `--set` (TOML, or JSON ending
+    /// in .json)
+    #[arg(long, requires = "set", conflicts_with_all = ["new_epc", "user_data"])]
+    pub template: Option<PathBuf>,
+
+    /// Template field to write as NAME=VALUE, e.g. installed=2026-10-17; repeat for several
+    /// fields
+    #[arg(long, value_parser = parse_field_value, requires = "template")]
+    pub set: Vec<(String, String)>,
+
+    /// Serial number of an `--sgtin` or `--grai`
+    #[arg(long, conflicts_with_all = ["sscc", "giai", "epc_uri", "epc_hex"])]
+    pub serial: Option<u64>,
+
+    /// Digits of the GS1 company prefix at the start of the key (6-12)
+    #[arg(long, conflicts_with_all = ["epc_uri", "epc_hex"])]
+    pub company_prefix_length: Option<usize>,
+
+    /// Filter value of the encoded EPC (0-7, e.g. 1 for point-of-sale items)
+    #[arg(long, default_value = "0", conflicts_with_all = ["epc_uri", "epc_hex"])]
+    pub filter: u8,
+
+    /// Only write to the tag with this EPC (hexadecimal)
+    #[arg(long, value_parser = parse_epc_filter)]
+    pub epc: Option<TagFilter>,
+
+    /// Access password to authenticate with before writing (hexadecimal, 8 characters)
+    #[arg(long, value_parser = parse_password)]
+    pub password: Option<u32>,
+}
+
+/// New EPC to write with a PC word whose length field matches it
+#[derive(Args)]
+#[group(id = "new_epc", multiple = false)]
+pub struct NewEpcArgs {
+    /// EPC to write (hexadecimal, whole words)
+    #[arg(long = "new-epc", value_parser = parse_hex_data)]
+    pub epc_hex: Option<HexBytes>,
+
+    /// GTIN-8, -12, -13 or -14 to write as an SGTIN-96 (with --serial)
+    #[arg(long)]
+    pub sgtin: Option<String>,
+
+    /// 18-digit SSCC to write as an SSCC-96
+    #[arg(long)]
+    pub sscc: Option<String>,
+
+    /// 14-digit GRAI without its serial to write as a GRAI-96 (with --serial)
+    #[arg(long)]
+    pub grai: Option<String>,
+
+    /// All-numeric GIAI to write as a GIAI-96
+    #[arg(long)]
+    pub giai: Option<String>,
+
+    /// EPC Tag URI to write, e.g. urn:epc:tag:sgtin-96:3.0614141.812345.6789 or
+    /// urn:epc:tag:gid-96:95100000.12345.400
+    #[arg(long, value_parser = parse_tag_uri)]
+    pub epc_uri: Option<Epc>,
+}
+
+impl WriteArgs {
+    /// EPC encoding the GS1 key, if one is given
+    ///
+    /// # Errors
+    /// Returns a message if an option the key needs is missing or the key does not encode.
+    pub fn gs1_epc(&self) -> Result<Option<Epc>, String> {
+        let gs1 = &self.new_epc;
+        if let Some(epc) = &gs1.epc_uri {
+            return Ok(Some(epc.clone()));
+        }
+        let company_prefix_length = || {
+            self.company_prefix_length
+                .ok_or_else(|| "Give the --company-prefix-length of the GS1 key".to_string())
+        };
+        let serial = || {
+            self.serial
+                .ok_or_else(|| "Give the --serial number to encode".to_string())
+        };
+        let epc = if let Some(gtin) = &gs1.sgtin {
+            Epc::sgtin(gtin, company_prefix_length()?, serial()?, self.filter)
+        } else if let Some(sscc) = &gs1.sscc {
+            Epc::sscc(sscc, company_prefix_length()?, self.filter)
+        } else if let Some(grai) = &gs1.grai {
+            Epc::grai(grai, company_prefix_length()?, serial()?, self.filter)
+        } else if let Some(giai) = &gs1.giai {
+            Epc::giai(giai, company_prefix_length()?, self.filter)
+        } else {
+            return Ok(None);
+        };
+        epc.map(Some).map_err(|e| e.to_string())
+    }
+
+    /// What to write: the `--data` words, a new EPC given as hex or a GS1 key,
+    /// ISO/IEC 15962 data sets, or template fields
+    ///
+    /// # Errors
+    /// Returns a message if none is given, the GS1 key, data sets or field values do not
+    /// encode, or the template file cannot be read.
+    pub fn payload(&self) -> Result<WritePayload, String> {
+        if let Some(path) = &self.template {
+            let template = template::load(path)?;
+            template.encode(&self.set).map_err(|e| e.to_string())?;
+            return Ok(WritePayload::Fields {
+                template,
+                values: self.set.clone(),
+            });
+        }
+        if let Some(data_format) = self.data_format {
+            let user_data = UserData::new(data_format, self.user_data.clone());
+            user_data.encode().map_err(|e| e.to_string())?;
+            return Ok(WritePayload::UserData(user_data));
+        }
+        if let Some(epc) = &self.new_epc.epc_hex {
+            return Ok(WritePayload::Epc(epc.clone()));
+        }
+        if let Some(epc) = self.gs1_epc()? {
+            let data = epc.encode().map_err(|e| e.to_string())?;
+            return Ok(WritePayload::Epc(data.to_vec()));
+        }
+        match (self.bank, &self.data) {
+            (Some(bank), Some(data)) => Ok(WritePayload::Words {
+                bank,
+                address: self.address,
+                data: data.clone(),
+            }),
+            _ => Err("Give --bank and --data, a new EPC, --user-data, or --template".to_string()),
+        }
+    }
+}
+
+/// What a `write` command writes
+pub enum WritePayload {
+    /// Words written as given
+    Words {
+        /// Memory bank
+        bank: MemoryBank,
+        /// Word address of the first word
+        address: u32,
+        /// Data, a whole number of words
+        data: Vec<u8>,
+    },
+    /// A new EPC, written with a PC word whose length field matches it
+    Epc(Vec<u8>),
+    /// ISO/IEC 15962 data sets, written to User memory from word 0
+    UserData(UserData),
+    /// Template fields given as text by name, written to User memory
+    Fields {
+        /// Template naming the fields
+        template: Template,
+        /// Field names and values
+        values: Vec<(String, String)>,
+    },
 }
 
 #[derive(Args)]
 pub struct LockArgs {
-    /// Memory bank to lock (`kill_password`, `access_password`, epc, tid, user)
-    #[arg(short, long, value_parser = parse_lockable_memory_bank)]
-    pub bank: LockableMemoryBank,
+    /// Memory bank to lock (`kill_password`, `access_password`, epc, tid, user); repeat with
+    /// `--action` to lock several banks in one command
+    #[arg(short, long, required = true, value_parser = parse_lockable_memory_bank)]
+    pub bank: Vec<LockableMemoryBank>,
+
+    /// Lock action to perform (writeable, permanent, secure, locked), one per `--bank`
+    #[arg(short, long, required = true, value_parser = parse_lock_action)]
+    pub action: Vec<LockAction>,
+
+    /// Only lock the tag with this EPC (hexadecimal)
+    #[arg(long, value_parser = parse_epc_filter)]
+    pub epc: Option<TagFilter>,
 
-    /// Lock action to perform (writeable, permanent, secure, locked)
-    #[arg(short, long, value_parser = parse_lock_action)]
-    pub action: LockAction,
+    /// Access password to authenticate with before locking (hexadecimal, 8 characters)
+    #[arg(long, value_parser = parse_password)]
+    pub password: Option<u32>,
 
     /// Skip confirmation prompt (use with caution)
     #[arg(short, long)]
     pub force: bool,
 }
 
+impl LockArgs {
+    /// Combine the `--bank`/`--action` pairs into a single lock payload
+    ///
+    /// # Errors
+    /// Returns a message if the pairs do not line up or a bank is given twice.
+    pub fn payload(&self) -> Result<LockPayload, String> {
+        if self.bank.len() != self.action.len() {
+            return Err(format!(
+                "Give one --action for every --bank ({} banks, {} actions)",
+                self.bank.len(),
+                self.action.len()
+            ));
+        }
+        let mut payload = LockPayload::new();
+        for (bank, action) in self.bank.iter().zip(&self.action) {
+            if payload.action(*bank).is_some() {
+                return Err(format!("Memory bank {bank:?} is given more than once"));
+            }
+            payload = payload.with_action(*bank, *action);
+        }
+        Ok(payload)
+    }
+}
+
 #[derive(Args)]
 pub struct PasswordArgs {
     /// Password value (hexadecimal, 8 characters, e.g., 12345678)
     #[arg(short, long, value_parser = parse_password)]
     pub password: u32,
 
+    /// Only set the password on the tag with this EPC (hexadecimal)
+    #[arg(long, value_parser = parse_epc_filter)]
+    pub epc: Option<TagFilter>,
+
+    /// Set the kill password instead of the access password
+    #[arg(long)]
+    pub kill: bool,
+
     /// Skip confirmation prompt
     #[arg(short, long)]
     pub force: bool,
 }
 
+#[derive(Args)]
+pub struct KillArgs {
+    /// EPC of the tag to kill (hexadecimal)
+    #[arg(long, value_parser = parse_hex_data)]
+    pub epc: HexBytes,
+
+    /// Only kill the tag if its TID starts with these bytes (hexadecimal)
+    #[arg(long, value_parser = parse_hex_data)]
+    pub tid: Option<HexBytes>,
+
+    /// Kill password (hexadecimal, 8 characters); an all-zero password is refused
+    #[arg(short, long, value_parser = parse_password)]
+    pub password: u32,
+}
+
 #[derive(Args)]
 pub struct RawCommandArgs {
-    /// Raw command data (hexadecimal string, e.g., 01020304)
+    /// Raw command data without the length byte (hexadecimal string, e.g., 0257)
     #[arg(short, long, value_parser = parse_hex_data)]
-    pub data: Vec<u8>,
+    pub data: HexBytes,
+
+    /// Send the data as it is in a single report, without adding the length byte
+    #[arg(long)]
+    pub unframed: bool,
+
+    /// How long to wait for the reply in milliseconds (default: the command timeout)
+    #[arg(long, value_name = "MS")]
+    pub reply_timeout_ms: Option<u64>,
+
+    /// Stop after the first reply report instead of collecting continuation reports
+    #[arg(long)]
+    pub first_report: bool,
 
     /// Skip safety warning
     #[arg(short, long)]
     pub force: bool,
 }
 
+impl RawCommandArgs {
+    /// How to send the data and collect the reply
+    pub fn options(&self) -> RawOptions {
+        RawOptions {
+            framed: !self.unframed,
+            timeout: self.reply_timeout_ms.map(Duration::from_millis),
+            collect_continuations: !self.first_report,
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct DecodeArgs {
+    /// Frame to decode, length byte first (hexadecimal string, e.g., 0302910000)
+    #[arg(value_parser = parse_hex_data)]
+    pub frame: HexBytes,
+
+    /// Decode the frame as a command sent to the reader
+    #[arg(short, long, conflicts_with = "response")]
+    pub command: bool,
+
+    /// Decode the frame as a response from the reader
+    #[arg(short, long)]
+    pub response: bool,
+}
+
+#[derive(Args)]
+pub struct ConfigArgs {
+    /// Transmit power in dBm (5-30)
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(
+        i64::from(RfSettings::MIN_TX_POWER_DBM)..=i64::from(RfSettings::MAX_TX_POWER_DBM)
+    ))]
+    pub power: Option<u8>,
+
+    /// Regulatory region selecting the channel plan (fcc, etsi, china, japan)
+    #[arg(short, long, value_parser = parse_region)]
+    pub region: Option<Region>,
+
+    /// Stay on this channel of the region's channel plan instead of hopping
+    #[arg(short, long, conflicts_with = "hop")]
+    pub channel: Option<u8>,
+
+    /// Hop across every channel of the region's channel plan
+    #[arg(long)]
+    pub hop: bool,
+
+    /// Gen2 session (0-3); use 2 or 3 where several readers cover the same tags
+    #[arg(short, long, value_parser = parse_session)]
+    pub session: Option<Session>,
+
+    /// Inventoried flag to ask for (a, b, ab)
+    #[arg(short, long, value_parser = parse_inventory_target)]
+    pub target: Option<InventoryTarget>,
+
+    /// Initial Q value (0-15)
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(
+        0..=i64::from(Gen2Settings::MAX_Q)
+    ))]
+    pub q: Option<u8>,
+
+    /// Store the settings on the reader so they survive a power cycle
+    #[arg(long)]
+    pub save: bool,
+}
+
+impl ConfigArgs {
+    /// Apply the requested changes on top of `settings`, returning whether there were any
+    pub fn apply(&self, settings: &mut ReaderSettings) -> bool {
+        let before = *settings;
+        if let Some(power) = self.power {
+            settings.rf.tx_power_dbm = power;
+        }
+        if let Some(region) = self.region {
+            settings.rf.region = region;
+        }
+        if let Some(channel) = self.channel {
+            settings.rf.channel = channel;
+            settings.rf.hopping = false;
+        }
+        if self.hop {
+            settings.rf.hopping = true;
+        }
+        if let Some(session) = self.session {
+            settings.gen2.session = session;
+        }
+        if let Some(target) = self.target {
+            settings.gen2.target = target;
+        }
+        if let Some(q) = self.q {
+            settings.gen2.q = q;
+        }
+        *settings != before
+    }
+}
+
 #[derive(Args, Debug)]
 pub struct ActionArgs {
     /// Actions to perform (beep, red, green, yellow)
@@ -171,6 +643,35 @@ fn parse_lock_action(arg: &str) -> Result<LockAction, String> {
     }
 }
 
+fn parse_region(arg: &str) -> Result<Region, String> {
+    match arg.to_lowercase().as_str() {
+        "fcc" | "us" => Ok(Region::Fcc),
+        "etsi" | "eu" => Ok(Region::Etsi),
+        "china" | "cn" => Ok(Region::China),
+        "japan" | "jp" => Ok(Region::Japan),
+        _ => Err(format!(
+            "Invalid region: {arg}. Use 'fcc', 'etsi', 'china', or 'japan'"
+        )),
+    }
+}
+
+fn parse_session(arg: &str) -> Result<Session, String> {
+    arg.trim_start_matches(['s', 'S'])
+        .parse()
+        .ok()
+        .and_then(Session::from_code)
+        .ok_or_else(|| format!("Invalid session: {arg}. Use 0, 1, 2, or 3"))
+}
+
+fn parse_inventory_target(arg: &str) -> Result<InventoryTarget, String> {
+    match arg.to_lowercase().as_str() {
+        "a" => Ok(InventoryTarget::A),
+        "b" => Ok(InventoryTarget::B),
+        "ab" => Ok(InventoryTarget::AB),
+        _ => Err(format!("Invalid target: {arg}. Use 'a', 'b', or 'ab'")),
+    }
+}
+
 fn parse_hex_data(arg: &str) -> Result<Vec<u8>, String> {
     if !arg.len().is_multiple_of(2) {
         return Err("Hex data must have an even number of characters".to_string());
@@ -180,6 +681,38 @@ fn parse_hex_data(arg: &str) -> Result<Vec<u8>, String> {
         .map_err(|_| "Invalid hex data. Use only 0-9 and A-F characters".to_string())
 }
 
+fn parse_epc_filter(arg: &str) -> Result<TagFilter, String> {
+    let epc = parse_hex_data(arg)?;
+    if epc.is_empty() || epc.len() > MAX_SELECT_MASK_BYTES {
+        return Err(format!(
+            "EPC must be 1 to {MAX_SELECT_MASK_BYTES} bytes of hexadecimal"
+        ));
+    }
+    Ok(TagFilter::epc(&epc))
+}
+
+fn parse_data_set(arg: &str) -> Result<DataSet, String> {
+    let (relative_oid, value) = arg
+        .split_once('=')
+        .ok_or_else(|| "A data set must be RELATIVE_OID=VALUE, e.g. 1=4000123".to_string())?;
+    let relative_oid = relative_oid
+        .trim()
+        .parse()
+        .map_err(|_| format!("Relative OID {relative_oid:?} is not a number"))?;
+    Ok(DataSet::new(relative_oid, value.as_bytes()))
+}
+
+fn parse_field_value(arg: &str) -> Result<(String, String), String> {
+    let (name, value) = arg
+        .split_once('=')
+        .ok_or_else(|| "A field must be NAME=VALUE, e.g. installed=2026-10-17".to_string())?;
+    Ok((name.trim().to_string(), value.to_string()))
+}
+
+fn parse_tag_uri(arg: &str) -> Result<Epc, String> {
+    arg.parse().map_err(|e: EpcError| e.to_string())
+}
+
 fn parse_password(arg: &str) -> Result<u32, String> {
     if arg.len() > 8 {
         return Err("Password must be at most 8 hex characters (32 bits)".to_string());
@@ -0,0 +1,30 @@
+use crate::cli::commands::ConfigArgs;
+use api::api::error::RfidError;
+use api::api::uhf_rfid_api::UhfRfidApi;
+use api::rfid_device::usb_device::UsbDevice;
+use colorful::{Color, Colorful};
+
+pub fn handle(api: &UhfRfidApi, device: &UsbDevice, args: &ConfigArgs) -> Result<(), RfidError> {
+    let mut settings = api.get_config(device)?;
+    let changed = args.apply(&mut settings);
+
+    if changed || args.save {
+        println!("{}", "Applying reader settings...".color(Color::Cyan));
+        let persisted = api.set_config(device, settings, args.save)?;
+        println!("{}", "Settings applied.".color(Color::Green).bold());
+        if persisted {
+            println!("{}", "Settings saved on the reader.".color(Color::Green));
+        } else if args.save {
+            println!(
+                "{}",
+                "This reader cannot store settings; they apply until it is switched off."
+                    .color(Color::Yellow)
+            );
+        }
+    }
+
+    println!("{}", "Reader Settings:".color(Color::Cyan).bold());
+    println!("{}", "----------------".color(Color::Cyan));
+    println!("{settings}");
+    Ok(())
+}
@@ -0,0 +1,14 @@
+use crate::cli::commands::DecodeArgs;
+use crate::cli::handlers::utils;
+use protocl::dissect;
+
+pub fn handle(args: &DecodeArgs) {
+    let dissection = if args.command {
+        dissect::dissect_command(&args.frame)
+    } else if args.response {
+        dissect::dissect_response(&args.frame)
+    } else {
+        dissect::dissect(&args.frame)
+    };
+    utils::print_dissection(&dissection);
+}
@@ -5,7 +5,7 @@ use api::rfid_device::usb_device::UsbDevice;
 use colorful::{Color, Colorful};
 use protocl::types::DeviceAction;
 
-pub fn handle(device: &UsbDevice, args: &ActionArgs) -> Result<(), RfidError> {
+pub fn handle(api: &UhfRfidApi, device: &UsbDevice, args: &ActionArgs) -> Result<(), RfidError> {
     println!(
         "{}",
         format!(
@@ -33,7 +33,7 @@ pub fn handle(device: &UsbDevice, args: &ActionArgs) -> Result<(), RfidError> {
         return Ok(());
     }
     // Perform the actions
-    match UhfRfidApi::device_action(device, &actions, args.time) {
+    match api.device_action(device, &actions, args.time) {
         Ok(()) => {
             println!("{}", "Actions performed successfully!".color(Color::Green));
             Ok(())
@@ -1,5 +1,6 @@
 use api::rfid_device::usb_device::UsbDevice;
 use colorful::{Color, Colorful};
+use protocl::types::ReaderFeature;
 
 pub fn handle(device: &UsbDevice) {
     println!("{}", "Device Information:".color(Color::Cyan).bold());
@@ -51,8 +52,44 @@ pub fn handle(device: &UsbDevice) {
             "No".color(Color::Red).bold()
         }
     );
-    // Try to print device capabilities or version if available
-    println!("{}", "\nChecking device capabilities...".color(Color::Cyan));
-    // This would depend on your specific device's capabilities
-    // Implement if your device supports this
+
+    println!("{}", "\nReader Capabilities:".color(Color::Cyan).bold());
+    println!("{}", "--------------------".color(Color::Cyan));
+    let Some(identity) = &info.identity else {
+        println!(
+            "{}",
+            "The reader did not report its firmware version or capabilities.".color(Color::Yellow)
+        );
+        return;
+    };
+    println!(
+        "{} {} (hardware revision {})",
+        "Firmware:".color(Color::Green),
+        identity.firmware.to_string().bold(),
+        identity.hardware_revision
+    );
+    let regions: Vec<String> = identity.regions.iter().map(ToString::to_string).collect();
+    println!(
+        "{} {}",
+        "Regions:".color(Color::Green),
+        if regions.is_empty() {
+            "none".to_string()
+        } else {
+            regions.join(", ")
+        }
+    );
+    println!(
+        "{} {} dBm",
+        "Max transmit power:".color(Color::Green),
+        identity.max_tx_power_dbm
+    );
+    println!("{}", "Commands:".color(Color::Green));
+    for feature in ReaderFeature::ALL {
+        let mark = if identity.supports(feature) {
+            "✓".color(Color::Green)
+        } else {
+            "✗".color(Color::Red)
+        };
+        println!("  {mark} {feature}");
+    }
 }
@@ -1,15 +1,20 @@
+use crate::cli::commands::InventoryArgs;
+use crate::cli::handlers::utils;
 use api::api::error::RfidError;
+use api::api::inventory_watch::{InventoryEvent, InventoryWatch, WatchConfig};
 use api::api::uhf_rfid_api::UhfRfidApi;
 use api::rfid_device::usb_device::UsbDevice;
 use colorful::{Color, Colorful};
 use std::println;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, Instant};
 
-pub fn handle(device: &UsbDevice) -> Result<(), RfidError> {
+pub fn handle(api: &UhfRfidApi, device: &UsbDevice) -> Result<(), RfidError> {
     println!(
         "{}",
         "Performing inventory (scanning for tags)...".color(Color::Cyan)
     );
-    match UhfRfidApi::inventory(device) {
+    match api.inventory(device) {
         Ok(tags) => {
             if tags.is_empty() {
                 println!("{}", "No tags found.".color(Color::Yellow));
@@ -22,7 +27,9 @@ pub fn handle(device: &UsbDevice) -> Result<(), RfidError> {
                 );
 
                 for (i, tag) in tags.iter().enumerate() {
-                    println!("  {}: {}", (i + 1).to_string().color(Color::Blue), tag);
+                    let number = (i + 1).to_string();
+                    println!("  {}: {}", number.clone().color(Color::Blue), tag);
+                    utils::print_epc_uri(number.len() + 4, &tag.epc);
                 }
             }
             Ok(())
@@ -33,3 +40,69 @@ pub fn handle(device: &UsbDevice) -> Result<(), RfidError> {
         }
     }
 }
+
+pub fn watch(api: &UhfRfidApi, device: UsbDevice, args: &InventoryArgs) -> Result<(), RfidError> {
+    let config = WatchConfig {
+        round_interval: Duration::from_millis(args.interval_ms),
+        absence_timeout: Duration::from_millis(args.absence_ms),
+        seen_interval: None,
+    };
+    println!(
+        "{}",
+        "Watching for tags (press Ctrl+C to stop)...".color(Color::Cyan)
+    );
+
+    let start = Instant::now();
+    let deadline = args.duration.map(|secs| start + Duration::from_secs(secs));
+    let watch = InventoryWatch::start(*api, device, config);
+    // Ctrl+C ends the loop below once the round in progress finishes, so the watch
+    // still stops cleanly and reports what was present
+    let cancel = watch.cancel_handle();
+    if let Err(e) = ctrlc::set_handler(move || cancel.cancel()) {
+        watch.stop()?;
+        return Err(RfidError::Protocol(format!(
+            "Failed to install the Ctrl+C handler: {e}"
+        )));
+    }
+    let mut present = 0usize;
+
+    while deadline.is_none_or(|deadline| Instant::now() < deadline) {
+        let event = match watch.recv_timeout(Duration::from_millis(100)) {
+            Ok(event) => event,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        let elapsed = format!("[{:>8.1}s]", start.elapsed().as_secs_f64());
+        match event {
+            InventoryEvent::TagArrived(state) => {
+                present += 1;
+                println!("{} {} {}", elapsed, "+ arrived ".color(Color::Green), state);
+                utils::print_epc_uri(elapsed.len() + 12, &state.tag.epc);
+            }
+            InventoryEvent::TagDeparted(state) => {
+                present = present.saturating_sub(1);
+                let stayed = state.last_seen.duration_since(state.first_seen);
+                println!(
+                    "{} {} {} (present {:.1}s)",
+                    elapsed,
+                    "- departed".color(Color::Yellow),
+                    state,
+                    stayed.as_secs_f64()
+                );
+            }
+            InventoryEvent::TagSeen(_) => {}
+            InventoryEvent::Error(e) => {
+                println!("{} {} {}", elapsed, "! error   ".color(Color::Red), e);
+            }
+        }
+    }
+
+    watch.stop()?;
+    println!(
+        "{} {} {}",
+        "Stopped watching with".color(Color::Cyan),
+        present.to_string().color(Color::White).bold(),
+        "tags present.".color(Color::Cyan)
+    );
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use crate::cli::commands::KillArgs;
+use api::api::error::RfidError;
+use api::api::uhf_rfid_api::UhfRfidApi;
+use api::rfid_device::usb_device::UsbDevice;
+use colorful::{Color, Colorful};
+use std::io;
+use std::io::Write;
+
+pub fn handle(api: &UhfRfidApi, device: &UsbDevice, args: &KillArgs) -> Result<(), RfidError> {
+    let epc = UhfRfidApi::hex_to_ascii(&args.epc);
+
+    // The tag must be in range before we even ask for confirmation
+    println!("{}", "Checking for tags in range...".color(Color::Cyan));
+    let tags = api.inventory(device)?;
+    if !tags.iter().any(|tag| tag.epc == epc) {
+        println!(
+            "{}",
+            format!("Tag {epc} is not in range. Nothing was killed.").color(Color::Yellow)
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        "⚠️  WARNING: Killing a tag is PERMANENT! The tag will never respond again. ⚠️"
+            .color(Color::Red3a)
+            .bold()
+    );
+    println!(
+        "{} {}",
+        "Tag to kill:".color(Color::Cyan),
+        epc.clone().color(Color::White).bold()
+    );
+    if let Some(tid) = &args.tid {
+        println!(
+            "{} {}",
+            "Required TID prefix:".color(Color::Cyan),
+            UhfRfidApi::hex_to_ascii(tid).color(Color::White).bold()
+        );
+    }
+    print!("{}", "Type the EPC to confirm: ".color(Color::Yellow));
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    if !input.trim().eq_ignore_ascii_case(&epc) {
+        println!(
+            "{}",
+            "Confirmation did not match. Operation cancelled.".color(Color::Yellow)
+        );
+        return Ok(());
+    }
+
+    println!("{}", "Killing tag...".color(Color::Cyan));
+    api.kill(device, &args.epc, args.tid.as_deref(), args.password)?;
+    println!("{}", "Tag killed.".color(Color::Green).bold());
+    Ok(())
+}
@@ -1,15 +1,22 @@
 use crate::cli::commands::LockArgs;
+use crate::cli::handlers::utils;
 use api::api::error::RfidError;
-use api::api::uhf_rfid_api::UhfRfidApi;
+use api::api::uhf_rfid_api::{TagAccess, UhfRfidApi};
 use api::rfid_device::usb_device::UsbDevice;
 use colorful::{Color, Colorful};
 use std::io;
 use std::io::Write;
 
-pub fn handle(device: &UsbDevice, args: &LockArgs) -> Result<(), RfidError> {
+pub fn handle(api: &UhfRfidApi, device: &UsbDevice, args: &LockArgs) -> Result<(), RfidError> {
+    let payload = args.payload().map_err(RfidError::InvalidArgument)?;
+    let access = TagAccess {
+        target: args.epc.clone(),
+        password: args.password,
+    };
+
     // First, do an inventory to check if tags are in range
     println!("{}", "Checking for tags in range...".color(Color::Cyan));
-    match UhfRfidApi::inventory(device) {
+    match api.inventory(device) {
         Err(e) => Err(e),
         Ok(tags) => {
             if tags.is_empty() {
@@ -26,7 +33,9 @@ pub fn handle(device: &UsbDevice, args: &LockArgs) -> Result<(), RfidError> {
                 tags.len().to_string().color(Color::Green).bold(),
                 "tags in range.".color(Color::Green)
             );
-            if tags.len() > 1 && !args.force {
+            if let Some(target) = &args.epc {
+                utils::print_target(target, &tags);
+            } else if tags.len() > 1 && !args.force {
                 println!(
                     "{}",
                     "Warning: Multiple tags detected. Lock may affect all tags in range."
@@ -71,14 +80,12 @@ pub fn handle(device: &UsbDevice, args: &LockArgs) -> Result<(), RfidError> {
                 }
             }
             println!(
-                "{} {} {} {} {}",
+                "{} {} {}",
                 "Locking".color(Color::Cyan),
-                format!("{:?}", args.bank).color(Color::White).bold(),
-                "memory with".color(Color::Cyan),
-                format!("{:?}", args.action).color(Color::White).bold(),
-                "action...".color(Color::Cyan)
+                payload.to_string().color(Color::White).bold(),
+                format!("(payload {:06X})...", payload.bits()).color(Color::Cyan)
             );
-            UhfRfidApi::lock_memory_bank(device, args.bank, args.action)?;
+            api.with_access(device, &access, |device| api.lock(device, payload))?;
             println!(
                 "{}",
                 "Lock operation successful!".color(Color::Green).bold()
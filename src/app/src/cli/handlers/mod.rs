@@ -1,10 +1,13 @@
+pub(crate) mod config;
+pub(crate) mod decode;
 pub(crate) mod device_action;
 pub(crate) mod device_info;
 pub(crate) mod inventory;
+pub(crate) mod kill;
 pub(crate) mod lock;
 pub(crate) mod password;
 pub(crate) mod raw_command;
 pub(crate) mod read;
 pub(crate) mod test;
-mod utils;
+pub(crate) mod utils;
 pub(crate) mod write;
@@ -1,4 +1,5 @@
 use crate::cli::commands::PasswordArgs;
+use crate::cli::handlers::utils;
 use api::api::error::RfidError;
 use api::api::uhf_rfid_api::UhfRfidApi;
 use api::rfid_device::usb_device::UsbDevice;
@@ -6,10 +7,10 @@ use colorful::{Color, Colorful};
 use std::io;
 use std::io::Write;
 
-pub fn handle(device: &UsbDevice, args: &PasswordArgs) -> Result<(), RfidError> {
+pub fn handle(api: &UhfRfidApi, device: &UsbDevice, args: &PasswordArgs) -> Result<(), RfidError> {
     // First, do an inventory to check if tags are in range
     println!("{}", "Checking for tags in range...".color(Color::Cyan));
-    match UhfRfidApi::inventory(device) {
+    match api.inventory(device) {
         Err(e) => Err(e),
         Ok(tags) => {
             if tags.is_empty() {
@@ -26,7 +27,9 @@ pub fn handle(device: &UsbDevice, args: &PasswordArgs) -> Result<(), RfidError>
                 tags.len().to_string().color(Color::Green).bold(),
                 "tags in range.".color(Color::Green)
             );
-            if tags.len() > 1 && !args.force {
+            if let Some(target) = &args.epc {
+                utils::print_target(target, &tags);
+            } else if tags.len() > 1 && !args.force {
                 println!(
                     "{}",
                     "Warning: Multiple tags detected. Password will be set on all tags in range."
@@ -45,13 +48,20 @@ pub fn handle(device: &UsbDevice, args: &PasswordArgs) -> Result<(), RfidError>
                     return Ok(());
                 }
             }
+            let kind = if args.kill { "kill" } else { "access" };
             println!(
                 "{} {:08X}{}",
-                "Setting access password to ".color(Color::Cyan),
+                format!("Setting {kind} password to ").color(Color::Cyan),
                 args.password,
                 "...".color(Color::Cyan)
             );
-            if let Err(e) = UhfRfidApi::set_access_password(device, args.password) {
+            if let Err(e) = api.with_target(device, args.epc.as_ref(), |device| {
+                if args.kill {
+                    api.set_kill_password(device, args.password)
+                } else {
+                    api.set_access_password(device, args.password)
+                }
+            }) {
                 Err(e)
             } else {
                 println!(
@@ -4,10 +4,15 @@ use api::api::error::RfidError;
 use api::api::uhf_rfid_api::UhfRfidApi;
 use api::rfid_device::usb_device::UsbDevice;
 use colorful::{Color, Colorful};
+use protocl::dissect;
 use std::io;
 use std::io::Write;
 
-pub fn handle(device: &UsbDevice, args: &RawCommandArgs) -> Result<(), RfidError> {
+pub fn handle(
+    api: &UhfRfidApi,
+    device: &UsbDevice,
+    args: &RawCommandArgs,
+) -> Result<(), RfidError> {
     if !args.force {
         println!("{}", "\nManual Command Mode".color(Color::Cyan).bold());
         println!("{}", "------------------".color(Color::Cyan));
@@ -42,37 +47,37 @@ pub fn handle(device: &UsbDevice, args: &RawCommandArgs) -> Result<(), RfidError
         "Sending command:".color(Color::Cyan),
         UhfRfidApi::hex_to_ascii(&args.data)
     );
-    let bytes_written = device.write(&args.data)?;
+    println!("{}", "Waiting for response...".color(Color::Green));
+    let reply = api.raw_transaction(device, &args.data, &args.options())?;
+    if reply.reports.is_empty() {
+        println!("{}", "No response received (timeout).".color(Color::Yellow));
+        return Ok(());
+    }
+    for (index, report) in reply.reports.iter().enumerate() {
+        println!(
+            "{} {} ({} bytes): {}",
+            "Report".color(Color::Green),
+            index + 1,
+            report.len(),
+            UhfRfidApi::hex_to_ascii(report)
+        );
+    }
+    let Some(frame) = &reply.frame else {
+        println!(
+            "{}",
+            "The reports do not form a complete reply frame.".color(Color::Yellow)
+        );
+        println!();
+        utils::print_dissection(&dissect::dissect_response(&reply.reports[0]));
+        return Ok(());
+    };
     println!(
-        "{} {}. {}",
-        "Sent".color(Color::Green),
-        bytes_written.to_string().color(Color::Green).bold(),
-        "bytes. Waiting for response...".color(Color::Green)
+        "{} {}",
+        "Response (hex):".color(Color::Cyan),
+        UhfRfidApi::hex_to_ascii(frame)
     );
-    // Read response
-    let mut buffer = [0u8; 256]; // Adjust buffer size as needed
-    match device.read(&mut buffer) {
-        Ok(bytes_read) if bytes_read > 0 => {
-            let response = &buffer[0..bytes_read];
-            println!(
-                "{} {}.",
-                "Received".color(Color::Green),
-                bytes_read.to_string().color(Color::Green).bold()
-            );
-            println!(
-                "{} {}",
-                "Response (hex):".color(Color::Cyan),
-                UhfRfidApi::hex_to_ascii(response)
-            );
-            // Try to display as ASCII if possible
-            utils::print_as_ascii("Response (ASCII):", &Vec::from(response));
-        }
-        Ok(_) => println!("{}", "No response received (timeout).".color(Color::Yellow)),
-        Err(e) => println!(
-            "{} {}",
-            "Error reading response:".color(Color::Red),
-            e.to_string().color(Color::Red).bold()
-        ),
-    }
+    utils::print_as_ascii("Response (ASCII):", frame);
+    println!();
+    utils::print_dissection(&dissect::dissect_response(frame));
     Ok(())
 }
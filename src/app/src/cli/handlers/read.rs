@@ -1,15 +1,23 @@
-use crate::cli::commands::ReadArgs;
+use crate::cli::commands::{ReadArgs, ReadRequest};
 use crate::cli::handlers::utils;
 use api::api::error::RfidError;
-use api::api::uhf_rfid_api::UhfRfidApi;
+use api::api::template::Template;
+use api::api::uhf_rfid_api::{TagAccess, UhfRfidApi};
+use api::api::user_memory::{UserData, UserDataError};
 use api::rfid_device::usb_device::UsbDevice;
 use colorful::{Color, Colorful};
 use protocl::types::MemoryBank;
 
-pub fn handle(device: &UsbDevice, args: &ReadArgs) -> Result<(), RfidError> {
+pub fn handle(api: &UhfRfidApi, device: &UsbDevice, args: &ReadArgs) -> Result<(), RfidError> {
+    let request = args.request().map_err(RfidError::InvalidArgument)?;
+    let access = TagAccess {
+        target: args.epc.clone(),
+        password: args.password,
+    };
+
     // First, do an inventory to check if tags are in range
     println!("{}", "Checking for tags in range...".color(Color::Cyan));
-    match UhfRfidApi::inventory(device) {
+    match api.inventory(device) {
         Err(e) => Err(e),
         Ok(tags) => {
             if tags.is_empty() {
@@ -26,31 +34,103 @@ pub fn handle(device: &UsbDevice, args: &ReadArgs) -> Result<(), RfidError> {
                 tags.len().to_string().color(Color::Green).bold(),
                 "tags in range.".color(Color::Green)
             );
-            println!(
-                "{} {} {} {} {} {}",
-                "Reading".color(Color::Cyan),
-                args.words.to_string().color(Color::White).bold(),
-                "words from".color(Color::Cyan),
-                format!("{:?}", args.bank).color(Color::White).bold(),
-                "memory at address".color(Color::Cyan),
-                args.address.to_string().color(Color::White).bold()
-            );
-            match UhfRfidApi::read(device, args.bank, args.address, args.words) {
-                Err(e) => Err(e),
-                Ok(data) => {
-                    println!("{}", "Read successful!".color(Color::Green).bold());
-                    println!(
-                        "{} {}",
-                        "Data (hex):".color(Color::Cyan),
-                        UhfRfidApi::hex_to_ascii(&data)
-                    );
-                    // If it's EPC or TID, try to display as ASCII too
-                    if args.bank == MemoryBank::Epc || args.bank == MemoryBank::Tid {
-                        utils::print_as_ascii("Data (ASCII):", &data);
-                    }
-                    Ok(())
-                }
+            if let Some(target) = &args.epc {
+                utils::print_target(target, &tags);
+            }
+            match request {
+                ReadRequest::Words {
+                    bank,
+                    address,
+                    words,
+                } => read_words(api, device, &access, bank, address, words),
+                ReadRequest::Fields(template) => read_fields(api, device, &access, &template),
             }
         }
     }
 }
+
+fn read_words(
+    api: &UhfRfidApi,
+    device: &UsbDevice,
+    access: &TagAccess,
+    bank: MemoryBank,
+    address: u32,
+    words: u32,
+) -> Result<(), RfidError> {
+    println!(
+        "{} {} {} {} {} {}",
+        "Reading".color(Color::Cyan),
+        words.to_string().color(Color::White).bold(),
+        "words from".color(Color::Cyan),
+        format!("{bank:?}").color(Color::White).bold(),
+        "memory at address".color(Color::Cyan),
+        address.to_string().color(Color::White).bold()
+    );
+    let data = api.with_access(device, access, |device| {
+        api.read_with_progress(device, bank, address, words, |progress| {
+            utils::print_progress("Read", progress);
+        })
+    })?;
+    println!("{}", "Read successful!".color(Color::Green).bold());
+    println!(
+        "{} {}",
+        "Data (hex):".color(Color::Cyan),
+        UhfRfidApi::hex_to_ascii(&data)
+    );
+    // If it's EPC or TID, try to display as ASCII too
+    if bank == MemoryBank::Epc || bank == MemoryBank::Tid {
+        utils::print_as_ascii("Data (ASCII):", &data);
+    }
+    if bank == MemoryBank::Epc && address == 0 {
+        utils::print_epc_memory(&data);
+    }
+    if bank == MemoryBank::Tid && address == 0 {
+        utils::print_tid(&data);
+    }
+    if bank == MemoryBank::User && address == 0 {
+        print_user_data(api, device, access, &data);
+    }
+    Ok(())
+}
+
+fn read_fields(
+    api: &UhfRfidApi,
+    device: &UsbDevice,
+    access: &TagAccess,
+    template: &Template,
+) -> Result<(), RfidError> {
+    let (address, words) = template.span();
+    println!(
+        "{} {} {} {} {}",
+        "Reading".color(Color::Cyan),
+        template.fields.len().to_string().color(Color::White).bold(),
+        "template fields from User memory at address".color(Color::Cyan),
+        address.to_string().color(Color::White).bold(),
+        format!("({words} words)").color(Color::Cyan)
+    );
+    let fields = api.with_access(device, access, |device| api.read_template(device, template))?;
+    println!("{}", "Read successful!".color(Color::Green).bold());
+    for (name, value) in fields {
+        println!("  {} {}", format!("{name}:").color(Color::Cyan), value);
+    }
+    Ok(())
+}
+
+/// Print the ISO/IEC 15962 data sets of User memory read from word 0
+///
+/// If the data sets continue past the words read, User memory is read again up to the
+/// terminating precursor. Memory that is not formatted prints nothing.
+fn print_user_data(api: &UhfRfidApi, device: &UsbDevice, access: &TagAccess, data: &[u8]) {
+    let user_data = match UserData::decode(data) {
+        Ok(user_data) => Ok(user_data),
+        Err(UserDataError::NotFormatted) => return,
+        Err(UserDataError::Truncated(_)) => api
+            .with_access(device, access, |device| api.read_user_data(device))
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    match user_data {
+        Ok(user_data) => utils::print_user_data(&user_data),
+        Err(message) => println!("  {}", message.color(Color::Yellow)),
+    }
+}
@@ -1,4 +1,18 @@
+use api::api::epc::Epc;
+use api::api::error::RfidError;
+use api::api::tid::Tid;
+use api::api::uhf_rfid_api::TransferProgress;
+use api::api::uhf_rfid_api::UhfRfidApi;
+use api::api::user_memory::UserData;
 use colorful::{Color, Colorful};
+use protocl::codec::{
+    MAX_WORDS_PER_COMMAND, STATUS_ACCESS_DENIED, STATUS_BAD_COMMAND, STATUS_MEMORY_LOCKED,
+    STATUS_MEMORY_OVERRUN, STATUS_NO_TAG,
+};
+use protocl::dissect::Dissection;
+use protocl::epc_bank::EpcMemory;
+use protocl::types::{InventoryResult, TagFilter};
+use std::io::{self, Write};
 
 pub fn print_as_ascii(msg: &str, data: &Vec<u8>) {
     print!("{} ", msg.color(Color::Cyan));
@@ -11,3 +25,122 @@ pub fn print_as_ascii(msg: &str, data: &Vec<u8>) {
     }
     println!();
 }
+
+/// Print a frame breakdown, one labelled field per line
+pub fn print_dissection(dissection: &Dissection) {
+    let color = if dissection.direction.is_some() {
+        Color::Cyan
+    } else {
+        Color::Yellow
+    };
+    println!("{}", dissection.summary.clone().color(color).bold());
+    for field in &dissection.fields {
+        println!(
+            "{:>4}  {:<24}  {} {}",
+            field.offset,
+            field.hex(),
+            format!("{:<9}", field.name).color(Color::Green),
+            field.meaning.clone().color(Color::White)
+        );
+    }
+}
+
+/// Print a single updating progress line for transfers that take several commands
+pub fn print_progress(verb: &str, progress: TransferProgress) {
+    if progress.words_total <= u32::from(MAX_WORDS_PER_COMMAND) {
+        return;
+    }
+    print!(
+        "\r{} {}/{} words",
+        verb.color(Color::Cyan),
+        progress.words_done,
+        progress.words_total
+    );
+    if progress.words_done == progress.words_total {
+        println!();
+    }
+    let _ = io::stdout().flush();
+}
+
+/// Announce the tag an operation is targeted at, warning if it was not seen in range
+pub fn print_target(target: &TagFilter, tags: &[InventoryResult]) {
+    let epc = UhfRfidApi::hex_to_ascii(&target.mask);
+    println!(
+        "{} {}",
+        "Targeting tag".color(Color::Cyan),
+        epc.clone().color(Color::White).bold()
+    );
+    if !tags.iter().any(|tag| tag.epc == epc) {
+        println!(
+            "{}",
+            "Warning: The target tag was not seen in range; the operation will likely fail."
+                .color(Color::Yellow)
+        );
+    }
+}
+
+/// Print the Pure Identity URI of an EPC that follows the GS1 Tag Data Standard, indented
+/// to line up under it
+pub fn print_epc_uri(indent: usize, epc: &str) {
+    if let Ok(epc) = Epc::from_hex(epc) {
+        println!(
+            "{:indent$}{}",
+            "",
+            epc.pure_identity_uri().color(Color::Cyan)
+        );
+    }
+}
+
+/// Print the chip and serial number decoded from TID bytes read from word 0
+pub fn print_tid(tid: &[u8]) {
+    match Tid::decode(tid) {
+        Ok(tid) => {
+            for (label, value) in tid.fields() {
+                println!("  {} {}", format!("{label}:").color(Color::Cyan), value);
+            }
+        }
+        Err(e) => println!("  {}", e.to_string().color(Color::Yellow)),
+    }
+}
+
+/// Print the stored CRC, PC word and EPC decoded from EPC bank bytes read from word 0
+pub fn print_epc_memory(bank: &[u8]) {
+    if let Some(memory) = EpcMemory::decode(bank) {
+        for (label, value) in memory.fields() {
+            println!("  {} {}", format!("{label}:").color(Color::Cyan), value);
+        }
+    }
+}
+
+/// Print the DSFID and data sets of ISO/IEC 15962 User memory
+pub fn print_user_data(user_data: &UserData) {
+    for (label, value) in user_data.fields() {
+        println!("  {} {}", format!("{label}:").color(Color::Cyan), value);
+    }
+}
+
+/// Suggest what the user can do about a failed command
+pub fn error_hint(error: &RfidError) -> Option<&'static str> {
+    match error {
+        RfidError::CommandFailed { status, .. } => match *status {
+            STATUS_NO_TAG => Some(
+                "Place a tag near the reader and try again; with --epc, check the EPC is in range.",
+            ),
+            STATUS_BAD_COMMAND => {
+                Some("The reader did not understand the command; check the arguments.")
+            }
+            STATUS_MEMORY_OVERRUN => {
+                Some("Check the address and word count against the size of the memory bank.")
+            }
+            STATUS_MEMORY_LOCKED => {
+                Some("The memory is locked; it may need the access password or be permalocked.")
+            }
+            STATUS_ACCESS_DENIED => Some("Check the access password."),
+            _ => None,
+        },
+        RfidError::InvalidResponse(_) | RfidError::FragmentationError(_) | RfidError::Timeout => {
+            Some("Check the reader connection and try again.")
+        }
+        _ => None,
+    }
+}
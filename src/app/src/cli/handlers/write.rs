@@ -1,15 +1,33 @@
-use crate::cli::commands::WriteArgs;
+use crate::cli::commands::{WriteArgs, WritePayload};
+use crate::cli::handlers::utils;
 use api::api::error::RfidError;
-use api::api::uhf_rfid_api::UhfRfidApi;
+use api::api::template::Template;
+use api::api::uhf_rfid_api::{TagAccess, UhfRfidApi};
+use api::api::user_memory::UserData;
 use api::rfid_device::usb_device::UsbDevice;
 use colorful::{Color, Colorful};
+use protocl::epc_bank::PC_ADDRESS;
+use protocl::types::MemoryBank;
 use std::io;
 use std::io::Write;
 
-pub fn handle(device: &UsbDevice, args: &WriteArgs) -> Result<(), RfidError> {
+pub fn handle(api: &UhfRfidApi, device: &UsbDevice, args: &WriteArgs) -> Result<(), RfidError> {
+    let payload = args.payload().map_err(RfidError::InvalidArgument)?;
+    if let Ok(Some(epc)) = args.gs1_epc() {
+        println!(
+            "{} {}",
+            "Encoded".color(Color::Cyan),
+            epc.tag_uri().color(Color::White).bold()
+        );
+    }
+    let access = TagAccess {
+        target: args.epc.clone(),
+        password: args.password,
+    };
+
     // First, do an inventory to check if tags are in range
     println!("{}", "Checking for tags in range...".color(Color::Cyan));
-    match UhfRfidApi::inventory(device) {
+    match api.inventory(device) {
         Err(e) => Err(e),
         Ok(tags) => {
             if tags.is_empty() {
@@ -26,7 +44,9 @@ pub fn handle(device: &UsbDevice, args: &WriteArgs) -> Result<(), RfidError> {
                 tags.len().to_string().color(Color::Green).bold(),
                 "tags in range.".color(Color::Green)
             );
-            if tags.len() > 1 {
+            if let Some(target) = &args.epc {
+                utils::print_target(target, &tags);
+            } else if tags.len() > 1 {
                 println!(
                     "{}",
                     "Warning: Multiple tags detected. Write may affect all tags in range."
@@ -45,21 +65,183 @@ pub fn handle(device: &UsbDevice, args: &WriteArgs) -> Result<(), RfidError> {
                     return Ok(());
                 }
             }
+            match payload {
+                WritePayload::Words {
+                    bank,
+                    address,
+                    data,
+                } => write_words(api, device, &access, bank, address, &data),
+                WritePayload::Epc(epc) => write_epc(api, device, &access, &epc),
+                WritePayload::UserData(user_data) => {
+                    write_user_data(api, device, &access, &user_data)
+                }
+                WritePayload::Fields { template, values } => {
+                    write_fields(api, device, &access, &template, &values)
+                }
+            }
+        }
+    }
+}
+
+fn write_words(
+    api: &UhfRfidApi,
+    device: &UsbDevice,
+    access: &TagAccess,
+    bank: MemoryBank,
+    address: u32,
+    data: &[u8],
+) -> Result<(), RfidError> {
+    println!(
+        "{} {} {} {} {} {}",
+        "Writing".color(Color::Cyan),
+        data.len().to_string().color(Color::White).bold(),
+        "bytes to".color(Color::Cyan),
+        format!("{bank:?}").color(Color::White).bold(),
+        "memory at address".color(Color::Cyan),
+        address.to_string().color(Color::White).bold()
+    );
+    if !fits_chip(api, device, access, bank, address, words(data)) {
+        return Ok(());
+    }
+    api.with_access(device, access, |device| {
+        api.write_with_progress(device, bank, address, data, |progress| {
+            utils::print_progress("Written", progress);
+        })
+    })?;
+    println!("{}", "Write successful!".color(Color::Green).bold());
+    Ok(())
+}
+
+fn write_epc(
+    api: &UhfRfidApi,
+    device: &UsbDevice,
+    access: &TagAccess,
+    epc: &[u8],
+) -> Result<(), RfidError> {
+    println!(
+        "{} {} {}",
+        "Writing EPC".color(Color::Cyan),
+        UhfRfidApi::hex_to_ascii(epc).color(Color::White).bold(),
+        "with its PC word".color(Color::Cyan)
+    );
+    // The PC word is written along with the EPC
+    if !fits_chip(
+        api,
+        device,
+        access,
+        MemoryBank::Epc,
+        PC_ADDRESS,
+        words(epc) + 1,
+    ) {
+        return Ok(());
+    }
+    let pc = api.with_access(device, access, |device| api.write_epc(device, epc))?;
+    println!("{}", "Write successful!".color(Color::Green).bold());
+    println!("  {} {}", "PC:".color(Color::Cyan), pc);
+    Ok(())
+}
+
+fn write_user_data(
+    api: &UhfRfidApi,
+    device: &UsbDevice,
+    access: &TagAccess,
+    user_data: &UserData,
+) -> Result<(), RfidError> {
+    println!(
+        "{} {} {}",
+        "Writing".color(Color::Cyan),
+        user_data
+            .data_sets
+            .len()
+            .to_string()
+            .color(Color::White)
+            .bold(),
+        "ISO/IEC 15962 data sets to User memory".color(Color::Cyan)
+    );
+    utils::print_user_data(user_data);
+    // The payload has already been encoded once, so this does not fail
+    let encoded = user_data.encode().unwrap_or_default();
+    if !fits_chip(api, device, access, MemoryBank::User, 0, words(&encoded)) {
+        return Ok(());
+    }
+    api.with_access(device, access, |device| {
+        api.write_user_data(device, user_data)
+    })?;
+    println!("{}", "Write successful!".color(Color::Green).bold());
+    Ok(())
+}
+
+fn write_fields(
+    api: &UhfRfidApi,
+    device: &UsbDevice,
+    access: &TagAccess,
+    template: &Template,
+    values: &[(String, String)],
+) -> Result<(), RfidError> {
+    println!(
+        "{} {} {}",
+        "Writing".color(Color::Cyan),
+        values.len().to_string().color(Color::White).bold(),
+        "template fields to User memory".color(Color::Cyan)
+    );
+    for (name, value) in values {
+        println!("  {} {}", format!("{name}:").color(Color::Cyan), value);
+    }
+    // The payload has already been encoded once, so this does not fail
+    let Ok(update) = template.encode(values) else {
+        return Ok(());
+    };
+    if !fits_chip(
+        api,
+        device,
+        access,
+        MemoryBank::User,
+        update.first_word,
+        update.words,
+    ) {
+        return Ok(());
+    }
+    api.with_access(device, access, |device| {
+        api.write_template(device, template, values)
+    })?;
+    println!("{}", "Write successful!".color(Color::Green).bold());
+    Ok(())
+}
+
+fn words(data: &[u8]) -> u32 {
+    u32::try_from(data.len() / 2).unwrap_or(u32::MAX)
+}
+
+/// Check the write range against the memory of the chip named by the tag's TID
+///
+/// Returns false if the range does not fit; chips missing from the table are not checked.
+fn fits_chip(
+    api: &UhfRfidApi,
+    device: &UsbDevice,
+    access: &TagAccess,
+    bank: MemoryBank,
+    address: u32,
+    words: u32,
+) -> bool {
+    match api
+        .with_access(device, access, |device| api.read_tid(device))
+        .map(|tid| tid.chip())
+    {
+        Ok(Some(chip)) => {
             println!(
-                "{} {} {} {} {} {}",
-                "Writing".color(Color::Cyan),
-                args.data.len().to_string().color(Color::White).bold(),
-                "bytes to".color(Color::Cyan),
-                format!("{:?}", args.bank).color(Color::White).bold(),
-                "memory at address".color(Color::Cyan),
-                args.address.to_string().color(Color::White).bold()
+                "{} {}",
+                "Tag chip:".color(Color::Cyan),
+                chip.name.color(Color::White).bold()
             );
-            if let Err(e) = UhfRfidApi::write(device, args.bank, args.address, &args.data) {
-                Err(e)
-            } else {
-                println!("{}", "Write successful!".color(Color::Green).bold());
-                Ok(())
+            if let Err(e) = chip.check_range(bank, address, words) {
+                println!("{}", e.to_string().color(Color::Red));
+                return false;
             }
         }
+        Ok(None) | Err(_) => println!(
+            "{}",
+            "Unknown tag chip; the write range is not checked.".color(Color::Yellow)
+        ),
     }
+    true
 }
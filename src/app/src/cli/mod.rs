@@ -1,22 +1,31 @@
 //! CLI module for the RFID command-line application
 
 use api::api::error::RfidError;
+use api::api::uhf_rfid_api::UhfRfidApi;
 use api::rfid_device::usb_device::UsbDevice;
 use clap::Parser;
 use colorful::{Color, Colorful};
 use commands::{CliArguments, Commands};
+use protocl::interface::RetryPolicy;
 use std::process;
+use tracing::info_span;
 
 pub mod app;
 pub mod commands;
 mod handlers;
 pub mod menu;
 
-pub fn run_cli() -> Result<(), RfidError> {
+pub fn run_cli(policy: RetryPolicy) -> Result<(), RfidError> {
     // Parse command line arguments
     let cli = CliArguments::parse();
 
-    // Create a device with the appropriate debug setting
+    // Decoding a frame needs no reader
+    if let Some(Commands::Decode(args)) = &cli.command {
+        handlers::decode::handle(args);
+        return Ok(());
+    }
+
+    let api = UhfRfidApi::new(policy);
     let device = match UsbDevice::new() {
         Ok(device) => {
             println!(
@@ -43,22 +52,40 @@ pub fn run_cli() -> Result<(), RfidError> {
         return Ok(());
     }
 
-    match cli.command.as_ref().unwrap() {
+    let command = cli.command.as_ref().unwrap();
+    let _span = info_span!("cli", command = <&'static str>::from(command)).entered();
+    let result = match command {
         Commands::Interactive => {
             println!("{}", "Starting interactive mode...".color(Color::Cyan));
-            app::run_interactive_app()
+            app::run_interactive_app(policy)
         }
-        Commands::Inventory => handlers::inventory::handle(&device),
-        Commands::Read(args) => handlers::read::handle(&device, args),
-        Commands::Write(args) => handlers::write::handle(&device, args),
-        Commands::Lock(args) => handlers::lock::handle(&device, args),
-        Commands::Password(args) => handlers::password::handle(&device, args),
+        Commands::Inventory(args) if args.watch => handlers::inventory::watch(&api, device, args),
+        Commands::Inventory(_) => handlers::inventory::handle(&api, &device),
+        Commands::Read(args) => handlers::read::handle(&api, &device, args),
+        Commands::Write(args) => handlers::write::handle(&api, &device, args),
+        Commands::Lock(args) => handlers::lock::handle(&api, &device, args),
+        Commands::Password(args) => handlers::password::handle(&api, &device, args),
+        Commands::Kill(args) => handlers::kill::handle(&api, &device, args),
         Commands::DeviceInfo => {
             handlers::device_info::handle(&device);
             Ok(())
         },
-        Commands::RawCommand(args) => handlers::raw_command::handle(&device, args),
-        Commands::Action(args) => handlers::device_action::handle(&device, args),
-        Commands::Test => handlers::test::handle(&device),
+        Commands::Config(args) => handlers::config::handle(&api, &device, args),
+        Commands::RawCommand(args) => handlers::raw_command::handle(&api, &device, args),
+        Commands::Decode(args) => {
+            handlers::decode::handle(args);
+            Ok(())
+        }
+        Commands::Action(args) => handlers::device_action::handle(&api, &device, args),
+        Commands::Test => handlers::test::handle(&api, &device),
+    };
+
+    if let Err(e) = &result {
+        eprintln!("{}", format!("Error: {e}").color(Color::Red));
+        if let Some(hint) = handlers::utils::error_hint(e) {
+            eprintln!("{}", hint.color(Color::Yellow));
+        }
+        process::exit(1);
     }
+    result
 }
@@ -0,0 +1,130 @@
+//! Configuration file with reader timeouts and retries
+//!
+//! The file is TOML, read from `--config-file` or, when that is not given and the file
+//! exists, from `$XDG_CONFIG_HOME/uhf-rfid/config.toml` (`~/.config/uhf-rfid/config.toml`):
+//!
+//! ```toml
+//! timeout_ms = 1500   # reply timeout of every command
+//! retries = 3         # extra attempts after a timeout or transfer failure
+//! backoff_ms = 100    # pause before the first retry, doubled for each further one
+//!
+//! [timeouts]          # per-command reply timeouts
+//! inventory = 3000
+//! write = 4000
+//! ```
+//!
+//! `--timeout-ms`, `--retries` and `--backoff-ms` override the file. `--timeout-ms` applies to
+//! every command, so the `[timeouts]` table is ignored when it is given. Timeouts must be at
+//! least one millisecond.
+
+use crate::cli::commands::CliArguments;
+use protocl::codec::CommandKind;
+use protocl::interface::RetryPolicy;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Contents of the configuration file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    /// Reply timeout of every command without its own timeout, in milliseconds
+    pub timeout_ms: Option<u64>,
+    /// Extra attempts after a retryable failure
+    pub retries: Option<u8>,
+    /// Pause before the first retry, in milliseconds
+    pub backoff_ms: Option<u64>,
+    /// Reply timeouts in milliseconds by command kind (`read`, `write`, `inventory`, ...)
+    pub timeouts: BTreeMap<String, u64>,
+}
+
+impl ConfigFile {
+    /// Location of the configuration file when `--config-file` is not given
+    #[must_use]
+    pub fn default_path() -> Option<PathBuf> {
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(config_home.join("uhf-rfid").join("config.toml"))
+    }
+
+    /// Read and parse the configuration file at `path`
+    ///
+    /// # Errors
+    /// Returns a message naming the file if it cannot be read or is not valid.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text =
+            fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+        toml::from_str(&text).map_err(|e| format!("Invalid {}: {e}", path.display()))
+    }
+
+    /// Retry policy described by the file, starting from the defaults
+    ///
+    /// # Errors
+    /// Returns a message if a timeout is zero or a per-command timeout names an unknown
+    /// command kind.
+    pub fn policy(&self) -> Result<RetryPolicy, String> {
+        let mut policy = RetryPolicy::default();
+        if let Some(ms) = self.timeout_ms {
+            policy = policy.with_default_timeout(timeout("timeout_ms", ms)?);
+        }
+        if let Some(retries) = self.retries {
+            policy = policy.with_retries(retries);
+        }
+        if let Some(ms) = self.backoff_ms {
+            policy = policy.with_backoff(Duration::from_millis(ms));
+        }
+        for (name, &ms) in &self.timeouts {
+            let kind = CommandKind::from_name(name).ok_or_else(|| {
+                let known: Vec<_> = CommandKind::ALL.iter().map(|kind| kind.name()).collect();
+                format!(
+                    "Unknown command '{name}' in [timeouts], expected one of: {}",
+                    known.join(", ")
+                )
+            })?;
+            policy = policy.with_timeout(kind, timeout(&format!("timeouts.{name}"), ms)?);
+        }
+        Ok(policy)
+    }
+}
+
+/// Timeout of `ms` milliseconds read from the `key` entry, which must not be zero
+fn timeout(key: &str, ms: u64) -> Result<Duration, String> {
+    if ms == 0 {
+        return Err(format!("Timeout '{key}' must be at least 1 ms"));
+    }
+    Ok(Duration::from_millis(ms))
+}
+
+/// Retry policy from the configuration file, overridden by the command-line flags
+///
+/// `--timeout-ms` replaces the default timeout and every per-command timeout of the file.
+///
+/// # Errors
+/// Returns a message if the configuration file cannot be read or is not valid.
+pub fn retry_policy(cli: &CliArguments) -> Result<RetryPolicy, String> {
+    let mut file = match &cli.config_file {
+        Some(path) => ConfigFile::load(path)?,
+        None => match ConfigFile::default_path() {
+            Some(path) if path.exists() => ConfigFile::load(&path)?,
+            _ => ConfigFile::default(),
+        },
+    };
+    if cli.timeout_ms.is_some() {
+        file.timeouts.clear();
+    }
+    let mut policy = file.policy()?;
+    if let Some(ms) = cli.timeout_ms {
+        policy = policy.with_default_timeout(Duration::from_millis(ms));
+    }
+    if let Some(retries) = cli.retries {
+        policy = policy.with_retries(retries);
+    }
+    if let Some(ms) = cli.backoff_ms {
+        policy = policy.with_backoff(Duration::from_millis(ms));
+    }
+    Ok(policy)
+}
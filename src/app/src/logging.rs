@@ -0,0 +1,59 @@
+//! Tracing subscriber setup for the CLI and TUI
+//!
+//! The level comes from `--debug`/`-v` unless `RUST_LOG` is set. The CLI logs to stderr;
+//! the TUI owns the terminal, so it only logs when `--log-file` is given. The log file gets
+//! one JSON object per event, with the fields of the enclosing spans and a record of every
+//! span's duration when it closes.
+
+use crate::cli::commands::CliArguments;
+use std::fs::File;
+use std::io;
+use std::sync::Mutex;
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{EnvFilter, fmt};
+
+/// Level selected by `--debug` and the number of `-v` flags
+fn level(cli: &CliArguments) -> LevelFilter {
+    let verbosity = if cli.debug {
+        cli.verbose.max(2)
+    } else {
+        cli.verbose
+    };
+    match verbosity {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+/// Install the global subscriber; `console` logs to stderr as well
+///
+/// # Errors
+/// Returns an error if the log file cannot be opened.
+pub fn init(cli: &CliArguments, console: bool) -> io::Result<()> {
+    let filter = EnvFilter::builder()
+        .with_default_directive(level(cli).into())
+        .from_env_lossy();
+    let file_layer = match &cli.log_file {
+        Some(path) => {
+            let file = File::options().create(true).append(true).open(path)?;
+            Some(
+                fmt::layer()
+                    .json()
+                    .with_span_events(FmtSpan::CLOSE)
+                    .with_writer(Mutex::new(file)),
+            )
+        }
+        None => None,
+    };
+    let console_layer = console.then(|| fmt::layer().with_writer(io::stderr));
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(file_layer)
+        .with(console_layer)
+        .init();
+    Ok(())
+}
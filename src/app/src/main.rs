@@ -3,9 +3,15 @@
 #![warn(clippy::pedantic)]
 
 mod cli;
+mod config;
+mod logging;
+mod template;
 mod tui;
 
-use crate::cli::{commands::CliArguments, run_cli};
+use crate::cli::{
+    commands::{CliArguments, Commands},
+    run_cli,
+};
 use crate::tui::run_tui;
 use api::api::error::RfidError;
 use api::platform;
@@ -13,21 +19,37 @@ use clap::Parser;
 use std::process;
 
 fn main() -> Result<(), RfidError> {
-    // Check for USB permissions before trying to connect
-    if let Err(e) = platform::check_usb_permissions() {
+    // Parse command line arguments to check for CLI mode
+    let cli = CliArguments::parse();
+    let cli_mode = cli.cli || cli.command.is_some();
+
+    // Check for USB permissions before trying to connect; decoding frames needs no device
+    if !matches!(cli.command, Some(Commands::Decode(_)))
+        && let Err(e) = platform::check_usb_permissions()
+    {
         eprintln!("USB permission check failed: {e}");
         platform::print_permission_instructions();
         process::exit(1);
     }
 
-    // Parse command line arguments to check for CLI mode
-    let cli = CliArguments::parse();
+    if let Err(e) = logging::init(&cli, cli_mode) {
+        eprintln!("Failed to open the log file: {e}");
+        process::exit(1);
+    }
+
+    let policy = match config::retry_policy(&cli) {
+        Ok(policy) => policy,
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    };
 
     // Run either CLI or TUI based on arguments
 
-    if cli.cli || cli.command.is_some() {
-        run_cli()
+    if cli_mode {
+        run_cli(policy)
     } else {
-        run_tui()
+        run_tui(policy)
     }
 }
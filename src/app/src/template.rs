@@ -0,0 +1,73 @@
+//! Template files naming typed fields of User memory
+//!
+//! A template is TOML, or JSON when the file name ends in `.json`. Each field gives its
+//! word address, bit offset within that word (from the most significant bit, default 0),
+//! width in bits and type:
+//!
+//! ```toml
+//! [[fields]]
+//! name = "asset_id"
+//! word = 0
+//! bits = 32
+//! type = "uint"
+//!
+//! [[fields]]
+//! name = "installed"
+//! word = 2
+//! bits = 32
+//! type = "bcd_date"          # YYYYMMDD; 24 bits for YYMMDD
+//!
+//! [[fields]]
+//! name = "inspections"
+//! word = 4
+//! bits = 12
+//! type = "uint"
+//!
+//! [[fields]]
+//! name = "status"
+//! word = 4
+//! bit = 12
+//! bits = 2
+//! type = "enum"
+//! values = { active = 1, retired = 2 }
+//!
+//! [[fields]]
+//! name = "alarms"
+//! word = 4
+//! bit = 14
+//! bits = 2
+//! type = "flags"
+//! flags = ["tamper", "battery"]   # from the least significant bit
+//!
+//! [[fields]]
+//! name = "site"
+//! word = 5
+//! bits = 32
+//! type = "ascii"
+//! ```
+//!
+//! `int` fields hold two's complement integers.
+
+use api::api::template::Template;
+use std::fs;
+use std::path::Path;
+
+/// Read, parse and check the template file at `path`
+///
+/// # Errors
+/// Returns a message naming the file if it cannot be read, is not valid, or has a field
+/// whose width does not suit its type.
+pub fn load(path: &Path) -> Result<Template, String> {
+    let text =
+        fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+    let template: Template = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_str(&text).map_err(|e| e.to_string())
+    } else {
+        toml::from_str(&text).map_err(|e| e.to_string())
+    }
+    .map_err(|e| format!("Invalid {}: {e}", path.display()))?;
+    template
+        .validate()
+        .map_err(|e| format!("Invalid {}: {e}", path.display()))?;
+    Ok(template)
+}
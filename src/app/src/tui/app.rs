@@ -1,15 +1,30 @@
 //! Application state for the TUI
 
+use api::api::epc::Epc;
+use api::api::inventory_watch::{InventoryWatch, TagState};
+use api::api::uhf_rfid_api::{TagAccess, UhfRfidApi};
 use api::rfid_device::usb_device::UsbDevice;
-use protocl::types::{LockAction, LockableMemoryBank, MemoryBank};
+use protocl::interface::{RawOptions, RawReply};
+use protocl::types::{
+    Gen2Settings, InventoryResult, InventoryTarget, LockAction, LockableMemoryBank, MemoryBank,
+    ReaderSettings, Region, RfSettings, Session, TagFilter,
+};
 use strum::{EnumIter, IntoEnumIterator};
 
 /// Application state
+#[allow(clippy::struct_excessive_bools)]
 pub struct App {
     pub state: AppState,
     pub status_message: String,
     pub device: Option<UsbDevice>,
+    // Commands go out with the timeouts and retries from the flags and config file
+    pub api: UhfRfidApi,
     pub tags: Vec<TagData>,
+    // Continuous inventory; owns the device while it runs
+    pub auto_refresh: Option<InventoryWatch<UsbDevice>>,
+    pub present_tags: Vec<TagState>,
+    // EPC of the tag picked in the tag list; operations only touch that tag
+    pub target_epc: Option<Vec<u8>>,
     pub selected_menu_item: MenuItem,
     pub active_input_field: usize,
     pub result_data: Vec<u8>,
@@ -37,9 +52,17 @@ pub struct App {
     pub lock_bank: LockableMemoryBank,
     pub lock_action: LockAction,
 
+    // Access password shared by the read, write and lock forms
+    pub access_password: String,
+
     // Password form field
     pub password: String,
 
+    // Kill form fields
+    pub kill_password: String,
+    pub kill_tid: String,
+    pub kill_confirm: String,
+
     // Device Actions
     pub action_beep: bool,
     pub action_red: bool,
@@ -49,7 +72,12 @@ pub struct App {
 
     // Raw command form fields
     pub raw_input: String,
-    pub raw_response: Vec<u8>,
+    pub raw_options: RawOptions,
+    pub raw_reply: RawReply,
+
+    // Reader settings form fields
+    pub reader_settings: ReaderSettings,
+    pub save_settings: bool,
 
     // Confirmation gate for risky operations
     pub pending_confirm: Option<PendingConfirm>,
@@ -58,8 +86,26 @@ pub struct App {
 /// Tag data structure
 pub struct TagData {
     pub epc: String,
+    pub epc_bytes: Vec<u8>,
     pub data: Vec<u8>,
     pub data_bank: Option<MemoryBank>,
+    /// Pure Identity URI, if the EPC follows the GS1 Tag Data Standard
+    pub uri: Option<String>,
+}
+
+impl From<&InventoryResult> for TagData {
+    fn from(tag: &InventoryResult) -> Self {
+        let epc_bytes = UhfRfidApi::ascii_to_hex(&tag.epc).unwrap_or_default();
+        Self {
+            epc: tag.to_string(),
+            uri: Epc::decode(&epc_bytes)
+                .ok()
+                .map(|epc| epc.pure_identity_uri()),
+            epc_bytes,
+            data: Vec::new(),
+            data_bank: None,
+        }
+    }
 }
 
 /// Application state
@@ -70,8 +116,10 @@ pub enum AppState {
     Write,
     Lock,
     Password,
+    Kill,
     Action,
     Raw,
+    Settings,
     Test,
 }
 
@@ -79,11 +127,14 @@ pub enum AppState {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
 pub enum MenuItem {
     Inventory,
+    AutoRefresh,
     ReadTag,
     WriteTag,
     LockTag,
     SetPassword,
+    KillTag,
     DeviceAction,
+    ReaderSettings,
     RawCommand,
     RunTest,
     Quit,
@@ -93,11 +144,14 @@ impl MenuItem {
     pub fn to_string(self) -> &'static str {
         match self {
             MenuItem::Inventory => "[i] Inventory (Scan for Tags)",
+            MenuItem::AutoRefresh => "[f] Toggle Auto-Refresh",
             MenuItem::ReadTag => "[r] Read Tag",
             MenuItem::WriteTag => "[w] Write Tag",
             MenuItem::LockTag => "[l] Lock Tag",
             MenuItem::SetPassword => "[p] Set Password",
+            MenuItem::KillTag => "[k] Kill Tag",
             MenuItem::DeviceAction => "[a] Device Action",
+            MenuItem::ReaderSettings => "[c] Reader Settings",
             MenuItem::RawCommand => "[m] Manual Raw Command",
             MenuItem::RunTest => "[t] Run Test",
             MenuItem::Quit => "[q] Quit",
@@ -111,7 +165,11 @@ impl App {
             state: AppState::Main,
             status_message: "Starting...".to_string(),
             device: None,
+            api: UhfRfidApi::default(),
             tags: Vec::new(),
+            auto_refresh: None,
+            present_tags: Vec::new(),
+            target_epc: None,
             selected_menu_item: MenuItem::Inventory,
             active_input_field: 0,
             result_data: Vec::new(),
@@ -134,9 +192,15 @@ impl App {
             lock_bank: LockableMemoryBank::Epc,
             lock_action: LockAction::SecureWriteable,
 
+            access_password: String::new(),
+
             // Default password
             password: String::new(),
 
+            kill_password: String::new(),
+            kill_tid: String::new(),
+            kill_confirm: String::new(),
+
             action_beep: false,
             action_red: false,
             action_green: false,
@@ -144,7 +208,11 @@ impl App {
             action_time: "50".to_string(),
 
             raw_input: String::new(),
-            raw_response: Vec::new(),
+            raw_options: RawOptions::default(),
+            raw_reply: RawReply::default(),
+
+            reader_settings: ReaderSettings::default(),
+            save_settings: false,
 
             pending_confirm: None,
         }
@@ -155,6 +223,47 @@ impl App {
         // This could include checking device connection, updating inventory, etc.
     }
 
+    /// Select filter for the targeted tag, if one is picked
+    pub fn target_filter(&self) -> Option<TagFilter> {
+        self.target_epc.as_deref().map(TagFilter::epc)
+    }
+
+    /// Targeted tag and access password for read, write and lock operations
+    ///
+    /// Returns `None` if the access password field is not valid hex.
+    pub fn tag_access(&self) -> Option<TagAccess> {
+        let password = if self.access_password.is_empty() {
+            None
+        } else {
+            Some(u32::from_str_radix(&self.access_password, 16).ok()?)
+        };
+        Some(TagAccess {
+            target: self.target_filter(),
+            password,
+        })
+    }
+
+    /// Target the tag at `index` in the tag list, or stop targeting it if it already is
+    pub fn toggle_target(&mut self, index: usize) {
+        let Some(tag) = self.tags.get(index) else {
+            format!("No tag {} in the list.", index + 1).clone_into(&mut self.status_message);
+            return;
+        };
+        if self.target_epc.as_ref() == Some(&tag.epc_bytes) {
+            self.target_epc = None;
+            "Operations act on any tag in range.".clone_into(&mut self.status_message);
+        } else {
+            self.target_epc = Some(tag.epc_bytes.clone());
+            format!("Targeting tag {}.", index + 1).clone_into(&mut self.status_message);
+        }
+    }
+
+    /// Index of the targeted tag in the tag list
+    pub fn target_index(&self) -> Option<usize> {
+        let target = self.target_epc.as_ref()?;
+        self.tags.iter().position(|tag| &tag.epc_bytes == target)
+    }
+
     pub fn next_menu_item(&mut self) {
         let items: Vec<MenuItem> = MenuItem::iter().collect();
         let current_pos = items
@@ -182,10 +291,13 @@ impl App {
     pub fn next_input_field(&mut self) {
         match self.state {
             AppState::Read | AppState::Write => {
+                self.active_input_field = (self.active_input_field + 1) % 4;
+            }
+            AppState::Lock | AppState::Kill | AppState::Raw => {
                 self.active_input_field = (self.active_input_field + 1) % 3;
             }
-            AppState::Lock => {
-                self.active_input_field = (self.active_input_field + 1) % 2;
+            AppState::Settings => {
+                self.active_input_field = (self.active_input_field + 1) % SETTINGS_FIELDS;
             }
             _ => {}
         }
@@ -194,14 +306,25 @@ impl App {
     pub fn prev_input_field(&mut self) {
         match self.state {
             AppState::Read | AppState::Write => {
+                self.active_input_field = if self.active_input_field == 0 {
+                    3
+                } else {
+                    self.active_input_field - 1
+                };
+            }
+            AppState::Lock | AppState::Kill | AppState::Raw => {
                 self.active_input_field = if self.active_input_field == 0 {
                     2
                 } else {
                     self.active_input_field - 1
                 };
             }
-            AppState::Lock => {
-                self.active_input_field = usize::from(self.active_input_field == 0);
+            AppState::Settings => {
+                self.active_input_field = if self.active_input_field == 0 {
+                    SETTINGS_FIELDS - 1
+                } else {
+                    self.active_input_field - 1
+                };
             }
             _ => {}
         }
@@ -213,50 +336,42 @@ impl App {
             AppState::Read => {
                 match self.active_input_field {
                     // 0 => { /* Bank selection is handled separately */ }
-                    1 => {
-                        // Address - only digits
-                        if c.is_ascii_digit() {
-                            self.read_address.push(c);
-                        }
-                    }
-                    2 => {
-                        // Word count - only digits
-                        if c.is_ascii_digit() {
-                            self.read_word_count.push(c);
-                        }
-                    }
+                    // Address - only digits
+                    1 if c.is_ascii_digit() => self.read_address.push(c),
+                    // Word count - only digits
+                    2 if c.is_ascii_digit() => self.read_word_count.push(c),
+                    // Access password - hex digits
+                    3 if c.is_ascii_hexdigit() => self.access_password.push(c),
                     _ => {}
                 }
             }
             AppState::Write => {
                 match self.active_input_field {
                     // 0 => { /* Bank selection is handled separately */ }
-                    1 => {
-                        // Address - only digits
-                        if c.is_ascii_digit() {
-                            self.write_address.push(c);
-                        }
-                    }
-                    2 => {
-                        // Data - hex digits
-                        if c.is_ascii_hexdigit() {
-                            self.write_data.push(c);
-                        }
-                    }
+                    // Address - only digits
+                    1 if c.is_ascii_digit() => self.write_address.push(c),
+                    // Data - hex digits
+                    2 if c.is_ascii_hexdigit() => self.write_data.push(c),
+                    // Access password - hex digits
+                    3 if c.is_ascii_hexdigit() => self.access_password.push(c),
                     _ => {}
                 }
             }
-            AppState::Password => {
-                // Password - hex digits
-                if c.is_ascii_hexdigit() {
-                    self.password.push(c);
-                }
+            // Lock access password - hex digits
+            AppState::Lock if self.active_input_field == 2 && c.is_ascii_hexdigit() => {
+                self.access_password.push(c);
             }
-            AppState::Raw => {
-                // Raw input - hex digits and spaces allowed (we'll ignore spaces)
-                if c.is_ascii_hexdigit() {
-                    self.raw_input.push(c);
-                }
+            // Password - hex digits
+            AppState::Password if c.is_ascii_hexdigit() => self.password.push(c),
+            // Kill password, TID prefix and confirmation - hex digits
+            AppState::Kill if c.is_ascii_hexdigit() => match self.active_input_field {
+                0 => self.kill_password.push(c),
+                1 => self.kill_tid.push(c),
+                _ => self.kill_confirm.push(c),
+            },
+            // Raw input - hex digits
+            AppState::Raw if self.active_input_field == 0 && c.is_ascii_hexdigit() => {
+                self.raw_input.push(c);
             }
             _ => {}
         }
@@ -271,6 +386,9 @@ impl App {
                 2 => {
                     self.read_word_count.pop();
                 }
+                3 => {
+                    self.access_password.pop();
+                }
                 _ => {}
             },
             AppState::Write => match self.active_input_field {
@@ -280,18 +398,42 @@ impl App {
                 2 => {
                     self.write_data.pop();
                 }
+                3 => {
+                    self.access_password.pop();
+                }
                 _ => {}
             },
+            AppState::Lock if self.active_input_field == 2 => {
+                self.access_password.pop();
+            }
             AppState::Password => {
                 self.password.pop();
             }
-            AppState::Raw => {
+            AppState::Kill => {
+                match self.active_input_field {
+                    0 => self.kill_password.pop(),
+                    1 => self.kill_tid.pop(),
+                    _ => self.kill_confirm.pop(),
+                };
+            }
+            AppState::Raw if self.active_input_field == 0 => {
                 self.raw_input.pop();
             }
             _ => {}
         }
     }
 
+    /// Toggle the raw command option under the cursor
+    pub fn toggle_raw_option(&mut self) {
+        match self.active_input_field {
+            1 => self.raw_options.framed = !self.raw_options.framed,
+            2 => {
+                self.raw_options.collect_continuations = !self.raw_options.collect_continuations;
+            }
+            _ => {}
+        }
+    }
+
     // Cycle through memory bank options
     pub fn cycle_read_bank(&mut self) {
         self.read_bank = match self.read_bank {
@@ -391,6 +533,64 @@ impl App {
             LockAction::NotWriteable => LockAction::SecureWriteable,
         };
     }
+
+    /// Step the focused row of the settings form forwards or backwards
+    pub fn adjust_setting(&mut self, forward: bool) {
+        let rf = &mut self.reader_settings.rf;
+        let gen2 = &mut self.reader_settings.gen2;
+        match self.active_input_field {
+            0 => {
+                rf.tx_power_dbm = if forward {
+                    rf.tx_power_dbm.saturating_add(1)
+                } else {
+                    rf.tx_power_dbm.saturating_sub(1)
+                }
+                .clamp(RfSettings::MIN_TX_POWER_DBM, RfSettings::MAX_TX_POWER_DBM);
+            }
+            1 => {
+                rf.region = cycle(&Region::ALL, rf.region, forward);
+                if rf.channel >= rf.region.channel_count() {
+                    rf.channel = 0;
+                }
+            }
+            // Hopping comes before the first channel and after the last one
+            2 => {
+                let last = rf.region.channel_count() - 1;
+                (rf.hopping, rf.channel) = match (rf.hopping, forward) {
+                    (true, true) => (false, 0),
+                    (true, false) => (false, last),
+                    (false, true) if rf.channel == last => (true, 0),
+                    (false, true) => (false, rf.channel + 1),
+                    (false, false) if rf.channel == 0 => (true, 0),
+                    (false, false) => (false, rf.channel - 1),
+                };
+            }
+            3 => gen2.session = cycle(&Session::ALL, gen2.session, forward),
+            4 => gen2.target = cycle(&InventoryTarget::ALL, gen2.target, forward),
+            5 => {
+                gen2.q = if forward {
+                    (gen2.q + 1).min(Gen2Settings::MAX_Q)
+                } else {
+                    gen2.q.saturating_sub(1)
+                };
+            }
+            _ => self.save_settings = !self.save_settings,
+        }
+    }
+}
+
+/// Rows of the settings form: power, region, channel, session, target, Q and save
+pub const SETTINGS_FIELDS: usize = 7;
+
+/// Next or previous entry of `all` after `current`, wrapping around
+fn cycle<T: Copy + PartialEq>(all: &[T], current: T, forward: bool) -> T {
+    let index = all.iter().position(|item| *item == current).unwrap_or(0);
+    let next = if forward {
+        (index + 1) % all.len()
+    } else {
+        (index + all.len() - 1) % all.len()
+    };
+    all[next]
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -0,0 +1,71 @@
+use crate::tui::App;
+use api::api::uhf_rfid_api::UhfRfidApi;
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::prelude::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+fn field_style(app: &App, field: usize) -> Style {
+    if app.active_input_field == field {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::White)
+    }
+}
+
+pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Target
+            Constraint::Length(3), // Kill password
+            Constraint::Length(3), // TID prefix
+            Constraint::Length(3), // Confirmation
+            Constraint::Min(0),    // Warning
+        ])
+        .margin(1)
+        .split(area);
+
+    // Draw form
+    let form_block = Block::default()
+        .borders(Borders::ALL)
+        .title("Kill Tag")
+        .border_style(Style::default().fg(Color::Red));
+    f.render_widget(form_block, area);
+
+    // Target tag
+    let target = match &app.target_epc {
+        Some(epc) => format!("Target EPC: {}", UhfRfidApi::hex_to_ascii(epc)),
+        None => "Target EPC: none (press Esc and pick a tag with [1-9])".to_string(),
+    };
+    f.render_widget(
+        Paragraph::new(target).style(Style::default().fg(Color::Cyan)),
+        chunks[0],
+    );
+
+    let password = Paragraph::new(format!("Kill password (hex): {}", app.kill_password))
+        .style(field_style(app, 0));
+    f.render_widget(password, chunks[1]);
+
+    let tid = Paragraph::new(format!("TID prefix (hex, optional): {}", app.kill_tid))
+        .style(field_style(app, 1));
+    f.render_widget(tid, chunks[2]);
+
+    let confirm = Paragraph::new(format!(
+        "Type the target EPC to confirm: {}",
+        app.kill_confirm
+    ))
+    .style(field_style(app, 2));
+    f.render_widget(confirm, chunks[3]);
+
+    // Warning
+    let warning = Paragraph::new(
+        "⚠️  WARNING: Killing a tag is PERMANENT! The tag will never respond again. ⚠️\n\n\
+         The kill is only sent if the target answers with its EPC (and TID prefix, if given)\n\
+         and the kill password is not all zeros. Tab to navigate, Enter to kill.",
+    )
+    .style(Style::default().fg(Color::Red));
+    f.render_widget(warning, chunks[4]);
+}
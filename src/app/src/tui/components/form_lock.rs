@@ -1,4 +1,5 @@
 use crate::tui::App;
+use protocl::types::LockPayload;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::prelude::{Color, Modifier, Style};
 use ratatui::widgets::{Block, Borders, Paragraph};
@@ -10,6 +11,7 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
         .constraints([
             Constraint::Length(3), // Bank
             Constraint::Length(3), // Action
+            Constraint::Length(3), // Access password
             Constraint::Min(0),    // Warning
         ])
         .margin(1)
@@ -54,11 +56,31 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
     .style(action_style);
     f.render_widget(action, chunks[1]);
 
+    // Access password input
+    let password_style = if app.active_input_field == 2 {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::White)
+    };
+
+    let password = Paragraph::new(format!(
+        "Access Password (hex, optional): {}",
+        app.access_password
+    ))
+    .style(password_style);
+    f.render_widget(password, chunks[2]);
+
     // Warning
-    let warning = Paragraph::new(
-        "⚠️  WARNING: Lock operations can be PERMANENT depending on settings! ⚠️\n\n\
-         Some lock actions cannot be reversed. Proceed with caution.",
-    )
+    let payload = LockPayload::new().with_action(app.lock_bank, app.lock_action);
+    let warning = Paragraph::new(format!(
+        "Lock payload: {:06X} ({payload})\n\n\
+         ⚠️  WARNING: Lock operations can be PERMANENT depending on settings! ⚠️\n\n\
+         Some lock actions cannot be reversed. Proceed with caution.\n\n\
+         Once the tag has a non-zero access password, locking needs that password.",
+        payload.bits()
+    ))
     .style(Style::default().fg(Color::Red));
-    f.render_widget(warning, chunks[2]);
+    f.render_widget(warning, chunks[3]);
 }
@@ -1,16 +1,29 @@
 use crate::tui::App;
 use api::api::uhf_rfid_api::UhfRfidApi;
+use protocl::dissect;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::prelude::{Color, Modifier, Style};
 use ratatui::widgets::{Block, Borders, Paragraph};
 use ratatui::Frame;
 
+fn field_style(app: &App, field: usize) -> Style {
+    if app.active_input_field == field {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::White)
+    }
+}
+
 pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3), // Input
-            Constraint::Length(5), // Instructions
+            Constraint::Length(2), // Input
+            Constraint::Length(1), // Framing
+            Constraint::Length(2), // Continuations
+            Constraint::Length(4), // Instructions
             Constraint::Min(0),    // Response
         ])
         .margin(1)
@@ -24,38 +37,75 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
     f.render_widget(form_block, area);
 
     // Input
-    let input_style = Style::default()
-        .fg(Color::Yellow)
-        .add_modifier(Modifier::BOLD);
-    let input = Paragraph::new(format!("Command (hex): {}", app.raw_input)).style(input_style);
+    let input =
+        Paragraph::new(format!("Command (hex): {}", app.raw_input)).style(field_style(app, 0));
     f.render_widget(input, chunks[0]);
 
+    // Options
+    let framed = if app.raw_options.framed { "[X]" } else { "[ ]" };
+    let framing = Paragraph::new(format!(
+        "{framed} Add the length byte and split into reports (Space to toggle)"
+    ))
+    .style(field_style(app, 1));
+    f.render_widget(framing, chunks[1]);
+
+    let collect = if app.raw_options.collect_continuations {
+        "[X]"
+    } else {
+        "[ ]"
+    };
+    let continuations = Paragraph::new(format!(
+        "{collect} Collect continuation reports (Space to toggle)"
+    ))
+    .style(field_style(app, 2));
+    f.render_widget(continuations, chunks[2]);
+
     // Instructions/warning
     let instructions = Paragraph::new(
         "WARNING: Sending raw commands may damage the device or tags.\n\
-         Enter even-length hex string (e.g., 01020304). Press Enter to send. ESC to go back.",
+         Enter even-length hex string without the length byte (e.g., 0257).\n\
+         Tab to navigate, Enter to send, ESC to go back.",
     )
     .style(Style::default().fg(Color::Red));
-    f.render_widget(instructions, chunks[1]);
+    f.render_widget(instructions, chunks[3]);
 
     // Response
-    if !app.raw_response.is_empty() {
-        let hex = UhfRfidApi::hex_to_ascii(&app.raw_response);
+    let reply = &app.raw_reply;
+    if !reply.reports.is_empty() {
+        let reports: Vec<_> = reply
+            .reports
+            .iter()
+            .enumerate()
+            .map(|(index, report)| {
+                format!(
+                    "Report {} ({} bytes): {}",
+                    index + 1,
+                    report.len(),
+                    UhfRfidApi::hex_to_ascii(report)
+                )
+            })
+            .collect();
+        let frame = reply.frame.as_ref().unwrap_or(&reply.reports[0]);
         let mut ascii = String::new();
-        for b in &app.raw_response {
+        for b in frame {
             if b.is_ascii() && !b.is_ascii_control() {
                 ascii.push(*b as char);
             } else {
                 ascii.push('.');
             }
         }
-        let resp = Paragraph::new(format!("Response (hex): {hex}\nResponse (ASCII): {ascii}"))
-            .style(Style::default().fg(Color::Green))
-            .block(
-                Block::default()
-                    .borders(Borders::TOP)
-                    .title("Last Response"),
-            );
-        f.render_widget(resp, chunks[2]);
+        let hex = UhfRfidApi::hex_to_ascii(frame);
+        let dissection = dissect::dissect_response(frame);
+        let resp = Paragraph::new(format!(
+            "{}\nResponse (hex): {hex}\nResponse (ASCII): {ascii}\n\n{dissection}",
+            reports.join("\n")
+        ))
+        .style(Style::default().fg(Color::Green))
+        .block(
+            Block::default()
+                .borders(Borders::TOP)
+                .title("Last Response"),
+        );
+        f.render_widget(resp, chunks[4]);
     }
 }
@@ -1,11 +1,15 @@
 use crate::tui::App;
+use api::api::tid::Tid;
 use api::api::uhf_rfid_api::UhfRfidApi;
+use api::api::user_memory::{UserData, UserDataError};
+use protocl::epc_bank::EpcMemory;
 use protocl::types::MemoryBank;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::prelude::{Color, Line, Modifier, Span, Style};
 use ratatui::widgets::{Block, Borders, Paragraph};
 use ratatui::Frame;
 
+#[allow(clippy::too_many_lines)]
 pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -13,6 +17,7 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
             Constraint::Length(3), // Bank
             Constraint::Length(3), // Address
             Constraint::Length(3), // Word count
+            Constraint::Length(3), // Access password
             Constraint::Min(0),    // Results
         ])
         .margin(1)
@@ -100,6 +105,22 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
         Paragraph::new(format!("Word Count: {}", app.read_word_count)).style(word_count_style);
     f.render_widget(word_count, chunks[2]);
 
+    // Access password input
+    let password_style = if app.active_input_field == 3 {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::White)
+    };
+
+    let password = Paragraph::new(format!(
+        "Access Password (hex, optional): {}",
+        app.access_password
+    ))
+    .style(password_style);
+    f.render_widget(password, chunks[3]);
+
     // Results panel (always visible so the user gets feedback even when no bytes are returned)
     if app.read_all_banks() {
         // Show per-bank sections and a combined length
@@ -128,13 +149,16 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
             text.push_str("No data yet. Press Enter to read.\n");
         }
         text.push_str(&section("EPC", &app.result_epc));
+        text.push_str(&epc_summary(app));
         text.push_str(&section("TID", &app.result_tid));
+        text.push_str(&tid_summary(app));
         text.push_str(&section("USER", &app.result_user));
+        text.push_str(&user_summary(app));
 
         let results = Paragraph::new(text)
             .style(Style::default().fg(Color::Green))
             .block(Block::default().borders(Borders::TOP).title(results_title));
-        f.render_widget(results, chunks[3]);
+        f.render_widget(results, chunks[4]);
     } else {
         let results_title = format!("Results from {:?}", app.read_bank);
 
@@ -166,10 +190,72 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
             )
         };
 
-        let results_text = format!("{header}\n    Hex: {hex_data}\n    ASCII: {ascii_data}");
+        let mut results_text = format!("{header}\n    Hex: {hex_data}\n    ASCII: {ascii_data}\n");
+        if was_read && app.read_bank == MemoryBank::Epc {
+            results_text.push_str(&epc_summary(app));
+        }
+        if was_read && app.read_bank == MemoryBank::Tid {
+            results_text.push_str(&tid_summary(app));
+        }
+        if was_read && app.read_bank == MemoryBank::User {
+            results_text.push_str(&user_summary(app));
+        }
         let results = Paragraph::new(results_text)
             .style(Style::default().fg(Color::Green))
             .block(Block::default().borders(Borders::TOP).title(results_title));
-        f.render_widget(results, chunks[3]);
+        f.render_widget(results, chunks[4]);
+    }
+}
+
+/// PC word and stored CRC decoded from the EPC result, if it was read from word 0
+fn epc_summary(app: &App) -> String {
+    if app.read_address.parse::<u32>().unwrap_or(0) != 0 {
+        return String::new();
+    }
+    EpcMemory::decode(&app.result_epc)
+        .map(|memory| {
+            let lines: Vec<String> = memory
+                .fields()
+                .iter()
+                .map(|(label, value)| format!("    {label}: {value}\n"))
+                .collect();
+            lines.concat()
+        })
+        .unwrap_or_default()
+}
+
+/// Chip details decoded from the TID result, if it was read from word 0
+fn tid_summary(app: &App) -> String {
+    if app.read_address.parse::<u32>().unwrap_or(0) != 0 {
+        return String::new();
+    }
+    Tid::decode(&app.result_tid)
+        .map(|tid| {
+            let lines: Vec<String> = tid
+                .fields()
+                .iter()
+                .map(|(label, value)| format!("    {label}: {value}\n"))
+                .collect();
+            lines.concat()
+        })
+        .unwrap_or_default()
+}
+
+/// ISO/IEC 15962 data sets decoded from the USER result, if it was read from word 0
+fn user_summary(app: &App) -> String {
+    if app.read_address.parse::<u32>().unwrap_or(0) != 0 {
+        return String::new();
+    }
+    match UserData::decode(&app.result_user) {
+        Ok(user_data) => {
+            let lines: Vec<String> = user_data
+                .fields()
+                .iter()
+                .map(|(label, value)| format!("    {label}: {value}\n"))
+                .collect();
+            lines.concat()
+        }
+        Err(e @ UserDataError::Truncated(_)) => format!("    {e}; read more words\n"),
+        Err(_) => String::new(),
     }
 }
@@ -0,0 +1,69 @@
+use crate::tui::App;
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::prelude::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Power
+            Constraint::Length(2), // Region
+            Constraint::Length(2), // Channel
+            Constraint::Length(2), // Session
+            Constraint::Length(2), // Target
+            Constraint::Length(2), // Q
+            Constraint::Length(2), // Save
+            Constraint::Min(0),    // Instructions
+        ])
+        .margin(1)
+        .split(area);
+
+    // Draw form
+    let form_block = Block::default()
+        .borders(Borders::ALL)
+        .title("Reader Settings")
+        .border_style(Style::default().fg(Color::Blue));
+    f.render_widget(form_block, area);
+
+    let rf = app.reader_settings.rf;
+    let gen2 = app.reader_settings.gen2;
+    let channel = if rf.hopping {
+        format!("Hopping over {} channels", rf.region.channel_count())
+    } else {
+        let khz = rf.region.channel_khz(rf.channel).unwrap_or(0);
+        format!("{} ({}.{:03} MHz)", rf.channel, khz / 1000, khz % 1000)
+    };
+    let rows = [
+        format!("Transmit Power: {} dBm", rf.tx_power_dbm),
+        format!("Region: {}", rf.region),
+        format!("Channel: {channel}"),
+        format!("Session: {:?}", gen2.session),
+        format!("Target: {:?}", gen2.target),
+        format!("Q: {}", gen2.q),
+        format!(
+            "Save on Reader: {}",
+            if app.save_settings { "Yes" } else { "No" }
+        ),
+    ];
+
+    for (i, row) in rows.into_iter().enumerate() {
+        let style = if app.active_input_field == i {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        f.render_widget(Paragraph::new(row).style(style), chunks[i]);
+    }
+
+    // Instructions
+    let instructions = Paragraph::new(
+        "←/→ to change, Tab to navigate, Enter to apply.\n\n\
+         Where several readers cover the same tags, use session S2 or S3 and lower the power.",
+    )
+    .style(Style::default().fg(Color::Gray));
+    f.render_widget(instructions, chunks[7]);
+}
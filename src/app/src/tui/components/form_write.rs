@@ -11,6 +11,7 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
             Constraint::Length(3), // Bank
             Constraint::Length(3), // Address
             Constraint::Length(3), // Data
+            Constraint::Length(3), // Access password
             Constraint::Min(0),    // Instructions
         ])
         .margin(1)
@@ -63,11 +64,27 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
     let data = Paragraph::new(format!("Data (hex): {}", app.write_data)).style(data_style);
     f.render_widget(data, chunks[2]);
 
+    // Access password input
+    let password_style = if app.active_input_field == 3 {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::White)
+    };
+
+    let password = Paragraph::new(format!(
+        "Access Password (hex, optional): {}",
+        app.access_password
+    ))
+    .style(password_style);
+    f.render_widget(password, chunks[3]);
+
     // Instructions
     let instructions = Paragraph::new(
         "Enter hex data to write to the tag (e.g., 01020304).\n\n\
          Warning: Writing to the wrong memory bank or address may permanently damage the tag.",
     )
     .style(Style::default().fg(Color::Red));
-    f.render_widget(instructions, chunks[3]);
+    f.render_widget(instructions, chunks[4]);
 }
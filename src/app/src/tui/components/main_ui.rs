@@ -2,8 +2,8 @@
 
 use crate::tui::app::{App, AppState};
 use crate::tui::components::{
-    form_device_action, form_lock, form_password, form_raw, form_read, form_write, menu_main,
-    panel_device_info,
+    form_device_action, form_kill, form_lock, form_password, form_raw, form_read, form_settings,
+    form_write, menu_main, panel_device_info,
 };
 use ratatui::prelude::*;
 use ratatui::style::{Color, Modifier, Style};
@@ -45,8 +45,10 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         AppState::Write => form_write::draw(f, app, chunks[1]),
         AppState::Lock => form_lock::draw(f, app, chunks[1]),
         AppState::Password => form_password::draw(f, app, chunks[1]),
+        AppState::Kill => form_kill::draw(f, app, chunks[1]),
         AppState::Action => form_device_action::draw(f, app, chunks[1]),
         AppState::Raw => form_raw::draw(f, app, chunks[1]),
+        AppState::Settings => form_settings::draw(f, app, chunks[1]),
         AppState::Test => {
             let block = Paragraph::new("Press Enter to run the built-in test.")
                 .style(Style::default().fg(Color::Yellow))
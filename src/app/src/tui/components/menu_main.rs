@@ -46,15 +46,27 @@ pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
                 Some(bank) => format!(" [{bank}]"),
                 None => String::new(),
             };
-            ListItem::new(format!("{}. {}{}", i + 1, tag.epc, bank_info))
-                .style(Style::default().fg(Color::Green))
+            let uri = match &tag.uri {
+                Some(uri) => format!("\n   {uri}"),
+                None => String::new(),
+            };
+            if app.target_index() == Some(i) {
+                ListItem::new(format!("{}. {}{} (target){uri}", i + 1, tag.epc, bank_info)).style(
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                ListItem::new(format!("{}. {}{}{uri}", i + 1, tag.epc, bank_info))
+                    .style(Style::default().fg(Color::Green))
+            }
         })
         .collect();
 
     let tags = List::new(tag_items).block(
         Block::default()
             .borders(Borders::ALL)
-            .title("Tags in Range"),
+            .title("Tags in Range ([1-9] target a tag)"),
     );
 
     f.render_widget(tags, chunks[1]);
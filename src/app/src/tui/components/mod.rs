@@ -1,8 +1,10 @@
 mod form_device_action;
+mod form_kill;
 mod form_lock;
 mod form_password;
 mod form_raw;
 mod form_read;
+mod form_settings;
 mod form_write;
 pub mod main_ui;
 mod menu_main;
@@ -1,7 +1,7 @@
 use crate::tui::App;
 use ratatui::layout::{Alignment, Rect};
 use ratatui::prelude::{Color, Style};
-use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
 use ratatui::Frame;
 
 pub fn draw_device_info(f: &mut Frame, app: &mut App, area: Rect) {
@@ -30,14 +30,49 @@ pub fn draw_device_info(f: &mut Frame, app: &mut App, area: Rect) {
             device.get_out_endpoint(),
             if device.is_connected() { "Yes" } else { "No" }
         );
+        let identity_text = match &info.identity {
+            Some(identity) => format!(
+                "Firmware: {} (hardware rev {}), max power {} dBm\n\
+                 Regions: {}\n\
+                 Commands: {}",
+                identity.firmware,
+                identity.hardware_revision,
+                identity.max_tx_power_dbm,
+                identity
+                    .regions
+                    .iter()
+                    .map(|region| region.label())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                identity
+                    .features
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            None => "Firmware: unknown (reader did not report its capabilities)".to_string(),
+        };
+        let info_text = format!("{info_text}\n{identity_text}");
 
         let info_widget = Paragraph::new(info_text)
             .style(Style::default().fg(Color::White))
             .block(Block::default().borders(Borders::NONE))
-            .alignment(Alignment::Left);
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: true });
 
         let inner_area = device_block.inner(area);
         f.render_widget(info_widget, inner_area);
+    } else if app.auto_refresh.is_some() {
+        let busy = Paragraph::new(format!(
+            "Auto-refresh running: {} tags in range.\nPress f to stop and use the device.",
+            app.present_tags.len()
+        ))
+        .style(Style::default().fg(Color::Yellow))
+        .alignment(Alignment::Center);
+
+        let inner_area = device_block.inner(area);
+        f.render_widget(busy, inner_area);
     } else {
         let no_device = Paragraph::new("No device connected.")
             .style(Style::default().fg(Color::Red))
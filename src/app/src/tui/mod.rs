@@ -11,6 +11,7 @@ use crossterm::{event, ExecutableCommand};
 use ratatui::prelude::*;
 
 use api::api::error::RfidError;
+use api::api::inventory_watch::{InventoryEvent, InventoryWatch, WatchConfig};
 use api::api::uhf_rfid_api::UhfRfidApi;
 use api::rfid_device::usb_device::UsbDevice;
 
@@ -18,9 +19,11 @@ mod app;
 mod components;
 
 pub use app::{App, AppState, MenuItem, PendingConfirm, TagData};
+use protocl::interface::{RawReply, RetryPolicy};
 use protocl::types::{DeviceAction, MemoryBank};
+use tracing::{error, warn};
 
-pub fn run_tui() -> Result<(), RfidError> {
+pub fn run_tui(policy: RetryPolicy) -> Result<(), RfidError> {
     // Set up terminal
     enable_raw_mode()?;
     stdout().execute(EnterAlternateScreen)?;
@@ -30,7 +33,7 @@ pub fn run_tui() -> Result<(), RfidError> {
     let app = App::new();
 
     // Run the application
-    let result = run_app(&mut terminal, app);
+    let result = run_app(&mut terminal, app, policy);
 
     // Restore terminal
     disable_raw_mode()?;
@@ -39,14 +42,21 @@ pub fn run_tui() -> Result<(), RfidError> {
 
     // Handle result
     if let Err(err) = result {
+        error!(error = %err, "TUI stopped");
         println!("Error: {err}");
     }
 
     Ok(())
 }
 
-fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<(), RfidError> {
+#[allow(clippy::too_many_lines)]
+fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    mut app: App,
+    policy: RetryPolicy,
+) -> Result<(), RfidError> {
     // Try to connect to a device
+    app.api = UhfRfidApi::new(policy);
     match UsbDevice::new() {
         Ok(device) => {
             app.device = Some(device);
@@ -56,6 +66,7 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<(), R
             update_inventory(&mut app);
         }
         Err(e) => {
+            warn!(error = %e, "failed to connect to device");
             format!("Failed to connect to device: {e}").clone_into(&mut app.status_message);
         }
     }
@@ -64,6 +75,7 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<(), R
     let mut last_tick = Instant::now();
 
     loop {
+        poll_auto_refresh(&mut app);
         terminal.draw(|f| components::main_ui::draw(f, &mut app))?;
 
         let timeout = tick_rate
@@ -74,6 +86,17 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<(), R
             && let Event::Key(key) = event::read()?
             && key.kind == KeyEventKind::Press
         {
+            // Every other operation needs the device back from the continuous inventory
+            let keeps_auto_refresh = matches!(key.code, KeyCode::Esc | KeyCode::Up | KeyCode::Down)
+                || (matches!(key.code, KeyCode::Char('1'..='9')) && app.state == AppState::Main)
+                || (key.code == KeyCode::Char('f') && app.state == AppState::Main)
+                || (key.code == KeyCode::Enter
+                    && app.state == AppState::Main
+                    && app.selected_menu_item == MenuItem::AutoRefresh);
+            if !keeps_auto_refresh {
+                stop_auto_refresh(&mut app);
+            }
+
             match key.code {
                 KeyCode::Esc => {
                     app.state = AppState::Main;
@@ -87,10 +110,14 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<(), R
                     }
                     app.state = AppState::Main;
                 }
-                KeyCode::Char('i') => {
-                    if app.state == AppState::Main {
-                        update_inventory(&mut app);
-                    }
+                KeyCode::Char('i') if app.state == AppState::Main => {
+                    update_inventory(&mut app);
+                }
+                KeyCode::Char('f') if app.state == AppState::Main => {
+                    toggle_auto_refresh(&mut app);
+                }
+                KeyCode::Char(c @ '1'..='9') if app.state == AppState::Main => {
+                    app.toggle_target(usize::from(c as u8 - b'1'));
                 }
                 KeyCode::Char('r') => {
                     if app.state == AppState::Main {
@@ -123,20 +150,28 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<(), R
                         handle_password(&mut app);
                     }
                 }
-                KeyCode::Char('a') => {
+                KeyCode::Char('k') if app.state == AppState::Main => {
+                    app.state = AppState::Kill;
+                    app.active_input_field = 0;
+                }
+                KeyCode::Char('a') if app.state == AppState::Main => {
+                    app.state = AppState::Action;
+                    app.active_input_field = 0;
+                }
+                // 'c' is also a hex digit, so leave it to the input fields of other forms
+                KeyCode::Char('c') if matches!(app.state, AppState::Main | AppState::Settings) => {
                     if app.state == AppState::Main {
-                        app.state = AppState::Action;
-                        app.active_input_field = 0;
+                        open_settings(&mut app);
+                    } else if app.state == AppState::Settings {
+                        handle_settings(&mut app);
                     }
                 }
                 KeyCode::Char('m') => {
                     if app.state == AppState::Main {
                         app.state = AppState::Raw;
                         app.active_input_field = 0;
-                    } else if app.state == AppState::Raw
-                        && let Err(e) = handle_raw(&mut app)
-                    {
-                        format!("Raw command failed: {e}").clone_into(&mut app.status_message);
+                    } else if app.state == AppState::Raw {
+                        handle_raw(&mut app);
                     }
                 }
                 KeyCode::Char('t') => {
@@ -148,6 +183,7 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<(), R
                         format!("Test failed: {e}").clone_into(&mut app.status_message);
                     }
                 }
+                KeyCode::Char(' ') if app.state == AppState::Raw => app.toggle_raw_option(),
                 KeyCode::Char(' ') => {
                     // Toggle action flags based on the selected field
                     match app.active_input_field {
@@ -175,6 +211,7 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<(), R
                         // Handle menu selection based on the selected item
                         match app.selected_menu_item {
                             MenuItem::Inventory => update_inventory(&mut app),
+                            MenuItem::AutoRefresh => toggle_auto_refresh(&mut app),
                             // Initialize focus to the bank field when entering a Read form
                             MenuItem::ReadTag => {
                                 app.state = AppState::Read;
@@ -189,10 +226,15 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<(), R
                                 app.active_input_field = 0;
                             }
                             MenuItem::SetPassword => app.state = AppState::Password,
+                            MenuItem::KillTag => {
+                                app.state = AppState::Kill;
+                                app.active_input_field = 0;
+                            }
                             MenuItem::DeviceAction => {
                                 app.state = AppState::Action;
                                 app.active_input_field = 0;
                             }
+                            MenuItem::ReaderSettings => open_settings(&mut app),
                             MenuItem::RawCommand => {
                                 app.state = AppState::Raw;
                             }
@@ -213,16 +255,14 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<(), R
                             AppState::Write => handle_write(&mut app),
                             AppState::Lock => handle_lock(&mut app),
                             AppState::Password => handle_password(&mut app),
+                            AppState::Kill => handle_kill(&mut app),
+                            AppState::Settings => handle_settings(&mut app),
                             AppState::Action => {
                                 if let Err(e) = handle_action(&mut app) {
                                     format!("Action failed: {e}").clone_into(&mut app.status_message);
                                 }
                             }
-                            AppState::Raw => {
-                                if let Err(e) = handle_raw(&mut app) {
-                                    format!("Raw command failed: {e}").clone_into(&mut app.status_message);
-                                }
-                            }
+                            AppState::Raw => handle_raw(&mut app),
                             AppState::Test => {
                                 if let Err(e) = handle_test(&mut app) {
                                     format!("Test failed: {e}").clone_into(&mut app.status_message);
@@ -257,7 +297,9 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<(), R
                 }
                 KeyCode::Right => {
                     // Cycle options with Right arrow when the focus is on a selectable row
-                    if app.active_input_field == 0 {
+                    if app.state == AppState::Settings {
+                        app.adjust_setting(true);
+                    } else if app.active_input_field == 0 {
                         match app.state {
                             AppState::Read => app.cycle_read_bank(),
                             AppState::Write => app.cycle_write_bank(),
@@ -271,7 +313,9 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<(), R
                 }
                 KeyCode::Left => {
                     // Cycle options backwards with Left arrow
-                    if app.active_input_field == 0 {
+                    if app.state == AppState::Settings {
+                        app.adjust_setting(false);
+                    } else if app.active_input_field == 0 {
                         match app.state {
                             AppState::Read => app.prev_read_bank(),
                             AppState::Write => app.prev_write_bank(),
@@ -295,18 +339,12 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> Result<(), R
 }
 
 fn update_inventory(app: &mut App) {
+    let api = app.api;
     "Scanning for tags...".clone_into(&mut app.status_message);
     if let Some(ref device) = app.device {
-        match UhfRfidApi::inventory(device) {
+        match api.inventory(device) {
             Ok(tags) => {
-                app.tags = tags
-                    .iter()
-                    .map(|tag| TagData {
-                        epc: tag.to_string(),
-                        data: Vec::new(),
-                        data_bank: None,
-                    })
-                    .collect();
+                app.tags = tags.iter().map(TagData::from).collect();
 
                 if app.tags.is_empty() {
                     "No tags found.".clone_into(&mut app.status_message);
@@ -323,12 +361,100 @@ fn update_inventory(app: &mut App) {
     }
 }
 
+fn toggle_auto_refresh(app: &mut App) {
+    let api = app.api;
+    if app.auto_refresh.is_some() {
+        stop_auto_refresh(app);
+    } else if let Some(device) = app.device.take() {
+        app.present_tags.clear();
+        app.tags.clear();
+        app.auto_refresh = Some(InventoryWatch::start(api, device, WatchConfig::default()));
+        "Auto-refresh on. Press f to stop.".clone_into(&mut app.status_message);
+    } else {
+        "No device connected.".clone_into(&mut app.status_message);
+    }
+}
+
+fn stop_auto_refresh(app: &mut App) {
+    let Some(watch) = app.auto_refresh.take() else {
+        return;
+    };
+    poll_events(app, &watch);
+    match watch.stop() {
+        Ok(device) => {
+            app.device = Some(device);
+            format!(
+                "Auto-refresh stopped with {} tags in range.",
+                app.present_tags.len()
+            )
+            .clone_into(&mut app.status_message);
+        }
+        Err(e) => {
+            format!("Auto-refresh failed: {e}").clone_into(&mut app.status_message);
+        }
+    }
+}
+
+fn poll_auto_refresh(app: &mut App) {
+    if let Some(watch) = app.auto_refresh.take() {
+        poll_events(app, &watch);
+        app.auto_refresh = Some(watch);
+    }
+}
+
+fn poll_events(app: &mut App, watch: &InventoryWatch<UsbDevice>) {
+    let mut changed = false;
+    for event in watch.try_events() {
+        match event {
+            InventoryEvent::TagArrived(state) => {
+                format!("Tag arrived: {}", state.tag.epc).clone_into(&mut app.status_message);
+                app.present_tags.push(state);
+            }
+            InventoryEvent::TagSeen(state) => {
+                if let Some(present) = app
+                    .present_tags
+                    .iter_mut()
+                    .find(|present| present.tag.epc == state.tag.epc)
+                {
+                    *present = state;
+                }
+            }
+            InventoryEvent::TagDeparted(state) => {
+                format!("Tag departed: {}", state.tag.epc).clone_into(&mut app.status_message);
+                app.present_tags
+                    .retain(|present| present.tag.epc != state.tag.epc);
+            }
+            InventoryEvent::Error(e) => {
+                format!("Inventory failed: {e}").clone_into(&mut app.status_message);
+            }
+        }
+        changed = true;
+    }
+
+    if changed {
+        app.tags = app
+            .present_tags
+            .iter()
+            .map(|state| TagData::from(&state.tag))
+            .collect();
+    }
+}
+
+#[allow(clippy::too_many_lines)]
 fn handle_read(app: &mut App) {
+    let api = app.api;
     "Reading tag...".clone_into(&mut app.status_message);
+    let Some(access) = app.tag_access() else {
+        "Invalid access password format. Use hex format (e.g. 12345678)."
+            .clone_into(&mut app.status_message);
+        return;
+    };
+    // Results are shown on the targeted tag, or on the first one
+    let shown = app.target_index().unwrap_or(0);
 
     if let Some(ref device) = app.device {
         // Ensure at least one tag is present so the device has a target
-        match UhfRfidApi::inventory(device) {
+        match api.inventory(device) {
             Ok(tags) => {
                 if tags.is_empty() {
                         "No tags in range. Please place a tag near the reader.".clone_into(&mut app.status_message);
@@ -359,7 +485,9 @@ fn handle_read(app: &mut App) {
             app.result_user.clear();
 
             for bank in &banks {
-                match UhfRfidApi::read(device, *bank, address, word_count) {
+                match api.with_access(device, &access, |device| {
+                    api.read(device, *bank, address, word_count)
+                }) {
                     Ok(data) => {
                         // Save per-bank results and reuse stored vec to avoid extra cloning
                         let stored = match bank {
@@ -370,9 +498,9 @@ fn handle_read(app: &mut App) {
                         };
                         *stored = data;
 
-                        // Store data for the EPC bank in the first tag (for a quick view)
+                        // Store data for the EPC bank in the shown tag (for a quick view)
                         if *bank == MemoryBank::Epc
-                            && let Some(tag) = app.tags.first_mut()
+                            && let Some(tag) = app.tags.get_mut(shown)
                         {
                             tag.data.clone_from(stored);
                             tag.data_bank = Some(*bank);
@@ -381,7 +509,7 @@ fn handle_read(app: &mut App) {
                     }
                     Err(e) => {
                         success = false;
-                        error_msg = e.to_string();
+                        error_msg = format!("{bank:?} bank: {e}");
                         break;
                     }
                 }
@@ -400,7 +528,9 @@ fn handle_read(app: &mut App) {
         } else {
             // Read a specific bank
             let bank = app.read_bank;
-            match UhfRfidApi::read(device, bank, address, word_count) {
+            match api.with_access(device, &access, |device| {
+                api.read(device, bank, address, word_count)
+            }) {
                 Ok(data) => {
                     format!("Read {bank:?} successful!").clone_into(&mut app.status_message);
                     app.result_data.clone_from(&data);
@@ -430,7 +560,7 @@ fn handle_read(app: &mut App) {
                     app.mark_last_read(bank);
 
                     // Update tag data if it matches one of our tags
-                    if let Some(tag) = app.tags.first_mut() {
+                    if let Some(tag) = app.tags.get_mut(shown) {
                         tag.data = data;
                         tag.data_bank = Some(bank);
                     }
@@ -446,19 +576,29 @@ fn handle_read(app: &mut App) {
 }
 
 fn handle_write(app: &mut App) {
+    let api = app.api;
     let Some(device) = app.device.as_ref() else {
         "No device connected.".clone_into(&mut app.status_message);
         return;
     };
 
+    let Some(access) = app.tag_access() else {
+        "Invalid access password format. Use hex format (e.g. 12345678)."
+            .clone_into(&mut app.status_message);
+        return;
+    };
+
     // Confirm multi-tag and risk before proceeding
-    match UhfRfidApi::inventory(device) {
+    match api.inventory(device) {
         Ok(tags) => {
             if tags.is_empty() {
                     "No tags in range. Please place a tag near the reader.".clone_into(&mut app.status_message);
                 return;
             }
-            if tags.len() > 1 && app.pending_confirm != Some(PendingConfirm::Write) {
+            if access.target.is_none()
+                && tags.len() > 1
+                && app.pending_confirm != Some(PendingConfirm::Write)
+            {
                 app.pending_confirm = Some(PendingConfirm::Write);
                     "Warning: Multiple tags detected. Press Enter again to confirm write."
                         .clone_into(&mut app.status_message);
@@ -478,7 +618,20 @@ fn handle_write(app: &mut App) {
 
     // Parse hex data
     if let Ok(data) = UhfRfidApi::ascii_to_hex(&app.write_data) {
-        match UhfRfidApi::write(device, bank, address, &data) {
+        // Refuse ranges beyond the memory of a known chip; other chips are not checked
+        let words = u32::try_from(data.len() / 2).unwrap_or(u32::MAX);
+        if let Ok(Some(chip)) = api
+            .with_access(device, &access, |device| api.read_tid(device))
+            .map(|tid| tid.chip())
+            && let Err(e) = chip.check_range(bank, address, words)
+        {
+            format!("Write refused: {e}").clone_into(&mut app.status_message);
+            app.pending_confirm = None;
+            return;
+        }
+        match api.with_access(device, &access, |device| {
+            api.write(device, bank, address, &data)
+        }) {
             Ok(()) => {
                 "Write successful!".clone_into(&mut app.status_message);
                 app.pending_confirm = None;
@@ -497,20 +650,27 @@ fn handle_write(app: &mut App) {
 }
 
 fn handle_lock(app: &mut App) {
+    let api = app.api;
     let Some(device) = app.device.as_ref() else {
         "No device connected.".clone_into(&mut app.status_message);
         return;
     };
 
+    let Some(access) = app.tag_access() else {
+        "Invalid access password format. Use hex format (e.g. 12345678)."
+            .clone_into(&mut app.status_message);
+        return;
+    };
+
     // Always warn about permanence and multi-tag situations
-    match UhfRfidApi::inventory(device) {
+    match api.inventory(device) {
         Ok(tags) => {
             if tags.is_empty() {
                 "No tags in range. Please place a tag near the reader.".clone_into(&mut app.status_message);
                 return;
             }
             if app.pending_confirm != Some(PendingConfirm::Lock) {
-                let warn = if tags.len() > 1 {
+                let warn = if access.target.is_none() && tags.len() > 1 {
                     "WARNING: Lock operations can be permanent! Press Enter again to confirm. Multiple tags detected; operation may affect all.".to_owned()
                 } else {
                     "WARNING: Lock operations can be permanent! Press Enter again to confirm."
@@ -528,7 +688,10 @@ fn handle_lock(app: &mut App) {
     }
 
     "Locking tag...".clone_into(&mut app.status_message);
-    match UhfRfidApi::lock_memory_bank(device, app.lock_bank, app.lock_action) {
+    let (bank, action) = (app.lock_bank, app.lock_action);
+    match api.with_access(device, &access, |device| {
+        api.lock_memory_bank(device, bank, action)
+    }) {
         Ok(()) => {
             "Lock operation successful!".clone_into(&mut app.status_message);
             app.pending_confirm = None;
@@ -541,6 +704,7 @@ fn handle_lock(app: &mut App) {
 }
 
 fn handle_password(app: &mut App) {
+    let api = app.api;
     if app.tags.is_empty() {
         "No tags in range. Please place a tag near the reader.".clone_into(&mut app.status_message);
         return;
@@ -551,7 +715,9 @@ fn handle_password(app: &mut App) {
     if let Some(device) = app.device.as_ref() {
         // Parse password
         match u32::from_str_radix(&app.password, 16) {
-            Ok(password) => match UhfRfidApi::set_access_password(device, password) {
+            Ok(password) => match api.with_target(device, app.target_filter().as_ref(), |device| {
+                api.set_access_password(device, password)
+            }) {
                 Ok(()) => {
                     "Password set successfully!".clone_into(&mut app.status_message);
                 }
@@ -568,7 +734,58 @@ fn handle_password(app: &mut App) {
     }
 }
 
+fn handle_kill(app: &mut App) {
+    let api = app.api;
+    let Some(device) = app.device.as_ref() else {
+        "No device connected.".clone_into(&mut app.status_message);
+        return;
+    };
+    let Some(epc) = app.target_epc.clone() else {
+        "Pick the tag to kill with [1-9] in the tag list first."
+            .clone_into(&mut app.status_message);
+        return;
+    };
+    let Ok(password) = u32::from_str_radix(&app.kill_password, 16) else {
+        "Invalid kill password format. Use hex format (e.g. 12345678)."
+            .clone_into(&mut app.status_message);
+        return;
+    };
+    let tid = if app.kill_tid.is_empty() {
+        None
+    } else if let Ok(tid) = UhfRfidApi::ascii_to_hex(&app.kill_tid) {
+        Some(tid)
+    } else {
+        "Invalid TID prefix (must be even length and 0-9A-F).".clone_into(&mut app.status_message);
+        return;
+    };
+    if !app
+        .kill_confirm
+        .eq_ignore_ascii_case(&UhfRfidApi::hex_to_ascii(&epc))
+    {
+        "Type the target EPC in the confirmation field to kill the tag."
+            .clone_into(&mut app.status_message);
+        return;
+    }
+
+    "Killing tag...".clone_into(&mut app.status_message);
+    match api.kill(device, &epc, tid.as_deref(), password) {
+        Ok(()) => {
+            app.target_epc = None;
+            app.kill_password.clear();
+            app.kill_tid.clear();
+            app.kill_confirm.clear();
+            app.state = AppState::Main;
+            update_inventory(app);
+            "Tag killed.".clone_into(&mut app.status_message);
+        }
+        Err(e) => {
+            format!("Kill failed: {e}").clone_into(&mut app.status_message);
+        }
+    }
+}
+
 fn handle_action(app: &mut App) -> Result<(), RfidError> {
+    let api = app.api;
     let Some(device) = app.device.as_ref() else {
         "No device connected".clone_into(&mut app.status_message);
         return Ok(());
@@ -601,64 +818,108 @@ fn handle_action(app: &mut App) -> Result<(), RfidError> {
     };
 
     // Perform the actions
-    UhfRfidApi::device_action(device, &actions, time)?;
+    api.device_action(device, &actions, time)?;
     "Device actions performed successfully".clone_into(&mut app.status_message);
     app.state = AppState::Main;
 
     Ok(())
 }
 
-fn handle_raw(app: &mut App) -> Result<(), RfidError> {
+fn open_settings(app: &mut App) {
+    let api = app.api;
     let Some(device) = app.device.as_ref() else {
         "No device connected.".clone_into(&mut app.status_message);
-        return Ok(());
+        return;
+    };
+    match api.get_config(device) {
+        Ok(settings) => {
+            app.reader_settings = settings;
+            app.save_settings = false;
+            app.state = AppState::Settings;
+            app.active_input_field = 0;
+            "Reader settings loaded.".clone_into(&mut app.status_message);
+        }
+        Err(e) => {
+            format!("Failed to read reader settings: {e}").clone_into(&mut app.status_message);
+        }
+    }
+}
+
+fn handle_settings(app: &mut App) {
+    let api = app.api;
+    let Some(device) = app.device.as_ref() else {
+        "No device connected.".clone_into(&mut app.status_message);
+        return;
+    };
+    match api.set_config(device, app.reader_settings, app.save_settings) {
+        Ok(true) => "Settings applied and saved on the reader.".clone_into(&mut app.status_message),
+        Ok(false) if app.save_settings => {
+            "Settings applied; this reader cannot store them across power cycles."
+                .clone_into(&mut app.status_message);
+        }
+        Ok(false) => {
+            "Settings applied until the reader is switched off."
+                .clone_into(&mut app.status_message);
+        }
+        Err(e) => format!("Failed to apply settings: {e}").clone_into(&mut app.status_message),
+    }
+}
+
+fn handle_raw(app: &mut App) {
+    let api = app.api;
+    let Some(device) = app.device.as_ref() else {
+        "No device connected.".clone_into(&mut app.status_message);
+        return;
     };
     let Ok(data) = UhfRfidApi::ascii_to_hex(&app.raw_input) else {
         "Invalid hex string (must be even length and 0-9A-F).".clone_into(&mut app.status_message);
-                 return Ok(());
+        return;
     };
 
     if app.pending_confirm != Some(PendingConfirm::Raw) {
         app.pending_confirm = Some(PendingConfirm::Raw);
         "Manual command is risky. Press Enter again to send.".clone_into(&mut app.status_message);
-        return Ok(());
+        return;
     }
 
     // send
-    let written = device.write(&data)?;
-    format!("Sent {written} bytes. Waiting for response...").clone_into(&mut app.status_message);
-
-    let mut buffer = [0u8; 256];
-    match device.read(&mut buffer) {
-        Ok(bytes_read) if bytes_read > 0 => {
-            app.raw_response = buffer[..bytes_read].to_vec();
-            format!("Received {bytes_read} bytes.").clone_into(&mut app.status_message);
-        }
-        Ok(_) => {
-            app.raw_response.clear();
-            "No response received (timeout).".clone_into(&mut app.status_message);
+    match api.raw_transaction(device, &data, &app.raw_options) {
+        Ok(reply) => {
+            app.status_message = match (reply.reports.len(), &reply.frame) {
+                (0, _) => "No response received (timeout).".to_string(),
+                (reports, Some(frame)) => {
+                    format!(
+                        "Received a {}-byte frame in {reports} report(s).",
+                        frame.len()
+                    )
+                }
+                (reports, None) => {
+                    format!("Received {reports} report(s) without a complete frame.")
+                }
+            };
+            app.raw_reply = reply;
         }
         Err(e) => {
-            app.raw_response.clear();
-            app.status_message = format!("Error reading response: {e}");
+            app.raw_reply = RawReply::default();
+            app.status_message = format!("Raw command failed: {e}");
         }
     }
 
     app.pending_confirm = None;
-    Ok(())
 }
 
 fn handle_test(app: &mut App) -> Result<(), RfidError> {
+    let api = app.api;
     let Some(device) = app.device.as_ref() else {
         "No device connected.".clone_into(&mut app.status_message);
         return Ok(());
     };
     // Basic test similar to CLI
-    UhfRfidApi::read(device, MemoryBank::Epc, 0, 8)?;
-    UhfRfidApi::read(device, MemoryBank::Tid, 0, 8)?;
-    UhfRfidApi::read(device, MemoryBank::User, 0, 8)?;
-    UhfRfidApi::read(device, MemoryBank::Reserved, 0, 8)?;
-    UhfRfidApi::device_action(device, &[DeviceAction::Beep, DeviceAction::GreenLed], 50)?;
+    api.read(device, MemoryBank::Epc, 0, 8)?;
+    api.read(device, MemoryBank::Tid, 0, 8)?;
+    api.read(device, MemoryBank::User, 0, 8)?;
+    api.read(device, MemoryBank::Reserved, 0, 8)?;
+    api.device_action(device, &[DeviceAction::Beep, DeviceAction::GreenLed], 50)?;
     "Test sequence completed successfully.".clone_into(&mut app.status_message);
     Ok(())
 }
@@ -2,7 +2,7 @@
 
 use criterion::{criterion_group, criterion_main, Criterion};
 
-fn bench_(c: &mut Criterion) {}
+fn bench_(_c: &mut Criterion) {}
 
 // link symbol to avoid the dead_code warning when clippy analyzes test targets.
 const _: fn(&mut Criterion) = bench_;
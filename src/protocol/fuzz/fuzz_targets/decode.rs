@@ -0,0 +1,23 @@
+//! Feeds arbitrary bytes to the frame decoders and to both deframers.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use protocl::codec::{Command, Response};
+use protocl::framing::{Deframer, Direction, REPORT_SIZE};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Response::decode(data);
+    let _ = Command::decode(data);
+
+    for direction in [Direction::HostToReader, Direction::ReaderToHost] {
+        let mut deframer = Deframer::new(direction);
+        for report in data.chunks(REPORT_SIZE) {
+            if let Ok(Some(frame)) = deframer.push(report) {
+                assert_eq!(usize::from(frame[0]) + 1, frame.len());
+                let _ = Response::decode(&frame);
+                let _ = Command::decode(&frame);
+            }
+        }
+    }
+});
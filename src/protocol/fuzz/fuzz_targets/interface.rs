@@ -0,0 +1,147 @@
+//! Runs an `Interface` method against a reader whose replies are chosen by the fuzzer.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use protocl::interface::{Interface, RawOptions, Result, RetryPolicy};
+use protocl::types::{LockPayload, MemoryBank, ReaderSettings, TagFilter, UhfError, UsbIo};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Reader replies and the method to call
+#[derive(Debug, Arbitrary)]
+struct Script {
+    /// Reports already waiting before the first command
+    stale: Vec<Vec<u8>>,
+    /// Reports the reader sends after each report written to it
+    batches: Vec<Vec<Vec<u8>>>,
+    operation: Operation,
+}
+
+#[derive(Debug, Arbitrary)]
+enum Operation {
+    Inventory,
+    Read {
+        bank: u8,
+        address: u8,
+        words: u8,
+    },
+    Write {
+        bank: u8,
+        address: u8,
+        data: Vec<u8>,
+    },
+    Action {
+        action: u8,
+        time_units: u8,
+    },
+    SetAccessPassword(u32),
+    Lock(u32),
+    Authenticate(u32),
+    Kill(u32),
+    Select(Vec<u8>),
+    ClearSelect,
+    GetConfig,
+    SetConfig {
+        persist: bool,
+    },
+    Identify,
+    Raw {
+        data: Vec<u8>,
+        framed: bool,
+        collect_continuations: bool,
+    },
+}
+
+/// Reader that answers each report written to it with the next scripted batch
+///
+/// A scripted report longer than the read buffer is cut short, but its full length is
+/// still returned, like a misbehaving transport.
+struct ScriptedReader {
+    batches: RefCell<VecDeque<Vec<Vec<u8>>>>,
+    pending: RefCell<VecDeque<Vec<u8>>>,
+}
+
+impl UsbIo for ScriptedReader {
+    fn read_bulk(&self, _endpoint: u8, buf: &mut [u8], _timeout: Duration) -> Result<usize> {
+        let report = self
+            .pending
+            .borrow_mut()
+            .pop_front()
+            .ok_or(UhfError::Timeout)?;
+        let len = report.len().min(buf.len());
+        buf[..len].copy_from_slice(&report[..len]);
+        Ok(report.len())
+    }
+
+    fn write_bulk(&self, _endpoint: u8, data: &[u8], _timeout: Duration) -> Result<usize> {
+        if let Some(batch) = self.batches.borrow_mut().pop_front() {
+            self.pending.borrow_mut().extend(batch);
+        }
+        Ok(data.len())
+    }
+}
+
+fn bank(index: u8) -> MemoryBank {
+    [
+        MemoryBank::Reserved,
+        MemoryBank::Epc,
+        MemoryBank::Tid,
+        MemoryBank::User,
+    ][usize::from(index % 4)]
+}
+
+fuzz_target!(|script: Script| {
+    let reader = ScriptedReader {
+        batches: RefCell::new(script.batches.into()),
+        pending: RefCell::new(script.stale.into()),
+    };
+    let interface = Interface {
+        policy: RetryPolicy::default().with_backoff(Duration::ZERO),
+    };
+    let _ = match script.operation {
+        Operation::Inventory => interface.inventory(&reader).map(drop),
+        Operation::Read {
+            bank: index,
+            address,
+            words,
+        } => interface
+            .read(&reader, bank(index), address, words)
+            .map(drop),
+        Operation::Write {
+            bank: index,
+            address,
+            data,
+        } => interface.write(&reader, bank(index), address, &data),
+        Operation::Action { action, time_units } => interface.action(&reader, action, time_units),
+        Operation::SetAccessPassword(password) => interface.set_access_password(&reader, password),
+        Operation::Lock(bits) => {
+            LockPayload::from_bits(bits).and_then(|payload| interface.lock_memory(&reader, payload))
+        }
+        Operation::Authenticate(password) => interface.authenticate(&reader, password),
+        Operation::Kill(password) => interface.kill(&reader, password),
+        Operation::Select(epc) => interface.select(&reader, &TagFilter::epc(&epc)),
+        Operation::ClearSelect => interface.clear_select(&reader),
+        Operation::GetConfig => interface.get_config(&reader).map(drop),
+        Operation::SetConfig { persist } => interface
+            .set_config(&reader, ReaderSettings::default(), persist)
+            .map(drop),
+        Operation::Identify => interface.identify(&reader).map(drop),
+        Operation::Raw {
+            data,
+            framed,
+            collect_continuations,
+        } => {
+            let options = RawOptions {
+                framed,
+                timeout: None,
+                collect_continuations,
+            };
+            interface
+                .raw_transaction(&reader, &data, &options)
+                .map(drop)
+        }
+    };
+});
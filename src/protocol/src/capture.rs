@@ -0,0 +1,420 @@
+//! Record-and-replay transports for reader sessions.
+//!
+//! [`RecordingTransport`] wraps any [`UsbIo`] (typically the real `UsbDevice`) and logs every
+//! `write_bulk`/`read_bulk` chunk with its timestamp and endpoint to a capture file.
+//! [`ReplayTransport`] serves the recorded chunks back to [`Interface`](crate::interface::Interface)
+//! and checks that the outgoing chunks match, so field sessions can be turned into
+//! regression tests.
+//!
+//! Capture files are plain text, one chunk per line:
+//!
+//! ```text
+//! # seconds direction endpoint payload
+//! 0.000012 OUT 03 0302558000...
+//! 0.004518 IN 82 0402558001...
+//! 2.001340 IN 82 !timeout
+//! ```
+//!
+//! The payload is the chunk as upper-case hex, `!timeout` for a timed-out transfer, or
+//! `!error <message>` for any other transport failure. Lines starting with `#` are comments.
+
+use crate::interface::Result;
+use crate::types::{UhfError, UsbIo};
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Header line written at the top of every capture file
+const CAPTURE_HEADER: &str = "# seconds direction endpoint payload";
+
+/// Direction of a captured chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Host to reader (`write_bulk`)
+    Out,
+    /// Reader to host (`read_bulk`)
+    In,
+}
+
+/// Outcome of a captured transfer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Payload {
+    /// Bytes written to or read from the reader
+    Bytes(Vec<u8>),
+    /// The transfer timed out
+    Timeout,
+    /// The transfer failed with a transport error
+    Error(String),
+}
+
+/// A single chunk of a captured session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptureEntry {
+    /// Time since the recording started
+    pub elapsed: Duration,
+    /// Transfer direction
+    pub direction: Direction,
+    /// USB endpoint address used for the transfer
+    pub endpoint: u8,
+    /// Transferred bytes or failure
+    pub payload: Payload,
+}
+
+impl fmt::Display for CaptureEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let direction = match self.direction {
+            Direction::Out => "OUT",
+            Direction::In => "IN",
+        };
+        write!(
+            f,
+            "{:.6} {direction} {:02X} ",
+            self.elapsed.as_secs_f64(),
+            self.endpoint
+        )?;
+        match &self.payload {
+            Payload::Bytes(bytes) => write!(f, "{}", hex::encode_upper(bytes)),
+            Payload::Timeout => write!(f, "!timeout"),
+            Payload::Error(message) => write!(f, "!error {message}"),
+        }
+    }
+}
+
+impl FromStr for CaptureEntry {
+    type Err = UhfError;
+
+    fn from_str(line: &str) -> Result<Self> {
+        let invalid = || UhfError::InvalidParameter(format!("Invalid capture line: {line}"));
+        let mut fields = line.trim().splitn(4, ' ');
+        let elapsed = fields
+            .next()
+            .and_then(|s| s.parse::<f64>().ok())
+            .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+            .ok_or_else(invalid)?;
+        let direction = match fields.next() {
+            Some("OUT") => Direction::Out,
+            Some("IN") => Direction::In,
+            _ => return Err(invalid()),
+        };
+        let endpoint = fields
+            .next()
+            .and_then(|s| u8::from_str_radix(s, 16).ok())
+            .ok_or_else(invalid)?;
+        let payload = match fields.next().unwrap_or_default() {
+            "!timeout" => Payload::Timeout,
+            s if s.starts_with("!error") => {
+                Payload::Error(s.trim_start_matches("!error").trim_start().to_string())
+            }
+            s => Payload::Bytes(hex::decode(s).map_err(|_| invalid())?),
+        };
+        Ok(Self {
+            elapsed,
+            direction,
+            endpoint,
+            payload,
+        })
+    }
+}
+
+/// Parse a capture file's contents into entries, skipping blank and comment lines
+///
+/// # Errors
+/// Returns `UhfError::InvalidParameter` for malformed lines and `UhfError::Communication`
+/// if reading fails.
+pub fn read_capture(reader: impl BufRead) -> Result<Vec<CaptureEntry>> {
+    let mut entries = Vec::new();
+    for line in reader.lines() {
+        let line = line.map_err(|e| UhfError::Communication(e.to_string()))?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        entries.push(line.parse()?);
+    }
+    Ok(entries)
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn payload_of(result: &Result<usize>, bytes: &[u8]) -> Payload {
+    match result {
+        Ok(len) => Payload::Bytes(bytes[..(*len).min(bytes.len())].to_vec()),
+        Err(UhfError::Timeout) => Payload::Timeout,
+        Err(e) => Payload::Error(e.to_string()),
+    }
+}
+
+/// `UsbIo` wrapper that records every chunk exchanged with the inner transport
+pub struct RecordingTransport<T: UsbIo, W: Write> {
+    inner: T,
+    sink: Mutex<W>,
+    started: Instant,
+}
+
+impl<T: UsbIo> RecordingTransport<T, File> {
+    /// Record the session of `inner` to a new capture file at `path`
+    ///
+    /// # Errors
+    /// Returns `UhfError::Communication` if the file cannot be created.
+    pub fn create(inner: T, path: impl AsRef<Path>) -> Result<Self> {
+        let file = File::create(path).map_err(|e| UhfError::Communication(e.to_string()))?;
+        Self::new(inner, file)
+    }
+}
+
+impl<T: UsbIo, W: Write> RecordingTransport<T, W> {
+    /// Record the session of `inner` to `sink`
+    ///
+    /// # Errors
+    /// Returns `UhfError::Communication` if the capture header cannot be written.
+    pub fn new(inner: T, mut sink: W) -> Result<Self> {
+        writeln!(sink, "{CAPTURE_HEADER}").map_err(|e| UhfError::Communication(e.to_string()))?;
+        Ok(Self {
+            inner,
+            sink: Mutex::new(sink),
+            started: Instant::now(),
+        })
+    }
+
+    /// Wrapped transport
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Stop recording and return the wrapped transport and capture sink
+    pub fn into_parts(self) -> (T, W) {
+        (
+            self.inner,
+            self.sink
+                .into_inner()
+                .unwrap_or_else(PoisonError::into_inner),
+        )
+    }
+
+    fn record(&self, direction: Direction, endpoint: u8, payload: Payload) -> Result<()> {
+        let entry = CaptureEntry {
+            elapsed: self.started.elapsed(),
+            direction,
+            endpoint,
+            payload,
+        };
+        let mut sink = lock(&self.sink);
+        writeln!(sink, "{entry}")
+            .and_then(|()| sink.flush())
+            .map_err(|e| UhfError::Communication(format!("Failed to write capture: {e}")))
+    }
+}
+
+impl<T: UsbIo, W: Write> UsbIo for RecordingTransport<T, W> {
+    fn read_bulk(&self, endpoint: u8, buf: &mut [u8], timeout: Duration) -> Result<usize> {
+        let result = self.inner.read_bulk(endpoint, buf, timeout);
+        self.record(Direction::In, endpoint, payload_of(&result, buf))?;
+        result
+    }
+
+    fn write_bulk(&self, endpoint: u8, data: &[u8], timeout: Duration) -> Result<usize> {
+        let result = self.inner.write_bulk(endpoint, data, timeout);
+        let payload = match &result {
+            Ok(_) => Payload::Bytes(data.to_vec()),
+            Err(_) => payload_of(&result, data),
+        };
+        self.record(Direction::Out, endpoint, payload)?;
+        result
+    }
+
+    fn is_connected(&self) -> bool {
+        self.inner.is_connected()
+    }
+}
+
+/// `UsbIo` transport that plays back a captured session
+///
+/// Every `write_bulk` must match the next recorded OUT chunk and every `read_bulk` is
+/// answered from the next recorded IN chunk. A mismatch fails the transfer with
+/// `UhfError::Communication` and is kept so [`ReplayTransport::finish`] can report it even
+/// when the caller swallowed the error.
+#[derive(Debug)]
+pub struct ReplayTransport {
+    entries: Mutex<VecDeque<CaptureEntry>>,
+    mismatches: Mutex<Vec<String>>,
+}
+
+impl ReplayTransport {
+    /// Replay the given entries
+    #[must_use]
+    pub fn new(entries: Vec<CaptureEntry>) -> Self {
+        Self {
+            entries: Mutex::new(entries.into()),
+            mismatches: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Replay a capture file
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or contains malformed lines.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let file = File::open(path).map_err(|e| UhfError::Communication(e.to_string()))?;
+        Ok(Self::new(read_capture(BufReader::new(file))?))
+    }
+
+    /// Number of recorded chunks not yet replayed
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        lock(&self.entries).len()
+    }
+
+    /// Check that the whole capture was replayed without mismatches
+    ///
+    /// # Errors
+    /// Returns `UhfError::Communication` describing the first mismatch, or the number of
+    /// chunks left unreplayed.
+    pub fn finish(&self) -> Result<()> {
+        if let Some(first) = lock(&self.mismatches).first() {
+            return Err(UhfError::Communication(first.clone()));
+        }
+        match self.remaining() {
+            0 => Ok(()),
+            n => Err(UhfError::Communication(format!(
+                "Replay ended with {n} recorded chunk(s) left"
+            ))),
+        }
+    }
+
+    fn next_entry(&self, direction: Direction, endpoint: u8) -> Result<CaptureEntry> {
+        let mut entries = lock(&self.entries);
+        let mismatch = match entries.front() {
+            Some(entry) if entry.direction == direction && entry.endpoint == endpoint => None,
+            Some(entry) => Some(format!(
+                "Replay expected {:?} on endpoint 0x{:02X} but got {direction:?} on 0x{endpoint:02X}",
+                entry.direction, entry.endpoint
+            )),
+            None => Some(format!(
+                "Replay exhausted at {direction:?} on endpoint 0x{endpoint:02X}"
+            )),
+        };
+        match mismatch {
+            None => entries.pop_front().ok_or(UhfError::Timeout),
+            Some(message) => Err(self.mismatch(message)),
+        }
+    }
+
+    fn mismatch(&self, message: String) -> UhfError {
+        lock(&self.mismatches).push(message.clone());
+        UhfError::Communication(message)
+    }
+}
+
+impl UsbIo for ReplayTransport {
+    fn read_bulk(&self, endpoint: u8, buf: &mut [u8], _timeout: Duration) -> Result<usize> {
+        match self.next_entry(Direction::In, endpoint)?.payload {
+            Payload::Bytes(bytes) => {
+                let len = bytes.len().min(buf.len());
+                buf[..len].copy_from_slice(&bytes[..len]);
+                Ok(len)
+            }
+            Payload::Timeout => Err(UhfError::Timeout),
+            Payload::Error(message) => Err(UhfError::Communication(message)),
+        }
+    }
+
+    fn write_bulk(&self, endpoint: u8, data: &[u8], _timeout: Duration) -> Result<usize> {
+        match self.next_entry(Direction::Out, endpoint)?.payload {
+            Payload::Bytes(expected) if expected == data => Ok(data.len()),
+            Payload::Bytes(expected) => Err(self.mismatch(format!(
+                "Replay expected OUT chunk {} but got {}",
+                hex::encode_upper(expected),
+                hex::encode_upper(data)
+            ))),
+            Payload::Timeout => Err(UhfError::Timeout),
+            Payload::Error(message) => Err(UhfError::Communication(message)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emulator::{ReaderEmulator, VirtualTag};
+    use crate::interface::Interface;
+    use crate::types::MemoryBank;
+
+    fn record_session() -> Vec<u8> {
+        let reader = ReaderEmulator::with_tags(vec![VirtualTag::new(&[0xAB; 12])]);
+        let recorder = RecordingTransport::new(reader, Vec::new()).unwrap();
+        let interface = Interface::default();
+        interface.inventory(&recorder).unwrap();
+        interface
+            .write(&recorder, MemoryBank::User, 0, &[0xBE, 0xEF])
+            .unwrap();
+        interface.read(&recorder, MemoryBank::User, 0, 1).unwrap();
+        recorder.into_parts().1
+    }
+
+    #[test]
+    fn entry_round_trips_through_text() {
+        for payload in [
+            Payload::Bytes(vec![0x04, 0x02, 0x55, 0x91, 0x00]),
+            Payload::Timeout,
+            Payload::Error("pipe broke".to_string()),
+        ] {
+            let entry = CaptureEntry {
+                elapsed: Duration::from_micros(1_234_567),
+                direction: Direction::In,
+                endpoint: 0x82,
+                payload,
+            };
+            assert_eq!(entry.to_string().parse::<CaptureEntry>().unwrap(), entry);
+        }
+    }
+
+    #[test]
+    fn recorded_session_replays_against_interface() {
+        let capture = record_session();
+        let entries = read_capture(capture.as_slice()).unwrap();
+        assert!(
+            entries
+                .iter()
+                .all(|e| e.endpoint == 0x03 || e.endpoint == 0x82)
+        );
+
+        let replay = ReplayTransport::new(entries);
+        let interface = Interface::default();
+        let tags = interface.inventory(&replay).unwrap();
+        assert_eq!(tags.len(), 1);
+        interface
+            .write(&replay, MemoryBank::User, 0, &[0xBE, 0xEF])
+            .unwrap();
+        assert_eq!(
+            interface.read(&replay, MemoryBank::User, 0, 1).unwrap(),
+            [0xBE, 0xEF]
+        );
+        replay.finish().unwrap();
+    }
+
+    #[test]
+    fn replay_flags_diverging_frames() {
+        let replay = ReplayTransport::new(read_capture(record_session().as_slice()).unwrap());
+        let interface = Interface::default();
+        interface.inventory(&replay).unwrap();
+        assert!(
+            interface
+                .write(&replay, MemoryBank::User, 0, &[0xCA, 0xFE])
+                .is_err()
+        );
+        assert!(replay.finish().is_err());
+    }
+
+    #[test]
+    fn malformed_lines_are_rejected() {
+        assert!("0.1 SIDEWAYS 03 00".parse::<CaptureEntry>().is_err());
+        assert!("0.1 OUT 03 0G".parse::<CaptureEntry>().is_err());
+    }
+}
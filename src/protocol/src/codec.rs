@@ -0,0 +1,1400 @@
+//! Typed command and response frames of the reader protocol.
+//!
+//! Every frame starts with a length byte counting the bytes that follow, then the
+//! protocol marker (`2`). [`Command::encode`]/[`Command::decode`] and
+//! [`Response::encode`]/[`Response::decode`] are the single place where frame layouts
+//! live; decoding an encoded value always yields the original value back.
+//!
+//! The "A" access family (`AR`, `AW`, `AP`, `AL`, `AK`, `AA`, `AS`) is ASCII: bank, address
+//! and word count are hex digits separated by commas, and tag data travels as ASCII hex with
+//! four characters per 16-bit word. Replies to it are `[2, 'A', status, cmd, ...]`.
+//! `AS` sets the select filter that decides which tag answers the other access commands.
+//! Inventory, reader configuration, identification and device actions use binary frames.
+//!
+//! Some layouts were inferred rather than taken from the reader's vendor protocol and have
+//! only been checked against the reader emulator. [`Command::encode`] refuses
+//! those commands ([`Command::is_verified`]) unless the `unverified-commands` feature is on.
+
+use crate::interface::Result;
+use crate::types::{
+    FirmwareVersion, Gen2Settings, InventoryTarget, LockPayload, MemoryBank, ReaderFeature,
+    ReaderIdentity, ReaderSettings, Region, RfSettings, Session, TagFilter, TagReport, UhfError,
+};
+
+/// Protocol marker that follows the length byte of every frame
+pub const PROTOCOL_MARKER: u8 = 2;
+/// Prefix of the ASCII access command family
+pub const ACCESS_PREFIX: u8 = b'A';
+/// Access command reading tag memory
+pub const ACCESS_READ: u8 = b'R';
+/// Access command writing tag memory
+pub const ACCESS_WRITE: u8 = b'W';
+/// Access command setting the access password
+pub const ACCESS_PASSWORD: u8 = b'P';
+/// Access command locking tag memory
+pub const ACCESS_LOCK: u8 = b'L';
+/// Access command authenticating with the access password
+pub const ACCESS_AUTHENTICATE: u8 = b'A';
+/// Access command permanently killing the tag
+pub const ACCESS_KILL: u8 = b'K';
+/// Access command setting or clearing the select filter
+pub const ACCESS_SELECT: u8 = b'S';
+/// Opcode prefix of the binary inventory commands
+pub const INVENTORY_OPCODE: u8 = 0x55;
+/// Inventory sub-command starting a new round
+pub const INVENTORY_START: u8 = 0x80;
+/// Inventory sub-command fetching the next tag of the round
+pub const INVENTORY_NEXT: u8 = 0x91;
+/// Opcode prefix of the binary reader configuration commands
+pub const CONFIG_OPCODE: u8 = 0x56;
+/// Configuration sub-command reading the current settings
+pub const CONFIG_GET: u8 = 0x01;
+/// Configuration sub-command applying new settings
+pub const CONFIG_SET: u8 = 0x02;
+/// Opcode of the command reporting firmware, hardware and capabilities
+pub const IDENTIFY_OPCODE: u8 = 0x57;
+/// Opcode of the buzzer/LED action command
+pub const ACTION_OPCODE: u8 = 145;
+/// Largest word count a single read or write can carry (one hex digit)
+pub const MAX_WORDS_PER_COMMAND: u8 = 15;
+/// Largest word address a single read or write can carry (two hex digits)
+pub const MAX_WORD_ADDRESS: u8 = 0xFF;
+/// Longest select mask, in bytes, a single command can carry
+pub const MAX_SELECT_MASK_BYTES: usize = 16;
+/// Status byte reported when the command succeeded
+pub const STATUS_OK: u8 = 0x00;
+/// Status byte reported when no tag answered the command
+pub const STATUS_NO_TAG: u8 = 0x01;
+/// Status byte reported when the command frame could not be parsed
+pub const STATUS_BAD_COMMAND: u8 = 0x02;
+/// Status byte reported when the operation addressed memory past the end of the bank
+pub const STATUS_MEMORY_OVERRUN: u8 = 0x03;
+/// Status byte reported when the addressed memory is locked or permalocked
+pub const STATUS_MEMORY_LOCKED: u8 = 0x04;
+/// Status byte reported when the tag rejected the access password
+pub const STATUS_ACCESS_DENIED: u8 = 0x05;
+/// Body of the `AW`/`AL`/`AK`/`AA`/`AS` success replies after the command byte
+const OK_SUFFIX: &[u8] = b",OK\r";
+
+/// Command sent from the host to the reader
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// `AR`: read `words` words starting at word `address` (0 reads to the end of the bank)
+    Read {
+        /// Memory bank to read
+        bank: MemoryBank,
+        /// Start word address
+        address: u8,
+        /// Number of words to read
+        words: u8,
+    },
+    /// `AW`: write whole words starting at word `address`
+    Write {
+        /// Memory bank to write
+        bank: MemoryBank,
+        /// Start word address
+        address: u8,
+        /// Data to write, two bytes per word
+        data: Vec<u8>,
+    },
+    /// `AP`: set the tag's 32-bit access password
+    SetAccessPassword {
+        /// New access password
+        password: u32,
+    },
+    /// `AL`: apply a 20-bit Gen2 lock payload (mask bits 19..10, action bits 9..0)
+    Lock {
+        /// Lock payload
+        payload: LockPayload,
+    },
+    /// `AA`: move the tag to the Secured state with its 32-bit access password (unverified)
+    Authenticate {
+        /// Access password stored in Reserved words 2-3
+        password: u32,
+    },
+    /// `AK`: permanently kill the tag with its 32-bit kill password (unverified)
+    Kill {
+        /// Kill password stored in Reserved words 0-1
+        password: u32,
+    },
+    /// `AS`: make only tags matching the filter answer later access commands (unverified)
+    Select(TagFilter),
+    /// `AS0`: let the first tag in the field answer access commands again (unverified)
+    ClearSelect,
+    /// Start an inventory round
+    InventoryStart,
+    /// Fetch the next tag of the current inventory round
+    InventoryNext,
+    /// Read the reader's RF and Gen2 settings (unverified)
+    GetConfig,
+    /// Apply RF and Gen2 settings (unverified)
+    SetConfig {
+        /// New settings
+        settings: ReaderSettings,
+        /// Also store the settings in the reader's non-volatile memory
+        persist: bool,
+    },
+    /// Ask the reader for its firmware version, hardware revision and capabilities
+    /// (unverified)
+    Identify,
+    /// Drive the buzzer/LEDs
+    Action {
+        /// `DeviceAction` bitmask (1..=15)
+        action: u8,
+        /// Duration in units of 10ms
+        time_units: u8,
+    },
+}
+
+/// Command family with its own reply timeout in a
+/// [`RetryPolicy`](crate::interface::RetryPolicy)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommandKind {
+    /// `AR` reads
+    Read,
+    /// `AW` writes
+    Write,
+    /// `AL` lock
+    Lock,
+    /// `AK` kill
+    Kill,
+    /// `AP` access password changes
+    Password,
+    /// `AA` authentication
+    Authenticate,
+    /// `AS` setting or clearing the select filter
+    Select,
+    /// Inventory rounds
+    Inventory,
+    /// Reading and applying reader settings
+    Config,
+    /// Identify
+    Identify,
+    /// Buzzer/LED actions
+    Action,
+}
+
+impl CommandKind {
+    /// All command kinds
+    pub const ALL: [CommandKind; 11] = [
+        CommandKind::Read,
+        CommandKind::Write,
+        CommandKind::Lock,
+        CommandKind::Kill,
+        CommandKind::Password,
+        CommandKind::Authenticate,
+        CommandKind::Select,
+        CommandKind::Inventory,
+        CommandKind::Config,
+        CommandKind::Identify,
+        CommandKind::Action,
+    ];
+
+    /// Lower-case name of the kind, as used in configuration files
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            CommandKind::Read => "read",
+            CommandKind::Write => "write",
+            CommandKind::Lock => "lock",
+            CommandKind::Kill => "kill",
+            CommandKind::Password => "password",
+            CommandKind::Authenticate => "authenticate",
+            CommandKind::Select => "select",
+            CommandKind::Inventory => "inventory",
+            CommandKind::Config => "config",
+            CommandKind::Identify => "identify",
+            CommandKind::Action => "action",
+        }
+    }
+
+    /// Kind with the given [`name`](Self::name)
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+/// Reply sent from the reader to the host
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Response {
+    /// Words returned by `AR`, two bytes per word
+    Read {
+        /// Data read from the tag
+        data: Vec<u8>,
+    },
+    /// `AW` completed
+    Written,
+    /// `AP` completed
+    PasswordSet,
+    /// `AL` completed
+    Locked,
+    /// `AA` completed; the tag is in the Secured state
+    Authenticated,
+    /// `AK` completed; the tag no longer responds
+    Killed,
+    /// `AS` completed
+    Selected,
+    /// An access command failed with a non-zero status byte
+    AccessFailed {
+        /// Access command byte (`R`, `W`, `P`, `L`, `A`, `K` or `S`)
+        command: u8,
+        /// Status byte reported by the reader
+        status: u8,
+    },
+    /// An inventory round started
+    InventoryStarted {
+        /// Number of tags the reader singulated in the round
+        tag_count: u8,
+    },
+    /// Next tag of the inventory round
+    InventoryTag(TagReport),
+    /// The inventory round has no more tags
+    InventoryEmpty,
+    /// Settings returned by a configuration read
+    Config(ReaderSettings),
+    /// Settings applied
+    ConfigSet {
+        /// Whether the reader also stored them in non-volatile memory
+        persisted: bool,
+    },
+    /// A configuration command failed with a non-zero status byte
+    ConfigFailed {
+        /// Configuration sub-command (`CONFIG_GET` or `CONFIG_SET`)
+        command: u8,
+        /// Status byte reported by the reader
+        status: u8,
+    },
+    /// Firmware version, hardware revision and capabilities of the reader
+    Identity(ReaderIdentity),
+    /// The identify command failed with a non-zero status byte
+    IdentifyFailed {
+        /// Status byte reported by the reader
+        status: u8,
+    },
+    /// Reply to a buzzer/LED action
+    Action {
+        /// Status byte reported by the reader (0 on success)
+        status: u8,
+    },
+}
+
+/// Convert a nibble to its upper-case ASCII hex digit
+fn hex_digit(value: u8) -> u8 {
+    if value >= 10 {
+        value + 55 // 'A'-'F'
+    } else {
+        value + 48 // '0'-'9'
+    }
+}
+
+/// Encode an address with one hex digit below 0x10 and two otherwise
+fn push_address(body: &mut Vec<u8>, address: u8) {
+    if address >= 0x10 {
+        body.push(hex_digit(address >> 4));
+    }
+    body.push(hex_digit(address & 0xF));
+}
+
+fn parse_hex_field(field: &[u8], max_digits: usize) -> Option<u8> {
+    if field.is_empty() || field.len() > max_digits {
+        return None;
+    }
+    u8::from_str_radix(std::str::from_utf8(field).ok()?, 16).ok()
+}
+
+fn parse_bank(field: &[u8]) -> Option<MemoryBank> {
+    match field {
+        [digit @ b'1'..=b'4'] => Some(MemoryBank::from(digit - b'0')),
+        _ => None,
+    }
+}
+
+/// Parse the eight hex digits of a 32-bit password
+fn parse_password(field: &[u8]) -> Option<u32> {
+    if field.len() != 8 {
+        return None;
+    }
+    u32::from_str_radix(std::str::from_utf8(field).ok()?, 16).ok()
+}
+
+/// Parse the `<bank>,<bit pointer>,<bit length>,<mask>` arguments of `AS`
+fn parse_select(args: &[u8]) -> Option<TagFilter> {
+    let fields: Vec<&[u8]> = args.split(|b| *b == b',').collect();
+    let [bank, bit_pointer, bit_length, mask] = fields.as_slice() else {
+        return None;
+    };
+    let bank = parse_bank(bank).filter(|bank| *bank != MemoryBank::Reserved)?;
+    if !(1..=4).contains(&bit_pointer.len()) {
+        return None;
+    }
+    let bit_pointer = u16::from_str_radix(std::str::from_utf8(bit_pointer).ok()?, 16).ok()?;
+    let bit_length = parse_hex_field(bit_length, 2).filter(|bits| *bits > 0)?;
+    let mask = hex::decode(mask).ok()?;
+    let mask_len = usize::from(bit_length).div_ceil(8);
+    (mask_len <= MAX_SELECT_MASK_BYTES && mask.len() == mask_len).then_some(TagFilter {
+        bank,
+        bit_pointer,
+        bit_length,
+        mask,
+    })
+}
+
+/// Append an `AS` select filter: bank, bit pointer and bit length in hex, then the mask
+fn push_select(body: &mut Vec<u8>, filter: &TagFilter) -> Result<()> {
+    if filter.bank == MemoryBank::Reserved {
+        return Err(UhfError::InvalidParameter(
+            "Select filters cannot match the Reserved bank".to_string(),
+        ));
+    }
+    let mask_len = usize::from(filter.bit_length).div_ceil(8);
+    if filter.bit_length == 0 || mask_len > MAX_SELECT_MASK_BYTES || filter.mask.len() != mask_len {
+        return Err(UhfError::InvalidParameter(format!(
+            "Select mask must hold 1 to {} bits in whole bytes",
+            MAX_SELECT_MASK_BYTES * 8
+        )));
+    }
+    body.extend_from_slice(&[ACCESS_PREFIX, ACCESS_SELECT, filter.bank.to_ascii()]);
+    body.extend_from_slice(
+        format!(",{:X},{:X},", filter.bit_pointer, filter.bit_length).as_bytes(),
+    );
+    body.extend_from_slice(hex::encode_upper(&filter.mask).as_bytes());
+    Ok(())
+}
+
+/// Append the seven setting bytes: power, region, hopping, channel, session, target, Q
+fn push_settings(body: &mut Vec<u8>, settings: ReaderSettings) {
+    body.extend_from_slice(&[
+        settings.rf.tx_power_dbm,
+        settings.rf.region as u8,
+        u8::from(settings.rf.hopping),
+        settings.rf.channel,
+        settings.gen2.session as u8,
+        settings.gen2.target as u8,
+        settings.gen2.q,
+    ]);
+}
+
+/// Parse the seven setting bytes written by `push_settings`, rejecting values out of range
+fn parse_settings(bytes: &[u8]) -> Option<ReaderSettings> {
+    let [
+        power,
+        region,
+        hopping @ (0 | 1),
+        channel,
+        session,
+        target,
+        q,
+    ] = *bytes
+    else {
+        return None;
+    };
+    let settings = ReaderSettings {
+        rf: RfSettings {
+            tx_power_dbm: power,
+            region: Region::from_code(region)?,
+            hopping: hopping == 1,
+            channel,
+        },
+        gen2: Gen2Settings {
+            session: Session::from_code(session)?,
+            target: InventoryTarget::from_code(target)?,
+            q,
+        },
+    };
+    settings.validate().ok().map(|()| settings)
+}
+
+/// Append the identity fields: firmware major, minor and patch, hardware revision, maximum
+/// power, region mask (`1 << code`) and the big-endian feature mask
+fn push_identity(body: &mut Vec<u8>, identity: &ReaderIdentity) {
+    let FirmwareVersion {
+        major,
+        minor,
+        patch,
+    } = identity.firmware;
+    body.extend_from_slice(&[
+        major,
+        minor,
+        patch,
+        identity.hardware_revision,
+        identity.max_tx_power_dbm,
+        Region::mask(&identity.regions),
+    ]);
+    body.extend_from_slice(&ReaderFeature::mask(&identity.features).to_be_bytes());
+}
+
+/// Parse the identity fields written by [`push_identity`]; unknown feature bits are ignored
+fn parse_identity(body: &[u8]) -> Option<ReaderIdentity> {
+    let [
+        major,
+        minor,
+        patch,
+        hardware_revision,
+        max_tx_power_dbm,
+        regions,
+        hi,
+        lo,
+        ..,
+    ] = *body
+    else {
+        return None;
+    };
+    Some(ReaderIdentity {
+        firmware: FirmwareVersion {
+            major,
+            minor,
+            patch,
+        },
+        hardware_revision,
+        regions: Region::from_mask(regions),
+        max_tx_power_dbm,
+        features: ReaderFeature::from_mask(u16::from_be_bytes([hi, lo])),
+    })
+}
+
+/// Bytes following the length byte, bounded by it
+fn frame_payload(frame: &[u8]) -> Option<&[u8]> {
+    let (&len, rest) = frame.split_first()?;
+    rest.get(..usize::from(len))
+}
+
+/// Bytes of a frame covered by its length byte, with report padding removed
+#[must_use]
+pub fn frame_bytes(frame: &[u8]) -> &[u8] {
+    frame
+        .first()
+        .and_then(|&len| frame.get(..=usize::from(len)))
+        .unwrap_or(frame)
+}
+
+/// Parse an inventory record: PC word, EPC sized by the PC length field, CRC, then the
+/// optional RSSI and antenna bytes
+fn parse_tag_record(record: &[u8]) -> Option<TagReport> {
+    let (pc, rest) = record.split_first_chunk::<2>()?;
+    let pc = u16::from_be_bytes(*pc);
+    let epc_len = usize::from(pc >> 11) * 2;
+    let epc = rest.get(..epc_len)?;
+    let (crc, extra) = rest[epc_len..].split_first_chunk::<2>()?;
+    Some(TagReport {
+        pc,
+        epc: epc.to_vec(),
+        crc: u16::from_be_bytes(*crc),
+        rssi: extra.first().map(|rssi| i8::from_be_bytes([*rssi])),
+        antenna: extra.get(1).copied(),
+    })
+}
+
+/// Prefix a frame body with its length byte
+fn with_length(mut body: Vec<u8>) -> Result<Vec<u8>> {
+    let len = u8::try_from(body.len())
+        .map_err(|_| UhfError::InvalidParameter("Command length too large".to_string()))?;
+    body.insert(0, len);
+    Ok(body)
+}
+
+impl Command {
+    /// Short name of the command, as used in logs
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Command::Read { .. } => "read",
+            Command::Write { .. } => "write",
+            Command::SetAccessPassword { .. } => "set_access_password",
+            Command::Lock { .. } => "lock",
+            Command::Authenticate { .. } => "authenticate",
+            Command::Kill { .. } => "kill",
+            Command::Select(_) => "select",
+            Command::ClearSelect => "clear_select",
+            Command::InventoryStart => "inventory_start",
+            Command::InventoryNext => "inventory_next",
+            Command::GetConfig => "get_config",
+            Command::SetConfig { .. } => "set_config",
+            Command::Identify => "identify",
+            Command::Action { .. } => "action",
+        }
+    }
+
+    /// Family of the command, which decides its reply timeout
+    #[must_use]
+    pub const fn kind(&self) -> CommandKind {
+        match self {
+            Command::Read { .. } => CommandKind::Read,
+            Command::Write { .. } => CommandKind::Write,
+            Command::SetAccessPassword { .. } => CommandKind::Password,
+            Command::Lock { .. } => CommandKind::Lock,
+            Command::Authenticate { .. } => CommandKind::Authenticate,
+            Command::Kill { .. } => CommandKind::Kill,
+            Command::Select(_) | Command::ClearSelect => CommandKind::Select,
+            Command::InventoryStart | Command::InventoryNext => CommandKind::Inventory,
+            Command::GetConfig | Command::SetConfig { .. } => CommandKind::Config,
+            Command::Identify => CommandKind::Identify,
+            Command::Action { .. } => CommandKind::Action,
+        }
+    }
+
+    /// Whether running the command twice has the same effect as running it once
+    ///
+    /// Writes, locks, kills, password and settings changes are not: they may already have
+    /// taken effect, in part or in full, when their reply is lost. `InventoryNext` is not
+    /// either, since the reader has moved on to the next tag of the round.
+    #[must_use]
+    pub const fn is_idempotent(&self) -> bool {
+        matches!(
+            self,
+            Command::Read { .. }
+                | Command::Authenticate { .. }
+                | Command::Select(_)
+                | Command::ClearSelect
+                | Command::InventoryStart
+                | Command::GetConfig
+                | Command::Identify
+        )
+    }
+
+    /// Whether the frame layout is taken from the reader's vendor protocol, as opposed to
+    /// inferred and only checked against the emulator
+    #[must_use]
+    pub const fn is_verified(&self) -> bool {
+        !matches!(
+            self,
+            Command::Authenticate { .. }
+                | Command::Kill { .. }
+                | Command::Select(_)
+                | Command::ClearSelect
+                | Command::GetConfig
+                | Command::SetConfig { .. }
+                | Command::Identify
+        )
+    }
+
+    /// Whether the command or its reply carries a password: access and kill passwords, or
+    /// Reserved bank words
+    #[must_use]
+    pub const fn carries_secret(&self) -> bool {
+        matches!(
+            self,
+            Command::SetAccessPassword { .. }
+                | Command::Authenticate { .. }
+                | Command::Kill { .. }
+                | Command::Read {
+                    bank: MemoryBank::Reserved,
+                    ..
+                }
+                | Command::Write {
+                    bank: MemoryBank::Reserved,
+                    ..
+                }
+        )
+    }
+
+    /// Encode the command into a frame, length byte included
+    ///
+    /// # Errors
+    /// Returns `UhfError::InvalidParameter` if a field does not fit the frame layout, or
+    /// `UhfError::Unverified` for an unverified command without the `unverified-commands`
+    /// feature.
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        if !self.is_verified() && !cfg!(any(test, feature = "unverified-commands")) {
+            return Err(UhfError::Unverified(self.name()));
+        }
+        let mut body = vec![PROTOCOL_MARKER];
+        match self {
+            Command::Read {
+                bank,
+                address,
+                words,
+            } => {
+                if *words > MAX_WORDS_PER_COMMAND {
+                    return Err(UhfError::InvalidParameter(format!(
+                        "Word count must be at most {MAX_WORDS_PER_COMMAND}"
+                    )));
+                }
+                body.extend_from_slice(&[ACCESS_PREFIX, ACCESS_READ, bank.to_ascii(), b',']);
+                push_address(&mut body, *address);
+                body.extend_from_slice(&[b',', hex_digit(*words)]);
+            }
+            Command::Write {
+                bank,
+                address,
+                data,
+            } => {
+                if data.is_empty() || !data.len().is_multiple_of(2) {
+                    return Err(UhfError::InvalidParameter(
+                        "Data length must be a non-zero number of words".to_string(),
+                    ));
+                }
+                let words = u8::try_from(data.len() / 2)
+                    .ok()
+                    .filter(|words| *words <= MAX_WORDS_PER_COMMAND)
+                    .ok_or_else(|| {
+                        UhfError::InvalidParameter(format!(
+                            "Data length must be at most {MAX_WORDS_PER_COMMAND} words"
+                        ))
+                    })?;
+                body.extend_from_slice(&[ACCESS_PREFIX, ACCESS_WRITE, bank.to_ascii(), b',']);
+                push_address(&mut body, *address);
+                body.extend_from_slice(&[b',', hex_digit(words), b',']);
+                body.extend_from_slice(hex::encode_upper(data).as_bytes());
+            }
+            Command::SetAccessPassword { password } => {
+                body.extend_from_slice(&[ACCESS_PREFIX, ACCESS_PASSWORD]);
+                body.extend_from_slice(format!("{password:08X}").as_bytes());
+            }
+            Command::Lock { payload } => {
+                body.extend_from_slice(&[ACCESS_PREFIX, ACCESS_LOCK]);
+                body.extend_from_slice(format!("{:06X}", payload.bits()).as_bytes());
+                body.push(0); // Trailing pad byte
+            }
+            Command::Authenticate { password } => {
+                body.extend_from_slice(&[ACCESS_PREFIX, ACCESS_AUTHENTICATE]);
+                body.extend_from_slice(format!("{password:08X}").as_bytes());
+            }
+            Command::Kill { password } => {
+                body.extend_from_slice(&[ACCESS_PREFIX, ACCESS_KILL]);
+                body.extend_from_slice(format!("{password:08X}").as_bytes());
+            }
+            Command::Select(filter) => push_select(&mut body, filter)?,
+            Command::ClearSelect => body.extend_from_slice(&[ACCESS_PREFIX, ACCESS_SELECT, b'0']),
+            Command::InventoryStart => body.extend_from_slice(&[INVENTORY_OPCODE, INVENTORY_START]),
+            Command::InventoryNext => body.extend_from_slice(&[INVENTORY_OPCODE, INVENTORY_NEXT]),
+            Command::GetConfig => body.extend_from_slice(&[CONFIG_OPCODE, CONFIG_GET]),
+            Command::SetConfig { settings, persist } => {
+                settings.validate()?;
+                body.extend_from_slice(&[CONFIG_OPCODE, CONFIG_SET, u8::from(*persist)]);
+                push_settings(&mut body, *settings);
+            }
+            Command::Identify => body.extend_from_slice(&[IDENTIFY_OPCODE]),
+            Command::Action { action, time_units } => {
+                if *action == 0 || *action > 15 {
+                    return Err(UhfError::InvalidParameter(
+                        "Invalid action value".to_string(),
+                    ));
+                }
+                body.extend_from_slice(&[ACTION_OPCODE, *action, *time_units]);
+            }
+        }
+        with_length(body)
+    }
+
+    /// Decode a command frame, length byte included
+    ///
+    /// # Errors
+    /// Returns `UhfError::InvalidParameter` if the frame is not a well-formed command.
+    pub fn decode(frame: &[u8]) -> Result<Self> {
+        let invalid = || {
+            UhfError::InvalidParameter(format!(
+                "Unrecognised command frame: {}",
+                hex::encode_upper(frame)
+            ))
+        };
+        let payload = frame_payload(frame).ok_or_else(invalid)?;
+        let command = match payload {
+            [PROTOCOL_MARKER, ACCESS_PREFIX, ACCESS_READ, args @ ..] => {
+                let fields: Vec<&[u8]> = args.split(|b| *b == b',').collect();
+                let [bank, address, words] = fields.as_slice() else {
+                    return Err(invalid());
+                };
+                Command::Read {
+                    bank: parse_bank(bank).ok_or_else(invalid)?,
+                    address: parse_hex_field(address, 2).ok_or_else(invalid)?,
+                    words: parse_hex_field(words, 1).ok_or_else(invalid)?,
+                }
+            }
+            [PROTOCOL_MARKER, ACCESS_PREFIX, ACCESS_WRITE, args @ ..] => {
+                let fields: Vec<&[u8]> = args.splitn(4, |b| *b == b',').collect();
+                let [bank, address, words, data] = fields.as_slice() else {
+                    return Err(invalid());
+                };
+                let words = parse_hex_field(words, 1).ok_or_else(invalid)?;
+                if words == 0 || data.len() != usize::from(words) * 4 {
+                    return Err(invalid());
+                }
+                Command::Write {
+                    bank: parse_bank(bank).ok_or_else(invalid)?,
+                    address: parse_hex_field(address, 2).ok_or_else(invalid)?,
+                    data: hex::decode(data).map_err(|_| invalid())?,
+                }
+            }
+            [
+                PROTOCOL_MARKER,
+                ACCESS_PREFIX,
+                ACCESS_PASSWORD,
+                password @ ..,
+            ] => Command::SetAccessPassword {
+                password: parse_password(password).ok_or_else(invalid)?,
+            },
+            [PROTOCOL_MARKER, ACCESS_PREFIX, ACCESS_LOCK, setting @ ..] => {
+                let setting = setting.strip_suffix(&[0]).unwrap_or(setting);
+                if setting.len() != 6 {
+                    return Err(invalid());
+                }
+                let setting = std::str::from_utf8(setting).map_err(|_| invalid())?;
+                let payload = u32::from_str_radix(setting, 16).map_err(|_| invalid())?;
+                Command::Lock {
+                    payload: LockPayload::from_bits(payload).map_err(|_| invalid())?,
+                }
+            }
+            [
+                PROTOCOL_MARKER,
+                ACCESS_PREFIX,
+                ACCESS_AUTHENTICATE,
+                password @ ..,
+            ] => Command::Authenticate {
+                password: parse_password(password).ok_or_else(invalid)?,
+            },
+            [PROTOCOL_MARKER, ACCESS_PREFIX, ACCESS_KILL, password @ ..] => Command::Kill {
+                password: parse_password(password).ok_or_else(invalid)?,
+            },
+            [PROTOCOL_MARKER, ACCESS_PREFIX, ACCESS_SELECT, b'0'] => Command::ClearSelect,
+            [PROTOCOL_MARKER, ACCESS_PREFIX, ACCESS_SELECT, args @ ..] => {
+                Command::Select(parse_select(args).ok_or_else(invalid)?)
+            }
+            [PROTOCOL_MARKER, INVENTORY_OPCODE, INVENTORY_START] => Command::InventoryStart,
+            [PROTOCOL_MARKER, INVENTORY_OPCODE, INVENTORY_NEXT] => Command::InventoryNext,
+            [PROTOCOL_MARKER, CONFIG_OPCODE, CONFIG_GET] => Command::GetConfig,
+            [
+                PROTOCOL_MARKER,
+                CONFIG_OPCODE,
+                CONFIG_SET,
+                persist @ (0 | 1),
+                settings @ ..,
+            ] => Command::SetConfig {
+                settings: parse_settings(settings).ok_or_else(invalid)?,
+                persist: *persist == 1,
+            },
+            [PROTOCOL_MARKER, IDENTIFY_OPCODE] => Command::Identify,
+            [PROTOCOL_MARKER, ACTION_OPCODE, action @ 1..=15, time_units] => Command::Action {
+                action: *action,
+                time_units: *time_units,
+            },
+            _ => return Err(invalid()),
+        };
+        Ok(command)
+    }
+}
+
+impl Response {
+    /// Encode the response into a frame, length byte included
+    ///
+    /// # Errors
+    /// Returns `UhfError::InvalidParameter` if the response does not fit a frame.
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let access =
+            |command: u8, status: u8| vec![PROTOCOL_MARKER, ACCESS_PREFIX, status, command];
+        let body = match self {
+            Response::Read { data } => {
+                let mut body = access(ACCESS_READ, STATUS_OK);
+                body.extend_from_slice(hex::encode_upper(data).as_bytes());
+                body
+            }
+            Response::Written => [access(ACCESS_WRITE, STATUS_OK), OK_SUFFIX.to_vec()].concat(),
+            Response::PasswordSet => access(ACCESS_PASSWORD, STATUS_OK),
+            Response::Locked => [access(ACCESS_LOCK, STATUS_OK), OK_SUFFIX.to_vec()].concat(),
+            Response::Authenticated => {
+                [access(ACCESS_AUTHENTICATE, STATUS_OK), OK_SUFFIX.to_vec()].concat()
+            }
+            Response::Killed => [access(ACCESS_KILL, STATUS_OK), OK_SUFFIX.to_vec()].concat(),
+            Response::Selected => [access(ACCESS_SELECT, STATUS_OK), OK_SUFFIX.to_vec()].concat(),
+            Response::AccessFailed { command, status } => access(*command, *status),
+            Response::InventoryStarted { tag_count } => {
+                vec![
+                    PROTOCOL_MARKER,
+                    INVENTORY_OPCODE,
+                    INVENTORY_START,
+                    *tag_count,
+                ]
+            }
+            Response::InventoryTag(tag) => {
+                if tag.epc.len() != usize::from(tag.pc >> 11) * 2 {
+                    return Err(UhfError::InvalidParameter(
+                        "EPC length does not match the PC word".to_string(),
+                    ));
+                }
+                let mut body = vec![PROTOCOL_MARKER, INVENTORY_OPCODE, INVENTORY_NEXT, 1];
+                body.extend_from_slice(&tag.pc.to_be_bytes());
+                body.extend_from_slice(&tag.epc);
+                body.extend_from_slice(&tag.crc.to_be_bytes());
+                if let Some(rssi) = tag.rssi {
+                    body.extend_from_slice(&rssi.to_be_bytes());
+                    body.extend(tag.antenna);
+                }
+                body
+            }
+            Response::InventoryEmpty => {
+                vec![PROTOCOL_MARKER, INVENTORY_OPCODE, INVENTORY_NEXT, 0]
+            }
+            Response::Config(settings) => {
+                let mut body = vec![PROTOCOL_MARKER, CONFIG_OPCODE, CONFIG_GET, STATUS_OK];
+                push_settings(&mut body, *settings);
+                body
+            }
+            Response::ConfigSet { persisted } => vec![
+                PROTOCOL_MARKER,
+                CONFIG_OPCODE,
+                CONFIG_SET,
+                STATUS_OK,
+                u8::from(*persisted),
+            ],
+            Response::ConfigFailed { command, status } => {
+                vec![PROTOCOL_MARKER, CONFIG_OPCODE, *command, *status]
+            }
+            Response::Identity(identity) => {
+                let mut body = vec![PROTOCOL_MARKER, IDENTIFY_OPCODE, STATUS_OK];
+                push_identity(&mut body, identity);
+                body
+            }
+            Response::IdentifyFailed { status } => {
+                vec![PROTOCOL_MARKER, IDENTIFY_OPCODE, *status]
+            }
+            Response::Action { status } => vec![PROTOCOL_MARKER, ACTION_OPCODE, *status],
+        };
+        with_length(body)
+    }
+
+    /// Whether this is the reply the reader sends to `command`, successful or not
+    ///
+    /// Used to tell the answer to the command just sent from a late reply to an earlier one.
+    #[must_use]
+    pub fn answers(&self, command: &Command) -> bool {
+        let access = |expected| matches!(self, Response::AccessFailed { command, .. } if *command == expected);
+        let config = |expected| matches!(self, Response::ConfigFailed { command, .. } if *command == expected);
+        match command {
+            Command::Read { .. } => matches!(self, Response::Read { .. }) || access(ACCESS_READ),
+            Command::Write { .. } => matches!(self, Response::Written) || access(ACCESS_WRITE),
+            Command::SetAccessPassword { .. } => {
+                matches!(self, Response::PasswordSet) || access(ACCESS_PASSWORD)
+            }
+            Command::Lock { .. } => matches!(self, Response::Locked) || access(ACCESS_LOCK),
+            Command::Authenticate { .. } => {
+                matches!(self, Response::Authenticated) || access(ACCESS_AUTHENTICATE)
+            }
+            Command::Kill { .. } => matches!(self, Response::Killed) || access(ACCESS_KILL),
+            Command::Select(_) | Command::ClearSelect => {
+                matches!(self, Response::Selected) || access(ACCESS_SELECT)
+            }
+            Command::InventoryStart => matches!(self, Response::InventoryStarted { .. }),
+            Command::InventoryNext => {
+                matches!(self, Response::InventoryTag(_) | Response::InventoryEmpty)
+            }
+            Command::GetConfig => matches!(self, Response::Config(_)) || config(CONFIG_GET),
+            Command::SetConfig { .. } => {
+                matches!(self, Response::ConfigSet { .. }) || config(CONFIG_SET)
+            }
+            Command::Identify => {
+                matches!(
+                    self,
+                    Response::Identity(_) | Response::IdentifyFailed { .. }
+                )
+            }
+            Command::Action { .. } => matches!(self, Response::Action { .. }),
+        }
+    }
+
+    /// Decode a response frame, length byte included; bytes past the length are ignored
+    ///
+    /// # Errors
+    /// Returns `UhfError::InvalidResponse` carrying the frame if it is not a well-formed response.
+    pub fn decode(frame: &[u8]) -> Result<Self> {
+        let invalid = || UhfError::InvalidResponse(frame_bytes(frame).to_vec());
+        let payload = frame_payload(frame).ok_or_else(invalid)?;
+        let response = match payload {
+            [PROTOCOL_MARKER, ACCESS_PREFIX, status, command, body @ ..] => {
+                if *status != STATUS_OK {
+                    return Ok(Response::AccessFailed {
+                        command: *command,
+                        status: *status,
+                    });
+                }
+                let ok = body.get(1..3) == Some(b"OK".as_slice());
+                match *command {
+                    ACCESS_READ => Response::Read {
+                        data: hex::decode(body).map_err(|_| invalid())?,
+                    },
+                    ACCESS_WRITE if ok => Response::Written,
+                    ACCESS_PASSWORD => Response::PasswordSet,
+                    ACCESS_LOCK if ok => Response::Locked,
+                    ACCESS_AUTHENTICATE if ok => Response::Authenticated,
+                    ACCESS_KILL if ok => Response::Killed,
+                    ACCESS_SELECT if ok => Response::Selected,
+                    _ => return Err(invalid()),
+                }
+            }
+            [
+                PROTOCOL_MARKER,
+                INVENTORY_OPCODE,
+                INVENTORY_START,
+                tag_count,
+                ..,
+            ] => Response::InventoryStarted {
+                tag_count: *tag_count,
+            },
+            [PROTOCOL_MARKER, INVENTORY_OPCODE, INVENTORY_NEXT, 0, ..] => Response::InventoryEmpty,
+            [
+                PROTOCOL_MARKER,
+                INVENTORY_OPCODE,
+                INVENTORY_NEXT,
+                _,
+                record @ ..,
+            ] => Response::InventoryTag(parse_tag_record(record).ok_or_else(invalid)?),
+            [PROTOCOL_MARKER, CONFIG_OPCODE, command, status, body @ ..] => {
+                match (*command, *status, body) {
+                    (command, status, _) if status != STATUS_OK => {
+                        Response::ConfigFailed { command, status }
+                    }
+                    (CONFIG_GET, _, settings) => {
+                        Response::Config(parse_settings(settings).ok_or_else(invalid)?)
+                    }
+                    (CONFIG_SET, _, [persisted @ (0 | 1), ..]) => Response::ConfigSet {
+                        persisted: *persisted == 1,
+                    },
+                    _ => return Err(invalid()),
+                }
+            }
+            [PROTOCOL_MARKER, IDENTIFY_OPCODE, STATUS_OK, body @ ..] => {
+                Response::Identity(parse_identity(body).ok_or_else(invalid)?)
+            }
+            [PROTOCOL_MARKER, IDENTIFY_OPCODE, status, ..] => {
+                Response::IdentifyFailed { status: *status }
+            }
+            [PROTOCOL_MARKER, ACTION_OPCODE, status, ..] => Response::Action { status: *status },
+            _ => return Err(invalid()),
+        };
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{LockAction, LockableMemoryBank, PasswordLockAction};
+
+    fn commands() -> Vec<Command> {
+        vec![
+            Command::Read {
+                bank: MemoryBank::Epc,
+                address: 2,
+                words: 6,
+            },
+            Command::Read {
+                bank: MemoryBank::User,
+                address: 0x3A,
+                words: 15,
+            },
+            Command::Write {
+                bank: MemoryBank::Reserved,
+                address: 0x10,
+                data: vec![0x12, 0x34, 0xAB, 0xCD],
+            },
+            Command::SetAccessPassword {
+                password: 0xDEAD_BEEF,
+            },
+            Command::Lock {
+                payload: LockPayload::from_bits(0xC_0020).unwrap(),
+            },
+            Command::Authenticate {
+                password: 0x1234_5678,
+            },
+            Command::Kill {
+                password: 0x0BAD_F00D,
+            },
+            Command::Select(TagFilter::epc(&[0x30, 0x08, 0x33, 0xB2])),
+            Command::Select(TagFilter {
+                bank: MemoryBank::Tid,
+                bit_pointer: 0x1234,
+                bit_length: 12,
+                mask: vec![0xE2, 0x80],
+            }),
+            Command::ClearSelect,
+            Command::InventoryStart,
+            Command::InventoryNext,
+            Command::GetConfig,
+            Command::SetConfig {
+                settings: dock_settings(),
+                persist: true,
+            },
+            Command::Identify,
+            Command::Action {
+                action: 0x05,
+                time_units: 50,
+            },
+        ]
+    }
+
+    fn responses() -> Vec<Response> {
+        vec![
+            Response::Read {
+                data: vec![0x30, 0x08, 0x33, 0xB2],
+            },
+            Response::Read { data: Vec::new() },
+            Response::Written,
+            Response::PasswordSet,
+            Response::Locked,
+            Response::Authenticated,
+            Response::Killed,
+            Response::Selected,
+            Response::AccessFailed {
+                command: ACCESS_WRITE,
+                status: 0x04,
+            },
+            Response::InventoryStarted { tag_count: 3 },
+            Response::InventoryTag(TagReport {
+                pc: 0x0800,
+                epc: vec![0xE2, 0x00],
+                crc: 0xBEEF,
+                rssi: None,
+                antenna: None,
+            }),
+            Response::InventoryTag(TagReport {
+                pc: 0x3000,
+                epc: vec![0x30, 0x08, 0x33, 0xB2, 0xDD, 0xD9, 0x01, 0x40, 0, 0, 0, 1],
+                crc: 0x1234,
+                rssi: Some(-61),
+                antenna: Some(2),
+            }),
+            Response::InventoryEmpty,
+            Response::Config(dock_settings()),
+            Response::ConfigSet { persisted: false },
+            Response::ConfigFailed {
+                command: CONFIG_SET,
+                status: STATUS_BAD_COMMAND,
+            },
+            Response::Identity(handheld_identity()),
+            Response::IdentifyFailed {
+                status: STATUS_BAD_COMMAND,
+            },
+            Response::Action { status: 0 },
+        ]
+    }
+
+    /// Settings for a dock door with neighbouring readers
+    fn dock_settings() -> ReaderSettings {
+        ReaderSettings {
+            rf: RfSettings {
+                tx_power_dbm: 20,
+                region: Region::Etsi,
+                hopping: false,
+                channel: 3,
+            },
+            gen2: Gen2Settings {
+                session: Session::S2,
+                target: InventoryTarget::A,
+                q: 6,
+            },
+        }
+    }
+
+    /// Identity of a handheld reader without persistent settings or kill support
+    fn handheld_identity() -> ReaderIdentity {
+        ReaderIdentity {
+            firmware: FirmwareVersion {
+                major: 1,
+                minor: 4,
+                patch: 2,
+            },
+            hardware_revision: 3,
+            regions: vec![Region::Fcc, Region::Japan],
+            max_tx_power_dbm: 27,
+            features: ReaderFeature::ALL
+                .into_iter()
+                .filter(|feature| {
+                    !matches!(
+                        feature,
+                        ReaderFeature::Kill | ReaderFeature::PersistSettings
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn commands_round_trip() {
+        for command in commands() {
+            let frame = command.encode().unwrap();
+            assert_eq!(usize::from(frame[0]), frame.len() - 1);
+            assert_eq!(Command::decode(&frame).unwrap(), command);
+        }
+    }
+
+    #[test]
+    fn responses_round_trip() {
+        for response in responses() {
+            let mut frame = response.encode().unwrap();
+            frame.resize(64, 0); // Report padding is ignored
+            assert_eq!(Response::decode(&frame).unwrap(), response);
+        }
+    }
+
+    #[test]
+    fn command_frames_match_wire_layout() {
+        let read = Command::Read {
+            bank: MemoryBank::Tid,
+            address: 0x1F,
+            words: 12,
+        };
+        assert_eq!(read.encode().unwrap(), b"\x09\x02AR2,1F,C");
+        let write = Command::Write {
+            bank: MemoryBank::User,
+            address: 4,
+            data: vec![0xCA, 0xFE],
+        };
+        assert_eq!(write.encode().unwrap(), b"\x0D\x02AW3,4,1,CAFE");
+        let lock = Command::Lock {
+            payload: LockPayload::new()
+                .with_action(LockableMemoryBank::Epc, LockAction::SecureWriteable),
+        };
+        assert_eq!(lock.encode().unwrap(), b"\x0A\x02AL00C020\x00");
+        let select = Command::Select(TagFilter::epc(&[0xE2, 0x00]));
+        assert_eq!(select.encode().unwrap(), b"\x0F\x02AS1,20,10,E200");
+        assert_eq!(Command::ClearSelect.encode().unwrap(), b"\x04\x02AS0");
+        let kill = Command::Kill {
+            password: 0x1234_ABCD,
+        };
+        assert_eq!(kill.encode().unwrap(), b"\x0B\x02AK1234ABCD");
+        let action = Command::Action {
+            action: 3,
+            time_units: 10,
+        };
+        assert_eq!(action.encode().unwrap(), [4, 2, 145, 3, 10]);
+        let config = Command::SetConfig {
+            settings: dock_settings(),
+            persist: false,
+        };
+        assert_eq!(
+            config.encode().unwrap(),
+            [11, 2, 0x56, 0x02, 0, 20, 2, 0, 3, 2, 0, 6]
+        );
+    }
+
+    #[test]
+    fn identity_frame_matches_wire_layout() {
+        assert_eq!(Command::Identify.encode().unwrap(), [2, 2, 0x57]);
+        let frame = Response::Identity(handheld_identity()).encode().unwrap();
+        // Regions FCC (1) and Japan (4); every feature but kill (bit 4) and persist (bit 9)
+        assert_eq!(
+            frame,
+            [11, 2, 0x57, 0, 1, 4, 2, 3, 27, 0b1_0010, 0x05, 0xEF]
+        );
+
+        // Bits of features this host does not know about are ignored
+        let newer = [11, 2, 0x57, 0, 2, 0, 0, 1, 30, 0b10, 0xF8, 0x01];
+        let Response::Identity(identity) = Response::decode(&newer).unwrap() else {
+            panic!("expected an identity");
+        };
+        assert_eq!(identity.firmware.to_string(), "2.0.0");
+        assert_eq!(identity.regions, [Region::Fcc]);
+        assert_eq!(identity.features, [ReaderFeature::Inventory]);
+        assert!(!identity.supports(ReaderFeature::Read));
+        assert!(Response::decode(&[5, 2, 0x57, 0, 1, 4]).is_err());
+    }
+
+    #[test]
+    fn replies_answer_only_their_own_command() {
+        let answered = |response: &Response| -> Vec<_> {
+            let mut names: Vec<_> = commands()
+                .iter()
+                .filter(|command| response.answers(command))
+                .map(Command::name)
+                .collect();
+            names.dedup();
+            names
+        };
+        for response in responses() {
+            let mut kinds: Vec<_> = commands()
+                .iter()
+                .filter(|command| response.answers(command))
+                .map(Command::kind)
+                .collect();
+            kinds.dedup();
+            assert_eq!(kinds.len(), 1, "{response:?}");
+        }
+        assert_eq!(answered(&Response::Written), ["write"]);
+        let write_failed = Response::AccessFailed {
+            command: ACCESS_WRITE,
+            status: STATUS_NO_TAG,
+        };
+        assert_eq!(answered(&write_failed), ["write"]);
+        let get_failed = Response::ConfigFailed {
+            command: CONFIG_GET,
+            status: STATUS_BAD_COMMAND,
+        };
+        assert_eq!(answered(&get_failed), ["get_config"]);
+        // The select filter is set and cleared with the same reply
+        let selected: Vec<_> = commands()
+            .iter()
+            .filter(|command| Response::Selected.answers(command))
+            .map(Command::name)
+            .collect();
+        assert_eq!(selected, ["select", "select", "clear_select"]);
+    }
+
+    #[test]
+    fn password_bearing_commands_are_flagged_as_secret() {
+        let secret: Vec<_> = commands()
+            .into_iter()
+            .filter(Command::carries_secret)
+            .map(|command| command.name())
+            .collect();
+        assert_eq!(
+            secret,
+            ["write", "set_access_password", "authenticate", "kill"]
+        );
+        let reserved_read = Command::Read {
+            bank: MemoryBank::Reserved,
+            address: 0,
+            words: 4,
+        };
+        assert!(reserved_read.carries_secret());
+    }
+
+    #[test]
+    fn only_vendor_documented_commands_are_verified() {
+        let mut verified: Vec<_> = commands()
+            .into_iter()
+            .filter(Command::is_verified)
+            .map(|command| command.name())
+            .collect();
+        verified.dedup();
+        assert_eq!(
+            verified,
+            [
+                "read",
+                "write",
+                "set_access_password",
+                "lock",
+                "inventory_start",
+                "inventory_next",
+                "action"
+            ]
+        );
+    }
+
+    #[test]
+    fn lock_payload_combines_banks_and_decodes_back() {
+        let payload = LockPayload::new()
+            .with_action(LockableMemoryBank::User, LockAction::NotWriteable)
+            .with_action(LockableMemoryBank::Epc, LockAction::PermanentlyWriteable)
+            .with_password_action(
+                LockableMemoryBank::KillPassword,
+                PasswordLockAction::SecureReadWriteable,
+            )
+            .unwrap();
+        // Mask 11_0011_0011 (Kill, EPC, User), action 10_0001_0011
+        assert_eq!(payload.bits(), 0xC_CE13);
+        let frame = Command::Lock { payload }.encode().unwrap();
+        assert_eq!(frame, b"\x0A\x02AL0CCE13\x00");
+
+        let Command::Lock { payload } = Command::decode(&frame).unwrap() else {
+            panic!("not a lock command");
+        };
+        assert_eq!(
+            payload.action(LockableMemoryBank::User),
+            Some(LockAction::NotWriteable)
+        );
+        assert_eq!(
+            payload.action(LockableMemoryBank::Epc),
+            Some(LockAction::PermanentlyWriteable)
+        );
+        assert_eq!(
+            payload.password_action(LockableMemoryBank::KillPassword),
+            Some(PasswordLockAction::SecureReadWriteable)
+        );
+        assert_eq!(payload.action(LockableMemoryBank::Tid), None);
+        assert_eq!(payload.password_action(LockableMemoryBank::Epc), None);
+        assert_eq!(
+            payload.to_string(),
+            "KillPassword: SecureReadWriteable, Epc: PermanentlyWriteable, User: NotWriteable"
+        );
+    }
+
+    #[test]
+    fn out_of_range_fields_are_rejected() {
+        let too_many = Command::Read {
+            bank: MemoryBank::User,
+            address: 0,
+            words: 16,
+        };
+        assert!(too_many.encode().is_err());
+        let odd = Command::Write {
+            bank: MemoryBank::User,
+            address: 0,
+            data: vec![1, 2, 3],
+        };
+        assert!(odd.encode().is_err());
+        assert!(LockPayload::from_bits(1 << 20).is_err());
+        assert!(Command::decode(b"\x0A\x02AL100000\x00").is_err());
+        let no_action = Command::Action {
+            action: 0,
+            time_units: 1,
+        };
+        assert!(no_action.encode().is_err());
+        let reserved = TagFilter {
+            bank: MemoryBank::Reserved,
+            ..TagFilter::epc(&[1])
+        };
+        assert!(Command::Select(reserved).encode().is_err());
+        let short_mask = TagFilter {
+            bit_length: 9,
+            ..TagFilter::epc(&[1])
+        };
+        assert!(Command::Select(short_mask).encode().is_err());
+        assert!(Command::Select(TagFilter::epc(&[0; 17])).encode().is_err());
+        let mut settings = dock_settings();
+        settings.rf.channel = 4; // ETSI has channels 0-3
+        let set = |settings| Command::SetConfig {
+            settings,
+            persist: false,
+        };
+        assert!(set(settings).encode().is_err());
+        settings.rf.channel = 0;
+        settings.rf.tx_power_dbm = 31;
+        assert!(set(settings).encode().is_err());
+        settings.rf.tx_power_dbm = 30;
+        settings.gen2.q = 16;
+        assert!(set(settings).encode().is_err());
+        // Region 9 does not exist
+        assert!(Command::decode(&[11, 2, 0x56, 0x02, 0, 20, 9, 0, 0, 2, 0, 6]).is_err());
+    }
+
+    #[test]
+    fn status_bytes_map_to_errors() {
+        assert!(matches!(
+            UhfError::from_status(STATUS_MEMORY_LOCKED, Vec::new()),
+            UhfError::MemoryLocked { .. }
+        ));
+        for status in 1..=u8::MAX {
+            let frame = vec![4, 2, b'A', status, b'W'];
+            let error = UhfError::from_status(status, frame.clone());
+            assert_eq!(error.status(), Some(status));
+            assert_eq!(error.frame(), Some(frame.as_slice()));
+        }
+    }
+
+    #[test]
+    fn inventory_record_is_sized_by_pc_word() {
+        // PC announces 2 words but only one EPC word and no CRC follow
+        let frame = [8, 2, 0x55, 0x91, 1, 0x10, 0x00, 0xE2, 0x00];
+        assert!(Response::decode(&frame).is_err());
+        let frame = [
+            12, 2, 0x55, 0x91, 1, 0x08, 0x00, 0xE2, 0x00, 0xAB, 0xCD, 0xC8, 0x01,
+        ];
+        let Response::InventoryTag(tag) = Response::decode(&frame).unwrap() else {
+            panic!("expected a tag");
+        };
+        assert_eq!(tag.epc, [0xE2, 0x00]);
+        assert_eq!(tag.crc, 0xABCD);
+        assert_eq!(tag.rssi, Some(-56));
+        assert_eq!(tag.antenna, Some(1));
+    }
+
+    #[test]
+    fn malformed_responses_are_rejected() {
+        assert!(Response::decode(&[]).is_err());
+        assert!(Response::decode(&[9, 2, b'A', 0]).is_err());
+        assert!(Response::decode(&[5, 2, b'A', 0, b'R', b'G']).is_err());
+        assert!(matches!(
+            Response::decode(&[3, 7, 7, 7, 0, 0]),
+            Err(UhfError::InvalidResponse(frame)) if frame == [3, 7, 7, 7]
+        ));
+    }
+}
@@ -0,0 +1,686 @@
+//! Human-readable breakdown of command and response frames.
+//!
+//! [`dissect`] labels every byte of a frame: the length byte, the protocol marker, the
+//! opcode, the fields of the command or reply (bank, address, word count, passwords,
+//! settings, tag data) and the status byte, followed by any report padding. Frames that
+//! do not decode still get their header labelled, with the rest shown as an undecoded
+//! payload, so the breakdown doubles as a debugging aid for hand-built frames.
+//!
+//! ```text
+//! Command: read
+//!    0  08                        length    8 bytes follow
+//!    1  02                        marker    protocol marker
+//!    2  41 52                     opcode    AR (read)
+//!    4  31                        bank      EPC
+//!    5  2C                        separator ','
+//!    6  32                        address   word 2
+//!    7  2C                        separator ','
+//!    8  36                        count     6 words
+//! ```
+
+use crate::codec::{
+    ACCESS_AUTHENTICATE, ACCESS_KILL, ACCESS_LOCK, ACCESS_PASSWORD, ACCESS_PREFIX, ACCESS_READ,
+    ACCESS_SELECT, ACCESS_WRITE, ACTION_OPCODE, CONFIG_GET, CONFIG_OPCODE, CONFIG_SET, Command,
+    IDENTIFY_OPCODE, INVENTORY_NEXT, INVENTORY_OPCODE, INVENTORY_START, PROTOCOL_MARKER, Response,
+    STATUS_OK,
+};
+use crate::framing::{Direction, MARKER_CONTINUE};
+use crate::types::{DeviceAction, ReaderSettings, UhfError};
+use std::fmt;
+
+/// Labelled run of bytes within a frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    /// Offset of the first byte within the frame
+    pub offset: usize,
+    /// Raw bytes of the field
+    pub bytes: Vec<u8>,
+    /// Role of the field: `length`, `marker`, `opcode`, `bank`, `address`, `count`,
+    /// `payload`, `status`, `padding`, ...
+    pub name: &'static str,
+    /// What the bytes mean
+    pub meaning: String,
+}
+
+impl Field {
+    /// Bytes of the field as space-separated hex
+    #[must_use]
+    pub fn hex(&self) -> String {
+        let bytes: Vec<_> = self.bytes.iter().map(|b| format!("{b:02X}")).collect();
+        bytes.join(" ")
+    }
+}
+
+/// Breakdown of a frame into labelled fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dissection {
+    /// Which way the frame travels, if it decoded as a command or a reply
+    pub direction: Option<Direction>,
+    /// One-line description of the frame
+    pub summary: String,
+    /// Fields in frame order, covering every byte
+    pub fields: Vec<Field>,
+}
+
+impl fmt::Display for Dissection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.summary)?;
+        for field in &self.fields {
+            write!(
+                f,
+                "\n{:>4}  {:<24}  {:<9} {}",
+                field.offset,
+                field.hex(),
+                field.name,
+                field.meaning
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Dissect a frame sent in either direction, trying it as a command first
+///
+/// Commands are tried first because their ASCII fields are stricter than the reply layouts.
+#[must_use]
+pub fn dissect(frame: &[u8]) -> Dissection {
+    if Command::decode(frame).is_ok() {
+        dissect_command(frame)
+    } else {
+        dissect_response(frame)
+    }
+}
+
+/// Dissect a frame sent from the host to the reader
+#[must_use]
+pub fn dissect_command(frame: &[u8]) -> Dissection {
+    let mut walker = Walker::new(frame);
+    let Some(body) = walker.header() else {
+        return walker.finish(None, describe_header_only(frame));
+    };
+    if let Ok(command) = Command::decode(frame) {
+        walker.command_fields(&command, body);
+        let summary = format!("Command: {}", command.name());
+        walker.finish(Some(Direction::HostToReader), summary)
+    } else {
+        walker.opcode(body);
+        walker.undecoded();
+        walker.finish(None, "Command with an unknown layout".to_string())
+    }
+}
+
+/// Dissect a frame sent from the reader to the host
+#[must_use]
+pub fn dissect_response(frame: &[u8]) -> Dissection {
+    let mut walker = Walker::new(frame);
+    let Some(body) = walker.header() else {
+        return walker.finish(None, describe_header_only(frame));
+    };
+    if let Ok(response) = Response::decode(frame) {
+        let summary = format!("Response: {}", walker.response_fields(&response, body));
+        walker.finish(Some(Direction::ReaderToHost), summary)
+    } else {
+        walker.reply_opcode(body);
+        walker.undecoded();
+        walker.finish(None, "Response with an unknown layout".to_string())
+    }
+}
+
+fn describe_header_only(frame: &[u8]) -> String {
+    match frame.first() {
+        None => "Empty frame".to_string(),
+        Some(&MARKER_CONTINUE) => "Continuation report of a multi-report command".to_string(),
+        Some(_) => "Frame without a payload".to_string(),
+    }
+}
+
+/// Meaning of a status byte
+fn status_meaning(status: u8) -> String {
+    if status == STATUS_OK {
+        "OK".to_string()
+    } else {
+        UhfError::from_status(status, Vec::new()).to_string()
+    }
+}
+
+/// Name of the access command with the given command byte
+fn access_name(command: u8) -> &'static str {
+    match command {
+        ACCESS_READ => "read",
+        ACCESS_WRITE => "write",
+        ACCESS_PASSWORD => "set access password",
+        ACCESS_LOCK => "lock",
+        ACCESS_AUTHENTICATE => "authenticate",
+        ACCESS_KILL => "kill",
+        ACCESS_SELECT => "select",
+        _ => "unknown access command",
+    }
+}
+
+/// Name of the inventory sub-command with the given byte
+fn inventory_name(command: u8) -> &'static str {
+    match command {
+        INVENTORY_START => "start round",
+        INVENTORY_NEXT => "next tag",
+        _ => "unknown inventory command",
+    }
+}
+
+/// Name of the configuration sub-command with the given byte
+fn config_name(command: u8) -> &'static str {
+    match command {
+        CONFIG_GET => "read settings",
+        CONFIG_SET => "apply settings",
+        _ => "unknown configuration command",
+    }
+}
+
+fn ascii(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| {
+            if b.is_ascii_graphic() {
+                char::from(b)
+            } else {
+                '.'
+            }
+        })
+        .collect()
+}
+
+fn plural(count: usize, unit: &str) -> String {
+    if count == 1 {
+        format!("1 {unit}")
+    } else {
+        format!("{count} {unit}s")
+    }
+}
+
+/// Cursor handing out consecutive fields of a frame
+struct Walker<'a> {
+    frame: &'a [u8],
+    offset: usize,
+    /// End of the bytes covered by the length byte
+    end: usize,
+    fields: Vec<Field>,
+}
+
+impl<'a> Walker<'a> {
+    fn new(frame: &'a [u8]) -> Self {
+        Self {
+            frame,
+            offset: 0,
+            end: frame.len(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Bytes of the frame body not labelled yet
+    fn rest(&self) -> &'a [u8] {
+        &self.frame[self.offset..self.end]
+    }
+
+    /// Label the next `len` bytes of the body, or as many as are left
+    fn take(&mut self, len: usize, name: &'static str, meaning: impl Into<String>) {
+        let end = self.offset.saturating_add(len).min(self.end);
+        if end > self.offset {
+            self.fields.push(Field {
+                offset: self.offset,
+                bytes: self.frame[self.offset..end].to_vec(),
+                name,
+                meaning: meaning.into(),
+            });
+        }
+        self.offset = end;
+    }
+
+    /// Label the rest of the body
+    fn take_rest(&mut self, name: &'static str, meaning: impl Into<String>) {
+        self.take(usize::MAX, name, meaning);
+    }
+
+    /// Label the bytes up to the next comma or the end of the body, then the comma
+    fn ascii_field(&mut self, name: &'static str, meaning: impl Into<String>) {
+        let rest = self.rest();
+        let len = rest.iter().position(|&b| b == b',').unwrap_or(rest.len());
+        self.take(len, name, meaning);
+        if self.rest().first() == Some(&b',') {
+            self.take(1, "separator", "','");
+        }
+    }
+
+    /// Label the length byte and the protocol marker, returning the body after the marker
+    ///
+    /// Returns `None` for an empty frame, a continuation report or a frame without payload.
+    fn header(&mut self) -> Option<&'a [u8]> {
+        let &first = self.frame.first()?;
+        if first == MARKER_CONTINUE {
+            self.take(1, "marker", "more reports follow");
+            self.take_rest("payload", "next piece of the command frame");
+            return None;
+        }
+        let len = usize::from(first);
+        let available = self.frame.len() - 1;
+        let meaning = if len > available {
+            format!("{} follow, only {available} present", plural(len, "byte"))
+        } else {
+            format!("{} follow", plural(len, "byte"))
+        };
+        self.take(1, "length", meaning);
+        self.end = (1 + len).min(self.frame.len());
+        let &marker = self.rest().first()?;
+        let meaning = if marker == PROTOCOL_MARKER {
+            "protocol marker".to_string()
+        } else {
+            format!("expected 0x{PROTOCOL_MARKER:02X}")
+        };
+        self.take(1, "marker", meaning);
+        Some(self.rest())
+    }
+
+    /// Label the opcode of a command body and its sub-command, if it has one
+    fn opcode(&mut self, body: &[u8]) {
+        match body {
+            [ACCESS_PREFIX, command, ..] => {
+                let name = access_name(*command);
+                self.take(2, "opcode", format!("A{} ({name})", char::from(*command)));
+            }
+            [INVENTORY_OPCODE, command, ..] => {
+                self.take(1, "opcode", "inventory");
+                self.take(1, "command", inventory_name(*command));
+            }
+            [CONFIG_OPCODE, command, ..] => {
+                self.take(1, "opcode", "configuration");
+                self.take(1, "command", config_name(*command));
+            }
+            [IDENTIFY_OPCODE, ..] => self.take(1, "opcode", "identify"),
+            [ACTION_OPCODE, ..] => self.take(1, "opcode", "device action"),
+            _ => {}
+        }
+    }
+
+    /// Label the opcode of a reply body, with the status and command byte of access replies
+    fn reply_opcode(&mut self, body: &[u8]) {
+        if let [ACCESS_PREFIX, status, command, ..] = *body {
+            let name = access_name(command);
+            self.take(1, "opcode", "A (access reply)");
+            self.take(1, "status", status_meaning(status));
+            self.take(1, "command", format!("{} ({name})", char::from(command)));
+        } else {
+            self.opcode(body);
+        }
+    }
+
+    /// Label whatever is left of the body as an undecoded payload
+    fn undecoded(&mut self) {
+        let rest = self.rest();
+        let meaning = format!("{}, \"{}\"", plural(rest.len(), "byte"), ascii(rest));
+        self.take_rest("payload", meaning);
+    }
+
+    fn command_fields(&mut self, command: &Command, body: &[u8]) {
+        self.opcode(body);
+        match command {
+            Command::Read {
+                bank,
+                address,
+                words,
+            } => {
+                self.ascii_field("bank", bank.to_string());
+                self.ascii_field("address", format!("word {address}"));
+                let count = if *words == 0 {
+                    "to the end of the bank".to_string()
+                } else {
+                    plural(usize::from(*words), "word")
+                };
+                self.ascii_field("count", count);
+            }
+            Command::Write {
+                bank,
+                address,
+                data,
+            } => {
+                self.ascii_field("bank", bank.to_string());
+                self.ascii_field("address", format!("word {address}"));
+                self.ascii_field("count", plural(data.len() / 2, "word"));
+                self.take_rest("payload", hex::encode_upper(data));
+            }
+            Command::SetAccessPassword { password }
+            | Command::Authenticate { password }
+            | Command::Kill { password } => {
+                self.take_rest("password", format!("0x{password:08X}"));
+            }
+            Command::Lock { payload } => {
+                self.take(6, "payload", format!("0x{:05X}: {payload}", payload.bits()));
+                self.take(1, "padding", "pad byte");
+            }
+            Command::Select(filter) => {
+                self.take(1, "bank", filter.bank.to_string());
+                self.take(1, "separator", "','");
+                self.ascii_field("pointer", format!("bit {}", filter.bit_pointer));
+                self.ascii_field("count", plural(usize::from(filter.bit_length), "bit"));
+                let mask = hex::encode_upper(&filter.mask);
+                self.take_rest("payload", format!("mask {mask}"));
+            }
+            Command::ClearSelect => self.take(1, "payload", "clear the select filter"),
+            Command::SetConfig { settings, persist } => {
+                let meaning = if *persist {
+                    "store in non-volatile memory"
+                } else {
+                    "until power-off"
+                };
+                self.take(1, "persist", meaning);
+                self.settings_fields(*settings);
+            }
+            Command::Action { action, time_units } => {
+                let actions: Vec<_> = DeviceAction::ALL
+                    .into_iter()
+                    .filter(|flag| action & (*flag as u8) != 0)
+                    .map(|flag| format!("{flag:?}"))
+                    .collect();
+                self.take(1, "action", actions.join(" + "));
+                let ms = u32::from(*time_units) * 10;
+                self.take(1, "duration", format!("{ms} ms"));
+            }
+            Command::InventoryStart
+            | Command::InventoryNext
+            | Command::GetConfig
+            | Command::Identify => {}
+        }
+    }
+
+    fn settings_fields(&mut self, settings: ReaderSettings) {
+        self.take(1, "power", format!("{} dBm", settings.rf.tx_power_dbm));
+        self.take(1, "region", settings.rf.region.to_string());
+        self.take(1, "hopping", if settings.rf.hopping { "on" } else { "off" });
+        self.take(1, "channel", settings.rf.channel.to_string());
+        self.take(1, "session", format!("{:?}", settings.gen2.session));
+        self.take(1, "target", format!("{:?}", settings.gen2.target));
+        self.take(1, "q", settings.gen2.q.to_string());
+    }
+
+    /// Label the fields of a decoded reply, returning its one-line description
+    fn response_fields(&mut self, response: &Response, body: &[u8]) -> String {
+        self.reply_opcode(body);
+        if let [ACCESS_PREFIX, status, command, ..] = *body {
+            let name = access_name(command);
+            if let Response::Read { data } = response {
+                let words = plural(data.len() / 2, "word");
+                self.take_rest("payload", format!("{words}: {}", hex::encode_upper(data)));
+            } else {
+                let suffix = ascii(self.rest());
+                self.take_rest("suffix", format!("\"{suffix}\""));
+            }
+            return format!("{name}, {}", status_meaning(status));
+        }
+        match response {
+            Response::InventoryStarted { tag_count } => {
+                let tags = plural(usize::from(*tag_count), "tag");
+                self.take(1, "count", format!("{tags} in the round"));
+                format!("inventory round started, {tags}")
+            }
+            Response::InventoryTag(tag) => {
+                self.take(1, "count", "tag follows");
+                let words = plural(usize::from(tag.pc >> 11), "EPC word");
+                self.take(2, "pc", format!("0x{:04X}, {words}", tag.pc));
+                self.take(tag.epc.len(), "epc", hex::encode_upper(&tag.epc));
+                self.take(2, "crc", format!("0x{:04X}", tag.crc));
+                if let Some(rssi) = tag.rssi {
+                    self.take(1, "rssi", format!("{rssi} dBm"));
+                }
+                if let Some(antenna) = tag.antenna {
+                    self.take(1, "antenna", format!("port {antenna}"));
+                }
+                format!("tag {}", hex::encode_upper(&tag.epc))
+            }
+            Response::InventoryEmpty => {
+                self.take(1, "count", "no more tags");
+                "inventory round finished".to_string()
+            }
+            Response::Config(settings) => {
+                self.take(1, "status", "OK");
+                self.settings_fields(*settings);
+                "settings read".to_string()
+            }
+            Response::ConfigSet { persisted } => {
+                self.take(1, "status", "OK");
+                let meaning = if *persisted {
+                    "stored"
+                } else {
+                    "until power-off"
+                };
+                self.take(1, "persist", meaning);
+                "settings applied".to_string()
+            }
+            Response::ConfigFailed { command, status } => {
+                self.take(1, "status", status_meaning(*status));
+                format!("{}, {}", config_name(*command), status_meaning(*status))
+            }
+            Response::Identity(identity) => {
+                self.take(1, "status", "OK");
+                self.take(3, "firmware", identity.firmware.to_string());
+                let hardware = format!("revision {}", identity.hardware_revision);
+                self.take(1, "hardware", hardware);
+                self.take(
+                    1,
+                    "power",
+                    format!("up to {} dBm", identity.max_tx_power_dbm),
+                );
+                let regions: Vec<_> = identity.regions.iter().map(|r| r.label()).collect();
+                self.take(1, "regions", regions.join(", "));
+                let features: Vec<_> = identity.features.iter().map(ToString::to_string).collect();
+                self.take(2, "features", features.join(", "));
+                format!("identify, firmware {}", identity.firmware)
+            }
+            Response::IdentifyFailed { status } => {
+                self.take(1, "status", status_meaning(*status));
+                format!("identify, {}", status_meaning(*status))
+            }
+            Response::Action { status } => {
+                self.take(1, "status", status_meaning(*status));
+                format!("device action, {}", status_meaning(*status))
+            }
+            _ => {
+                self.undecoded();
+                "unrecognised reply".to_string()
+            }
+        }
+    }
+
+    /// Label the bytes left in the body as trailing, then any report padding past it
+    fn finish(mut self, direction: Option<Direction>, summary: String) -> Dissection {
+        if self.offset < self.end {
+            self.take_rest("trailing", "not part of the layout");
+        }
+        if self.end < self.frame.len() {
+            let padding = &self.frame[self.end..];
+            let meaning = if padding.iter().all(|&b| b == 0) {
+                format!("{} of report padding", plural(padding.len(), "byte"))
+            } else {
+                format!("{} past the frame length", plural(padding.len(), "byte"))
+            };
+            self.end = self.frame.len();
+            self.take_rest("padding", meaning);
+        }
+        Dissection {
+            direction,
+            summary,
+            fields: self.fields,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{MemoryBank, TagReport};
+
+    fn names(dissection: &Dissection) -> Vec<&'static str> {
+        dissection.fields.iter().map(|field| field.name).collect()
+    }
+
+    /// Every byte of the frame belongs to exactly one field, in order
+    fn assert_covers(dissection: &Dissection, frame: &[u8]) {
+        let mut offset = 0;
+        for field in &dissection.fields {
+            assert_eq!(field.offset, offset, "{dissection}");
+            offset += field.bytes.len();
+        }
+        let bytes: Vec<u8> = dissection
+            .fields
+            .iter()
+            .flat_map(|f| f.bytes.clone())
+            .collect();
+        assert_eq!(bytes, frame, "{dissection}");
+    }
+
+    #[test]
+    fn read_commands_are_broken_into_their_fields() {
+        let frame = Command::Read {
+            bank: MemoryBank::Epc,
+            address: 2,
+            words: 6,
+        }
+        .encode()
+        .unwrap();
+        let dissection = dissect(&frame);
+        assert_eq!(dissection.direction, Some(Direction::HostToReader));
+        assert_eq!(dissection.summary, "Command: read");
+        assert_eq!(
+            names(&dissection),
+            [
+                "length",
+                "marker",
+                "opcode",
+                "bank",
+                "separator",
+                "address",
+                "separator",
+                "count"
+            ]
+        );
+        assert_eq!(dissection.fields[3].meaning, "EPC");
+        assert_eq!(dissection.fields[7].meaning, "6 words");
+        assert_covers(&dissection, &frame);
+    }
+
+    #[test]
+    fn every_command_and_reply_is_fully_covered() {
+        let commands = [
+            Command::Write {
+                bank: MemoryBank::User,
+                address: 0x10,
+                data: vec![0x12, 0x34],
+            },
+            Command::Kill {
+                password: 0x0BAD_F00D,
+            },
+            Command::Select(crate::types::TagFilter::epc(&[0x30, 0x08])),
+            Command::ClearSelect,
+            Command::InventoryStart,
+            Command::SetConfig {
+                settings: ReaderSettings::default(),
+                persist: true,
+            },
+            Command::Identify,
+            Command::Action {
+                action: 0x05,
+                time_units: 20,
+            },
+        ];
+        for command in commands {
+            let frame = command.encode().unwrap();
+            let dissection = dissect_command(&frame);
+            assert_eq!(dissection.direction, Some(Direction::HostToReader));
+            assert!(!names(&dissection).contains(&"trailing"), "{dissection}");
+            assert_covers(&dissection, &frame);
+        }
+        let responses = [
+            Response::Read {
+                data: vec![0xE2, 0x00, 0x12, 0x34],
+            },
+            Response::Written,
+            Response::AccessFailed {
+                command: b'W',
+                status: 0x04,
+            },
+            Response::InventoryTag(TagReport {
+                pc: 0x3000,
+                epc: vec![0xE2; 12],
+                crc: 0x1234,
+                rssi: Some(-61),
+                antenna: Some(1),
+            }),
+            Response::InventoryEmpty,
+            Response::Config(ReaderSettings::default()),
+            Response::ConfigSet { persisted: false },
+            Response::Action { status: 0 },
+        ];
+        for response in responses {
+            let frame = response.encode().unwrap();
+            let dissection = dissect_response(&frame);
+            assert_eq!(dissection.direction, Some(Direction::ReaderToHost));
+            assert!(!names(&dissection).contains(&"trailing"), "{dissection}");
+            assert_covers(&dissection, &frame);
+        }
+    }
+
+    #[test]
+    fn failed_replies_name_their_status() {
+        let frame = Response::AccessFailed {
+            command: b'R',
+            status: 0x01,
+        }
+        .encode()
+        .unwrap();
+        let dissection = dissect(&frame);
+        assert_eq!(
+            dissection.summary,
+            "Response: read, No tag responded to the command"
+        );
+        assert_eq!(names(&dissection)[3], "status");
+    }
+
+    #[test]
+    fn report_padding_is_labelled() {
+        let mut report = Response::Action { status: 0 }.encode().unwrap();
+        report.resize(64, 0);
+        let dissection = dissect_response(&report);
+        let padding = dissection.fields.last().unwrap();
+        assert_eq!(padding.name, "padding");
+        assert_eq!(padding.meaning, "60 bytes of report padding");
+        assert_covers(&dissection, &report);
+    }
+
+    #[test]
+    fn undecodable_frames_keep_their_header() {
+        let frame = [
+            5,
+            PROTOCOL_MARKER,
+            ACCESS_PREFIX,
+            STATUS_OK,
+            ACCESS_READ,
+            b'X',
+        ];
+        let dissection = dissect(&frame);
+        assert_eq!(dissection.direction, None);
+        assert_eq!(dissection.summary, "Response with an unknown layout");
+        assert_eq!(
+            names(&dissection),
+            ["length", "marker", "opcode", "status", "command", "payload"]
+        );
+        assert_covers(&dissection, &frame);
+
+        let short = [9, PROTOCOL_MARKER, ACTION_OPCODE];
+        let dissection = dissect(&short);
+        assert_eq!(
+            dissection.fields[0].meaning,
+            "9 bytes follow, only 2 present"
+        );
+        assert_covers(&dissection, &short);
+
+        assert_eq!(dissect(&[]).summary, "Empty frame");
+        let continuation = [MARKER_CONTINUE, b'A', b'B'];
+        assert_covers(&dissect(&continuation), &continuation);
+    }
+}
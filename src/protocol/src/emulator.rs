@@ -0,0 +1,992 @@
+//! In-memory emulator of the UHF-U6-CU-91 reader.
+//!
+//! [`ReaderEmulator`] implements [`UsbIo`] and answers the frames emitted by
+//! [`Interface`](crate::interface::Interface) the way the real reader does, backed by a
+//! configurable population of [`VirtualTag`]s. It lets the protocol and API layers run
+//! end to end without hardware. It is only built with the `emulator` feature, which test
+//! code enables through its dev-dependencies.
+//!
+//! Wire model:
+//! - every HID report is 64 bytes, and frames longer than one report are split as described
+//!   in [`framing`](crate::framing)
+//! - the "A" command family (`AR`, `AW`, `AP`, `AL`, `AK`, `AA`, `AS`) carries bank, address
+//!   and lengths as ASCII hex, and tag data as ASCII hex with four characters per 16-bit word
+//! - replies to the "A" family are `[2, 'A', status, cmd, ...]`; a non-zero status byte
+//!   reports why the tag operation failed
+//! - inventory (`0x55 0x80` to start a round, `0x55 0x91` to fetch the next tag), reader
+//!   configuration (`0x56 0x01` to read, `0x56 0x02` to apply), identification (`0x57`)
+//!   and device actions (`145`) use binary frames
+//!
+//! The first tag in the population answers every access command, like the strongest tag
+//! in the field would on the real reader. Once an `AS` select filter is set, the first
+//! tag matching it answers instead, and no tag answers if none matches. A killed tag
+//! leaves the field for good. `AA` moves the answering tag to the Secured state until the
+//! next inventory round singulates it again.
+//!
+//! Transport faults can be injected to exercise retries: lost replies leave the command
+//! executed but unanswered, failed writes never reach the reader, and late replies only
+//! reach the host once it sends its next command.
+
+use crate::codec::{
+    ACCESS_AUTHENTICATE, ACCESS_KILL, ACCESS_LOCK, ACCESS_PASSWORD, ACCESS_PREFIX, ACCESS_READ,
+    ACCESS_WRITE, CONFIG_OPCODE, CONFIG_SET, Command, PROTOCOL_MARKER, Response,
+    STATUS_ACCESS_DENIED, STATUS_BAD_COMMAND, STATUS_MEMORY_LOCKED, STATUS_MEMORY_OVERRUN,
+    STATUS_NO_TAG, STATUS_OK,
+};
+use crate::epc_bank::crc16;
+use crate::framing::{Deframer, Direction, Framer, REPORT_SIZE};
+use crate::interface::{ENDPOINT_IN, ENDPOINT_OUT, Result};
+use crate::types::{
+    FirmwareVersion, LockAction, LockPayload, LockableMemoryBank, MemoryBank, ReaderFeature,
+    ReaderIdentity, ReaderSettings, Region, RfSettings, TagFilter, TagReport, UhfError, UsbIo,
+};
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+/// Identity the emulator reports unless told otherwise: every region, full power and every
+/// command group
+fn emulated_identity() -> ReaderIdentity {
+    ReaderIdentity {
+        firmware: FirmwareVersion {
+            major: 2,
+            minor: 1,
+            patch: 0,
+        },
+        hardware_revision: 1,
+        regions: Region::ALL.to_vec(),
+        max_tx_power_dbm: RfSettings::MAX_TX_POWER_DBM,
+        features: ReaderFeature::ALL.to_vec(),
+    }
+}
+
+/// A virtual Gen2 tag with Reserved, EPC, TID and User memory.
+///
+/// Memory banks are stored as big-endian byte vectors, two bytes per word. The EPC bank
+/// follows the Gen2 layout: `StoredCRC` at word 0, PC at word 1 and the EPC from word 2.
+/// The TID bank is treated as factory-locked and rejects writes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VirtualTag {
+    /// Reserved bank: kill password (words 0-1) and access password (words 2-3)
+    pub reserved: Vec<u8>,
+    /// EPC bank: `StoredCRC`, PC word and EPC
+    pub epc: Vec<u8>,
+    /// TID bank
+    pub tid: Vec<u8>,
+    /// User bank
+    pub user: Vec<u8>,
+    /// Signal strength reported for the tag in inventory replies (dBm)
+    pub rssi: i8,
+    /// Antenna port reported for the tag in inventory replies
+    pub antenna: u8,
+    /// Number of times the tag answers each inventory round
+    pub reads_per_round: u8,
+    /// Gen2 lock action bits (kill, access, EPC, TID, User; two bits each, kill first)
+    lock_bits: u16,
+    /// Whether the tag was authenticated with its access password since it was last singulated
+    authenticated: bool,
+}
+
+impl VirtualTag {
+    /// Create a tag carrying `epc`, with zeroed passwords, a TID derived from the EPC and
+    /// 32 words of zeroed User memory. An odd-length EPC is padded with a trailing zero byte.
+    #[must_use]
+    pub fn new(epc: &[u8]) -> Self {
+        let mut epc_words = epc.to_vec();
+        if !epc_words.len().is_multiple_of(2) {
+            epc_words.push(0);
+        }
+
+        let mut tid = vec![0xE2, 0x80, 0x11, 0x05, 0x20, 0x00];
+        let serial_start = epc_words.len().saturating_sub(6);
+        tid.extend_from_slice(&epc_words[serial_start..]);
+        tid.resize(12, 0);
+
+        let mut tag = Self {
+            reserved: vec![0; 8],
+            epc: vec![0; 4],
+            tid,
+            user: vec![0; 64],
+            rssi: -55,
+            antenna: 1,
+            reads_per_round: 1,
+            lock_bits: 0,
+            authenticated: false,
+        };
+        tag.set_epc(&epc_words);
+        tag
+    }
+
+    /// Replace the TID bank contents
+    #[must_use]
+    pub fn with_tid(mut self, tid: &[u8]) -> Self {
+        self.tid = tid.to_vec();
+        self
+    }
+
+    /// Grow the EPC bank to `words` words after the `StoredCRC` and PC words, zero-filled
+    /// past the current EPC, like a chip with more EPC memory than its EPC uses
+    #[must_use]
+    pub fn with_epc_words(mut self, words: usize) -> Self {
+        self.epc.resize(self.epc.len().max(4 + words * 2), 0);
+        self
+    }
+
+    /// Resize the User bank to `words` zeroed words
+    #[must_use]
+    pub fn with_user_words(mut self, words: usize) -> Self {
+        self.user = vec![0; words * 2];
+        self
+    }
+
+    /// Make the tag answer `reads` times in every inventory round, as a tag in a strong
+    /// field does on the real reader
+    #[must_use]
+    pub fn with_reads_per_round(mut self, reads: u8) -> Self {
+        self.reads_per_round = reads;
+        self
+    }
+
+    /// Set the kill and access passwords stored in the Reserved bank
+    #[must_use]
+    pub fn with_passwords(mut self, kill: u32, access: u32) -> Self {
+        self.reserved[..4].copy_from_slice(&kill.to_be_bytes());
+        self.reserved[4..8].copy_from_slice(&access.to_be_bytes());
+        self
+    }
+
+    /// Set the lock state of a bank as if a Lock command had been applied
+    #[must_use]
+    pub fn with_lock(mut self, bank: LockableMemoryBank, action: LockAction) -> Self {
+        let shift = bank.lock_shift();
+        self.lock_bits = (self.lock_bits & !(0b11 << shift)) | (action.bits() << shift);
+        self
+    }
+
+    /// EPC as identified by the length field of the PC word
+    #[must_use]
+    pub fn epc_bytes(&self) -> Vec<u8> {
+        let end = (4 + self.epc_len_words() * 2).min(self.epc.len());
+        self.epc[4.min(end)..end].to_vec()
+    }
+
+    /// Protocol Control word stored at EPC word 1
+    #[must_use]
+    pub fn pc_word(&self) -> u16 {
+        u16::from_be_bytes([self.epc[2], self.epc[3]])
+    }
+
+    /// `StoredCRC` word stored at EPC word 0
+    #[must_use]
+    pub fn stored_crc(&self) -> u16 {
+        u16::from_be_bytes([self.epc[0], self.epc[1]])
+    }
+
+    /// Kill password stored in Reserved words 0-1
+    #[must_use]
+    pub fn kill_password(&self) -> u32 {
+        u32::from_be_bytes([
+            self.reserved[0],
+            self.reserved[1],
+            self.reserved[2],
+            self.reserved[3],
+        ])
+    }
+
+    /// Access password stored in Reserved words 2-3
+    #[must_use]
+    pub fn access_password(&self) -> u32 {
+        u32::from_be_bytes([
+            self.reserved[4],
+            self.reserved[5],
+            self.reserved[6],
+            self.reserved[7],
+        ])
+    }
+
+    /// Current lock action of a bank; for password banks the action covers both read and write
+    #[must_use]
+    pub fn lock_action(&self, bank: LockableMemoryBank) -> LockAction {
+        LockAction::from_bits(self.lock_bits >> bank.lock_shift())
+    }
+
+    /// Replace the EPC, updating the PC length field and `StoredCRC`
+    fn set_epc(&mut self, epc: &[u8]) {
+        let words = u16::try_from(epc.len() / 2).unwrap_or(0).min(31);
+        let pc = (self.pc_word() & 0x07FF) | (words << 11);
+        self.epc.truncate(4);
+        self.epc[2..4].copy_from_slice(&pc.to_be_bytes());
+        self.epc.extend_from_slice(epc);
+        self.refresh_crc();
+    }
+
+    /// Recompute `StoredCRC` over the PC word and EPC, like a tag does at power-up
+    fn refresh_crc(&mut self) {
+        let end = (4 + self.epc_len_words() * 2).min(self.epc.len());
+        let crc = crc16(&self.epc[2..end]);
+        self.epc[..2].copy_from_slice(&crc.to_be_bytes());
+    }
+
+    fn epc_len_words(&self) -> usize {
+        usize::from(self.pc_word() >> 11)
+    }
+
+    /// A tag is in the Secured state when its access password is zero or it was authenticated
+    fn is_secured(&self) -> bool {
+        self.access_password() == 0 || self.authenticated
+    }
+
+    /// Enter the Secured state if `password` matches the access password
+    fn authenticate(&mut self, password: u32) -> std::result::Result<(), u8> {
+        if password != self.access_password() {
+            return Err(STATUS_ACCESS_DENIED);
+        }
+        self.authenticated = true;
+        Ok(())
+    }
+
+    /// Whether the password-lock bit of `bank` blocks access in the current state
+    fn is_guarded(&self, bank: LockableMemoryBank) -> bool {
+        let pwd_locked = (self.lock_bits >> (bank.lock_shift() + 1)) & 1 == 1;
+        let perma_locked = (self.lock_bits >> bank.lock_shift()) & 1 == 1;
+        pwd_locked && (perma_locked || !self.is_secured())
+    }
+
+    /// Lockable areas covered by a word range of a memory bank
+    fn lockable_areas(bank: MemoryBank, address: usize, words: usize) -> Vec<LockableMemoryBank> {
+        match bank {
+            MemoryBank::Epc => vec![LockableMemoryBank::Epc],
+            MemoryBank::Tid => vec![LockableMemoryBank::Tid],
+            MemoryBank::User => vec![LockableMemoryBank::User],
+            MemoryBank::Reserved => {
+                let mut areas = Vec::new();
+                if address < 2 {
+                    areas.push(LockableMemoryBank::KillPassword);
+                }
+                if address + words > 2 {
+                    areas.push(LockableMemoryBank::AccessPassword);
+                }
+                areas
+            }
+        }
+    }
+
+    fn bank(&self, bank: MemoryBank) -> &Vec<u8> {
+        match bank {
+            MemoryBank::Reserved => &self.reserved,
+            MemoryBank::Epc => &self.epc,
+            MemoryBank::Tid => &self.tid,
+            MemoryBank::User => &self.user,
+        }
+    }
+
+    fn bank_mut(&mut self, bank: MemoryBank) -> &mut Vec<u8> {
+        match bank {
+            MemoryBank::Reserved => &mut self.reserved,
+            MemoryBank::Epc => &mut self.epc,
+            MemoryBank::Tid => &mut self.tid,
+            MemoryBank::User => &mut self.user,
+        }
+    }
+
+    /// Read `words` words from `address`; zero words reads to the end of the bank
+    fn read_words(
+        &self,
+        bank: MemoryBank,
+        address: usize,
+        words: usize,
+    ) -> std::result::Result<Vec<u8>, u8> {
+        let memory = self.bank(bank);
+        let start = address * 2;
+        let end = if words == 0 {
+            memory.len()
+        } else {
+            start + words * 2
+        };
+        if start >= memory.len() || end > memory.len() {
+            return Err(STATUS_MEMORY_OVERRUN);
+        }
+        if bank == MemoryBank::Reserved
+            && Self::lockable_areas(bank, address, (end - start) / 2)
+                .into_iter()
+                .any(|area| self.is_guarded(area))
+        {
+            return Err(STATUS_MEMORY_LOCKED);
+        }
+        Ok(memory[start..end].to_vec())
+    }
+
+    fn write_words(
+        &mut self,
+        bank: MemoryBank,
+        address: usize,
+        data: &[u8],
+    ) -> std::result::Result<(), u8> {
+        let start = address * 2;
+        let end = start + data.len();
+        if end > self.bank(bank).len() {
+            return Err(STATUS_MEMORY_OVERRUN);
+        }
+        if bank == MemoryBank::Tid
+            || Self::lockable_areas(bank, address, data.len() / 2)
+                .into_iter()
+                .any(|area| self.is_guarded(area))
+        {
+            return Err(STATUS_MEMORY_LOCKED);
+        }
+        self.bank_mut(bank)[start..end].copy_from_slice(data);
+        if bank == MemoryBank::Epc && end > 2 {
+            self.refresh_crc();
+        }
+        Ok(())
+    }
+
+    /// Apply a Gen2 lock payload
+    fn apply_lock(&mut self, payload: LockPayload) -> std::result::Result<(), u8> {
+        if !self.is_secured() {
+            return Err(STATUS_MEMORY_LOCKED);
+        }
+        let mask = payload.mask();
+        let action = payload.action_bits();
+        // Permalocked fields may not change
+        let perma = self.lock_bits & 0b01_0101_0101;
+        let frozen = perma | (perma << 1);
+        if (self.lock_bits ^ action) & mask & frozen != 0 {
+            return Err(STATUS_MEMORY_LOCKED);
+        }
+        self.lock_bits = (self.lock_bits & !mask) | (action & mask);
+        Ok(())
+    }
+}
+
+/// Mutable emulator state shared behind the `UsbIo` `&self` methods
+#[derive(Debug, Default)]
+struct EmulatorState {
+    tags: Vec<VirtualTag>,
+    incoming: Deframer,
+    inventory_round: VecDeque<usize>,
+    pending_reports: VecDeque<[u8; REPORT_SIZE]>,
+    received_frames: Vec<Vec<u8>>,
+    last_action: Option<(u8, u8)>,
+    selection: Option<TagFilter>,
+    settings: ReaderSettings,
+    saved_settings: Option<ReaderSettings>,
+    volatile_settings: bool,
+    identity: Option<ReaderIdentity>,
+    lost_replies: usize,
+    failed_writes: usize,
+    delayed_replies: usize,
+    late_reports: Vec<[u8; REPORT_SIZE]>,
+}
+
+/// In-memory UHF reader answering protocol frames from a population of virtual tags
+#[derive(Debug)]
+pub struct ReaderEmulator {
+    state: Mutex<EmulatorState>,
+}
+
+impl Default for ReaderEmulator {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(EmulatorState {
+                identity: Some(emulated_identity()),
+                ..EmulatorState::default()
+            }),
+        }
+    }
+}
+
+impl ReaderEmulator {
+    /// Create an emulator with no tags in the field
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an emulator with the given tags in the field
+    #[must_use]
+    pub fn with_tags(tags: Vec<VirtualTag>) -> Self {
+        let emulator = Self::new();
+        emulator.state().tags = tags;
+        emulator
+    }
+
+    /// Bring another tag into the field
+    pub fn add_tag(&self, tag: VirtualTag) {
+        self.state().tags.push(tag);
+    }
+
+    /// Take a tag out of the field
+    pub fn remove_tag(&self, index: usize) -> Option<VirtualTag> {
+        let mut state = self.state();
+        (index < state.tags.len()).then(|| state.tags.remove(index))
+    }
+
+    /// Snapshot of a tag in the field
+    #[must_use]
+    pub fn tag(&self, index: usize) -> Option<VirtualTag> {
+        self.state().tags.get(index).cloned()
+    }
+
+    /// Number of tags in the field
+    #[must_use]
+    pub fn tag_count(&self) -> usize {
+        self.state().tags.len()
+    }
+
+    /// Every command frame received so far, without the leading length byte
+    #[must_use]
+    pub fn received_frames(&self) -> Vec<Vec<u8>> {
+        self.state().received_frames.clone()
+    }
+
+    /// Last buzzer/LED action executed as `(action bitmask, time units)`
+    #[must_use]
+    pub fn last_action(&self) -> Option<(u8, u8)> {
+        self.state().last_action
+    }
+
+    /// Settings currently in effect
+    #[must_use]
+    pub fn settings(&self) -> ReaderSettings {
+        self.state().settings
+    }
+
+    /// Settings stored in non-volatile memory, if any were saved
+    #[must_use]
+    pub fn saved_settings(&self) -> Option<ReaderSettings> {
+        self.state().saved_settings
+    }
+
+    /// Emulate a reader without non-volatile settings storage
+    pub fn set_volatile_settings(&self, volatile: bool) {
+        self.state().volatile_settings = volatile;
+    }
+
+    /// Identity set for the identify command, if the emulated firmware supports it
+    #[must_use]
+    pub fn identity(&self) -> Option<ReaderIdentity> {
+        self.state().identity.clone()
+    }
+
+    /// Identity reported to the identify command; `None` emulates firmware that predates it
+    /// and rejects the command. Settings the identity rules out are rejected as well.
+    pub fn set_identity(&self, identity: Option<ReaderIdentity>) {
+        self.state().identity = identity;
+    }
+
+    /// Execute the next `count` commands without their replies reaching the host, like
+    /// replies lost at the edge of the field
+    pub fn lose_replies(&self, count: usize) {
+        self.state().lost_replies = count;
+    }
+
+    /// Answer the next `count` commands too late: each reply reaches the host only as it
+    /// sends its next command, after it has stopped waiting
+    pub fn delay_replies(&self, count: usize) {
+        self.state().delayed_replies = count;
+    }
+
+    /// Leave `reports` waiting for the host, like the rest of a reply it stopped reading
+    pub fn queue_stale_reports(&self, reports: impl IntoIterator<Item = [u8; REPORT_SIZE]>) {
+        self.state().pending_reports.extend(reports);
+    }
+
+    /// Send `reports` to the host while it sends its next command, after it has drained
+    /// stale input and ahead of the command's own reply
+    pub fn queue_late_reports(&self, reports: impl IntoIterator<Item = [u8; REPORT_SIZE]>) {
+        self.state().late_reports.extend(reports);
+    }
+
+    /// Number of reports waiting for the host to read them
+    #[must_use]
+    pub fn pending_reports(&self) -> usize {
+        self.state().pending_reports.len()
+    }
+
+    /// Fail the next `count` report writes with a communication error before they reach
+    /// the reader
+    pub fn fail_writes(&self, count: usize) {
+        self.state().failed_writes = count;
+    }
+
+    /// Power-cycle the reader: saved settings (or the defaults) come back into effect and
+    /// the select filter and inventory round are dropped
+    pub fn restart(&self) {
+        let mut state = self.state();
+        state.settings = state.saved_settings.unwrap_or_default();
+        state.selection = None;
+        state.inventory_round.clear();
+    }
+
+    fn state(&self) -> MutexGuard<'_, EmulatorState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl EmulatorState {
+    /// Collect an outgoing report, returning the frame payload once it is complete
+    ///
+    /// Reports breaking the framing rules are dropped along with the partial frame, like
+    /// the reader ignores a garbled command.
+    fn accept_report(&mut self, report: &[u8]) -> Option<Vec<u8>> {
+        let frame = self.incoming.push(report).ok()??;
+        Some(frame[1..].to_vec())
+    }
+
+    /// Execute a frame and return the reply, if the reader answers it
+    fn execute(&mut self, frame: &[u8]) -> Option<Response> {
+        let command = u8::try_from(frame.len())
+            .ok()
+            .and_then(|len| Command::decode(&[&[len], frame].concat()).ok());
+        let Some(command) = command else {
+            // Malformed access commands are rejected with a status, anything else is ignored
+            return match frame {
+                [PROTOCOL_MARKER, ACCESS_PREFIX, cmd, ..] => Some(Response::AccessFailed {
+                    command: *cmd,
+                    status: STATUS_BAD_COMMAND,
+                }),
+                [PROTOCOL_MARKER, CONFIG_OPCODE, cmd, ..] => Some(Response::ConfigFailed {
+                    command: *cmd,
+                    status: STATUS_BAD_COMMAND,
+                }),
+                _ => None,
+            };
+        };
+        let reply = match command {
+            Command::Read {
+                bank,
+                address,
+                words,
+            } => self
+                .target()
+                .and_then(|tag| tag.read_words(bank, usize::from(address), usize::from(words)))
+                .map(|data| Response::Read { data })
+                .map_err(|status| (ACCESS_READ, status)),
+            Command::Write {
+                bank,
+                address,
+                data,
+            } => self
+                .target()
+                .and_then(|tag| tag.write_words(bank, usize::from(address), &data))
+                .map(|()| Response::Written)
+                .map_err(|status| (ACCESS_WRITE, status)),
+            // `AP` writes the tag's access password into Reserved words 2-3
+            Command::SetAccessPassword { password } => self
+                .target()
+                .and_then(|tag| tag.write_words(MemoryBank::Reserved, 2, &password.to_be_bytes()))
+                .map(|()| Response::PasswordSet)
+                .map_err(|status| (ACCESS_PASSWORD, status)),
+            Command::Lock { payload } => self
+                .target()
+                .and_then(|tag| tag.apply_lock(payload))
+                .map(|()| Response::Locked)
+                .map_err(|status| (ACCESS_LOCK, status)),
+            Command::Authenticate { password } => self
+                .target()
+                .and_then(|tag| tag.authenticate(password))
+                .map(|()| Response::Authenticated)
+                .map_err(|status| (ACCESS_AUTHENTICATE, status)),
+            // Gen2 tags with a zero kill password ignore Kill
+            Command::Kill { password } => self
+                .target_index()
+                .and_then(|index| match self.tags[index].kill_password() {
+                    0 => Err(STATUS_ACCESS_DENIED),
+                    stored if stored != password => Err(STATUS_ACCESS_DENIED),
+                    _ => {
+                        self.tags.remove(index);
+                        Ok(Response::Killed)
+                    }
+                })
+                .map_err(|status| (ACCESS_KILL, status)),
+            Command::Select(filter) => {
+                self.selection = Some(filter);
+                Ok(Response::Selected)
+            }
+            Command::ClearSelect => {
+                self.selection = None;
+                Ok(Response::Selected)
+            }
+            Command::InventoryStart => Ok(self.start_inventory_round()),
+            Command::InventoryNext => Ok(self.next_inventory_tag()),
+            Command::GetConfig => Ok(Response::Config(self.settings)),
+            Command::SetConfig { settings, persist } => Ok(self.configure(settings, persist)),
+            Command::Identify => Ok(self.identify()),
+            Command::Action { action, time_units } => {
+                self.last_action = Some((action, time_units));
+                Ok(Response::Action { status: STATUS_OK })
+            }
+        };
+        Some(reply.unwrap_or_else(|(command, status)| Response::AccessFailed { command, status }))
+    }
+
+    fn start_inventory_round(&mut self) -> Response {
+        // Singulating a tag again drops it out of the Secured state
+        for tag in &mut self.tags {
+            tag.authenticated = false;
+        }
+        self.inventory_round = self
+            .tags
+            .iter()
+            .enumerate()
+            .flat_map(|(i, tag)| std::iter::repeat_n(i, usize::from(tag.reads_per_round)))
+            .collect();
+        Response::InventoryStarted {
+            tag_count: u8::try_from(self.tags.len()).unwrap_or(u8::MAX),
+        }
+    }
+
+    fn next_inventory_tag(&mut self) -> Response {
+        match self
+            .inventory_round
+            .pop_front()
+            .and_then(|i| self.tags.get(i))
+        {
+            Some(tag) => Response::InventoryTag(TagReport {
+                pc: tag.pc_word(),
+                epc: tag.epc_bytes(),
+                crc: tag.stored_crc(),
+                rssi: Some(tag.rssi),
+                antenna: Some(tag.antenna),
+            }),
+            None => Response::InventoryEmpty,
+        }
+    }
+
+    fn configure(&mut self, settings: ReaderSettings, persist: bool) -> Response {
+        let unsupported = self.identity.as_ref().is_some_and(|identity| {
+            settings.rf.tx_power_dbm > identity.max_tx_power_dbm
+                || !identity.supports_region(settings.rf.region)
+        });
+        if unsupported {
+            return Response::ConfigFailed {
+                command: CONFIG_SET,
+                status: STATUS_BAD_COMMAND,
+            };
+        }
+        self.settings = settings;
+        let persisted = persist && !self.volatile_settings;
+        if persisted {
+            self.saved_settings = Some(settings);
+        }
+        Response::ConfigSet { persisted }
+    }
+
+    fn identify(&self) -> Response {
+        let Some(mut identity) = self.identity.clone() else {
+            return Response::IdentifyFailed {
+                status: STATUS_BAD_COMMAND,
+            };
+        };
+        if self.volatile_settings {
+            identity
+                .features
+                .retain(|feature| *feature != ReaderFeature::PersistSettings);
+        }
+        Response::Identity(identity)
+    }
+
+    /// Index of the tag answering access commands: the first one matching the select
+    /// filter, if any
+    fn target_index(&self) -> std::result::Result<usize, u8> {
+        let selection = self.selection.as_ref();
+        self.tags
+            .iter()
+            .position(|tag| selection.is_none_or(|filter| filter.matches(tag.bank(filter.bank))))
+            .ok_or(STATUS_NO_TAG)
+    }
+
+    fn target(&mut self) -> std::result::Result<&mut VirtualTag, u8> {
+        let index = self.target_index()?;
+        Ok(&mut self.tags[index])
+    }
+
+    /// Split an encoded reply into the reports the host reads back
+    fn queue_reply(&mut self, reply: &[u8]) {
+        let Ok(framer) = Framer::new(Direction::ReaderToHost, reply) else {
+            return;
+        };
+        if self.delayed_replies > 0 {
+            self.delayed_replies -= 1;
+            self.late_reports.extend(framer);
+        } else {
+            self.pending_reports.extend(framer);
+        }
+    }
+}
+
+impl UsbIo for ReaderEmulator {
+    fn read_bulk(&self, endpoint: u8, buf: &mut [u8], _timeout: Duration) -> Result<usize> {
+        if endpoint != ENDPOINT_IN {
+            return Err(UhfError::Communication(format!(
+                "Unknown IN endpoint 0x{endpoint:02X}"
+            )));
+        }
+        let report = self
+            .state()
+            .pending_reports
+            .pop_front()
+            .ok_or(UhfError::Timeout)?;
+        let len = buf.len().min(REPORT_SIZE);
+        buf[..len].copy_from_slice(&report[..len]);
+        Ok(len)
+    }
+
+    fn write_bulk(&self, endpoint: u8, data: &[u8], _timeout: Duration) -> Result<usize> {
+        if endpoint != ENDPOINT_OUT {
+            return Err(UhfError::Communication(format!(
+                "Unknown OUT endpoint 0x{endpoint:02X}"
+            )));
+        }
+        let mut state = self.state();
+        // Late replies turn up while the next command goes out
+        let late_reports = std::mem::take(&mut state.late_reports);
+        state.pending_reports.extend(late_reports);
+        if state.failed_writes > 0 {
+            state.failed_writes -= 1;
+            return Err(UhfError::Communication(
+                "Injected write failure".to_string(),
+            ));
+        }
+        if let Some(frame) = state.accept_report(data) {
+            state.received_frames.push(frame.clone());
+            let reply = state.execute(&frame).and_then(|reply| reply.encode().ok());
+            if let Some(reply) = reply {
+                if state.lost_replies > 0 {
+                    state.lost_replies -= 1;
+                } else {
+                    state.queue_reply(&reply);
+                }
+            }
+        }
+        Ok(data.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interface::Interface;
+
+    const EPC_A: [u8; 12] = [
+        0x30, 0x08, 0x33, 0xB2, 0xDD, 0xD9, 0x01, 0x40, 0x00, 0x00, 0x00, 0x01,
+    ];
+    const EPC_B: [u8; 12] = [
+        0x30, 0x08, 0x33, 0xB2, 0xDD, 0xD9, 0x01, 0x40, 0x00, 0x00, 0x00, 0x02,
+    ];
+
+    fn interface() -> Interface {
+        Interface::default()
+    }
+
+    #[test]
+    fn inventory_reports_every_tag_in_the_field() {
+        let reader =
+            ReaderEmulator::with_tags(vec![VirtualTag::new(&EPC_A), VirtualTag::new(&EPC_B)]);
+        let tags = interface().inventory(&reader).unwrap();
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags[0].epc, EPC_A);
+        assert_eq!(tags[0].pc, 0x3000);
+        assert_eq!(tags[0].crc, reader.tag(0).unwrap().stored_crc());
+        assert_eq!(tags[0].rssi, Some(-55));
+        assert_eq!(tags[1].epc, EPC_B);
+    }
+
+    #[test]
+    fn inventory_polls_until_the_round_is_empty() {
+        let tags = (0..25u8).map(|i| VirtualTag::new(&[0x30, i])).collect();
+        let reader = ReaderEmulator::with_tags(tags);
+        assert_eq!(interface().inventory(&reader).unwrap().len(), 25);
+    }
+
+    #[test]
+    fn inventory_with_empty_field_returns_no_tags() {
+        let reader = ReaderEmulator::new();
+        assert!(interface().inventory(&reader).unwrap().is_empty());
+    }
+
+    #[test]
+    fn read_returns_requested_words() {
+        let reader = ReaderEmulator::with_tags(vec![VirtualTag::new(&EPC_A)]);
+        let data = interface().read(&reader, MemoryBank::Epc, 2, 6).unwrap();
+        assert_eq!(data, EPC_A);
+    }
+
+    #[test]
+    fn write_then_read_user_memory() {
+        let reader = ReaderEmulator::with_tags(vec![VirtualTag::new(&EPC_A)]);
+        interface()
+            .write(&reader, MemoryBank::User, 0x12, &[0xCA, 0xFE, 0xBA, 0xBE])
+            .unwrap();
+        assert_eq!(
+            &reader.tag(0).unwrap().user[0x24..0x28],
+            &[0xCA, 0xFE, 0xBA, 0xBE]
+        );
+        let data = interface()
+            .read(&reader, MemoryBank::User, 0x12, 2)
+            .unwrap();
+        assert_eq!(data, [0xCA, 0xFE, 0xBA, 0xBE]);
+    }
+
+    #[test]
+    fn read_past_end_of_bank_fails() {
+        let reader = ReaderEmulator::with_tags(vec![VirtualTag::new(&EPC_A)]);
+        assert!(matches!(
+            interface().read(&reader, MemoryBank::Tid, 4, 4),
+            Err(UhfError::MemoryOverrun { .. })
+        ));
+    }
+
+    #[test]
+    fn access_without_tag_fails() {
+        let reader = ReaderEmulator::new();
+        let error = interface()
+            .read(&reader, MemoryBank::Epc, 2, 6)
+            .unwrap_err();
+        assert!(matches!(error, UhfError::NoTag { .. }));
+        assert_eq!(error.frame(), Some([4, 2, b'A', 0x01, b'R'].as_slice()));
+    }
+
+    #[test]
+    fn epc_write_refreshes_stored_crc() {
+        let reader = ReaderEmulator::with_tags(vec![VirtualTag::new(&EPC_A)]);
+        let before = reader.tag(0).unwrap().stored_crc();
+        interface()
+            .write(&reader, MemoryBank::Epc, 7, &[0x00, 0x02])
+            .unwrap();
+        let tag = reader.tag(0).unwrap();
+        assert_eq!(tag.epc_bytes(), EPC_B);
+        assert_ne!(tag.stored_crc(), before);
+        assert_eq!(tag.stored_crc(), VirtualTag::new(&EPC_B).stored_crc());
+    }
+
+    #[test]
+    fn password_and_lock_guard_writes() {
+        let reader = ReaderEmulator::with_tags(vec![VirtualTag::new(&EPC_A)]);
+        // Secure the EPC bank: mask bits 15..14, action bit 5
+        let payload =
+            LockPayload::new().with_action(LockableMemoryBank::Epc, LockAction::SecureWriteable);
+        interface().lock_memory(&reader, payload).unwrap();
+        assert_eq!(
+            reader.tag(0).unwrap().lock_action(LockableMemoryBank::Epc),
+            LockAction::SecureWriteable
+        );
+        // With a zero access password the tag stays Secured, so writes still succeed
+        interface()
+            .write(&reader, MemoryBank::Epc, 2, &[0x12, 0x34])
+            .unwrap();
+
+        interface()
+            .set_access_password(&reader, 0x1122_3344)
+            .unwrap();
+        assert_eq!(reader.tag(0).unwrap().access_password(), 0x1122_3344);
+        assert!(matches!(
+            interface().write(&reader, MemoryBank::Epc, 2, &[0x56, 0x78]),
+            Err(UhfError::MemoryLocked { .. })
+        ));
+        interface()
+            .write(&reader, MemoryBank::User, 0, &[0x56, 0x78])
+            .unwrap();
+    }
+
+    #[test]
+    fn access_password_unlocks_secured_banks_until_next_round() {
+        let tag = VirtualTag::new(&EPC_A)
+            .with_passwords(0, 0x1122_3344)
+            .with_lock(LockableMemoryBank::User, LockAction::SecureWriteable);
+        let reader = ReaderEmulator::with_tags(vec![tag]);
+        assert!(matches!(
+            interface().write(&reader, MemoryBank::User, 0, &[0xAB, 0xCD]),
+            Err(UhfError::MemoryLocked { .. })
+        ));
+        assert!(matches!(
+            interface().authenticate(&reader, 0x1122_3345),
+            Err(UhfError::AccessDenied { .. })
+        ));
+
+        interface().authenticate(&reader, 0x1122_3344).unwrap();
+        interface()
+            .write(&reader, MemoryBank::User, 0, &[0xAB, 0xCD])
+            .unwrap();
+        assert_eq!(&reader.tag(0).unwrap().user[..2], &[0xAB, 0xCD]);
+
+        interface().inventory(&reader).unwrap();
+        assert!(matches!(
+            interface().write(&reader, MemoryBank::User, 0, &[0x00, 0x00]),
+            Err(UhfError::MemoryLocked { .. })
+        ));
+    }
+
+    #[test]
+    fn permalocked_bank_cannot_be_unlocked() {
+        let tag =
+            VirtualTag::new(&EPC_A).with_lock(LockableMemoryBank::User, LockAction::NotWriteable);
+        let reader = ReaderEmulator::with_tags(vec![tag]);
+        assert!(
+            interface()
+                .write(&reader, MemoryBank::User, 0, &[0x00, 0x01])
+                .is_err()
+        );
+        // Unlock User: mask bits 11..10, action bits cleared
+        let unlock =
+            LockPayload::new().with_action(LockableMemoryBank::User, LockAction::Writeable);
+        assert!(interface().lock_memory(&reader, unlock).is_err());
+    }
+
+    #[test]
+    fn action_is_recorded() {
+        let reader = ReaderEmulator::new();
+        interface().action(&reader, 0x05, 20).unwrap();
+        assert_eq!(reader.last_action(), Some((0x05, 20)));
+        assert!(interface().action(&reader, 0, 20).is_err());
+    }
+
+    #[test]
+    fn long_replies_span_several_reports() {
+        let reader = ReaderEmulator::new();
+        reader
+            .state()
+            .queue_reply(&[[100].as_slice(), &[0xAA; 100]].concat());
+        let mut report = [0u8; REPORT_SIZE];
+        assert_eq!(
+            reader
+                .read_bulk(ENDPOINT_IN, &mut report, Duration::ZERO)
+                .unwrap(),
+            64
+        );
+        assert_eq!(report[0], 63);
+        reader
+            .read_bulk(ENDPOINT_IN, &mut report, Duration::ZERO)
+            .unwrap();
+        assert_eq!(report[0], 37);
+        assert!(matches!(
+            reader.read_bulk(ENDPOINT_IN, &mut report, Duration::ZERO),
+            Err(UhfError::Timeout)
+        ));
+    }
+
+    #[test]
+    fn malformed_config_frames_are_rejected_with_a_status() {
+        let reader = ReaderEmulator::new();
+        // Channel 60 is past the FCC channel plan
+        reader
+            .write_bulk(
+                ENDPOINT_OUT,
+                &[11, 2, 0x56, 0x02, 0, 20, 1, 0, 60, 1, 0, 4],
+                Duration::ZERO,
+            )
+            .unwrap();
+        let mut report = [0u8; REPORT_SIZE];
+        reader
+            .read_bulk(ENDPOINT_IN, &mut report, Duration::ZERO)
+            .unwrap();
+        assert_eq!(
+            Response::decode(&report).unwrap(),
+            Response::ConfigFailed {
+                command: 0x02,
+                status: STATUS_BAD_COMMAND
+            }
+        );
+    }
+}
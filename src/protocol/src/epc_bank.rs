@@ -0,0 +1,348 @@
+//! Layout of the Gen2 EPC bank: `StoredCRC`, Protocol Control word, EPC and XPC words.
+//!
+//! ```text
+//! word  0     StoredCRC    CRC-16 over the PC word and the EPC
+//! word  1     StoredPC     EPC length, UMI, XI, toggle and attribute bits
+//! word  2-32  EPC          as many words as the PC length field says
+//! word  33    XPC_W1       extended PC word, present when XI is set
+//! word  34    XPC_W2       second extended PC word, present when XEB is set
+//! ```
+//!
+//! A tag recomputes `StoredCRC` itself when the PC or EPC changes, but reads only as many
+//! EPC words as the PC length field says, so the PC word has to be written with the EPC.
+
+use std::fmt;
+
+/// Word address of `StoredCRC`
+pub const STORED_CRC_ADDRESS: u32 = 0;
+
+/// Word address of the Protocol Control word
+pub const PC_ADDRESS: u32 = 1;
+
+/// Word address of the first EPC word
+pub const EPC_ADDRESS: u32 = 2;
+
+/// Word address of `XPC_W1`
+pub const XPC_W1_ADDRESS: u32 = 0x21;
+
+/// Longest EPC the PC length field can describe, in words
+pub const MAX_EPC_WORDS: u8 = 31;
+
+/// Gen2 CRC-16 (CCITT polynomial, preset 0xFFFF, complemented output)
+#[must_use]
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0xFFFFu16;
+    for byte in data {
+        crc ^= u16::from(*byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 == 0 {
+                crc << 1
+            } else {
+                (crc << 1) ^ 0x1021
+            };
+        }
+    }
+    !crc
+}
+
+/// Protocol Control word at EPC bank word 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ProtocolControl {
+    /// EPC length in words (0-31)
+    pub epc_words: u8,
+    /// User memory indicator: the User bank holds data
+    pub umi: bool,
+    /// XPC indicator: `XPC_W1` is non-zero
+    pub xi: bool,
+    /// Numbering system toggle: the attribute byte is an ISO application family identifier
+    /// rather than `EPCglobal` attribute bits
+    pub toggle: bool,
+    /// `EPCglobal` attribute bits, or the AFI when `toggle` is set
+    pub attributes: u8,
+}
+
+impl ProtocolControl {
+    /// Decode a PC word
+    #[must_use]
+    pub fn from_word(word: u16) -> Self {
+        let [high, attributes] = word.to_be_bytes();
+        Self {
+            epc_words: high >> 3,
+            umi: high & 0b100 != 0,
+            xi: high & 0b010 != 0,
+            toggle: high & 0b001 != 0,
+            attributes,
+        }
+    }
+
+    /// Encode the PC word; lengths beyond 31 words are cut to the five-bit field
+    #[must_use]
+    pub fn to_word(self) -> u16 {
+        let high = ((self.epc_words & 0x1F) << 3)
+            | (u8::from(self.umi) << 2)
+            | (u8::from(self.xi) << 1)
+            | u8::from(self.toggle);
+        u16::from_be_bytes([high, self.attributes])
+    }
+
+    /// The same PC word describing an EPC of `epc_words` words
+    #[must_use]
+    pub fn with_epc_words(self, epc_words: u8) -> Self {
+        Self { epc_words, ..self }
+    }
+}
+
+impl fmt::Display for ProtocolControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:04X}: {}-word EPC", self.to_word(), self.epc_words)?;
+        for (set, name) in [
+            (self.umi, "UMI"),
+            (self.xi, "XI"),
+            (self.toggle, "ISO toggle"),
+        ] {
+            if set {
+                write!(f, ", {name}")?;
+            }
+        }
+        if self.toggle {
+            write!(f, ", AFI 0x{:02X}", self.attributes)
+        } else if self.attributes != 0 {
+            write!(f, ", attributes 0x{:02X}", self.attributes)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// First extended Protocol Control word, at EPC bank word 0x21
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct XpcW1(pub u16);
+
+impl XpcW1 {
+    /// XPC extension bit: `XPC_W2` follows
+    pub const XEB: u16 = 1 << 15;
+    /// Sensor alarm
+    pub const SA: u16 = 1 << 14;
+    /// Simple sensor present
+    pub const SS: u16 = 1 << 13;
+    /// Full-function sensor present
+    pub const FS: u16 = 1 << 12;
+    /// Snapshot sensor present
+    pub const SN: u16 = 1 << 11;
+    /// Battery-assisted passive tag
+    pub const B: u16 = 1 << 10;
+    /// Computed response: the tag appends a response to its EPC
+    pub const C: u16 = 1 << 9;
+    /// Security level indicator
+    pub const SLI: u16 = 1 << 8;
+    /// Tag notification
+    pub const TN: u16 = 1 << 7;
+    /// Untraceable: the tag hides part of its memory
+    pub const U: u16 = 1 << 6;
+    /// Killable
+    pub const K: u16 = 1 << 5;
+    /// Non-removable: the tag is embedded in the item
+    pub const NR: u16 = 1 << 4;
+    /// Hazardous material
+    pub const H: u16 = 1 << 3;
+
+    const NAMES: [(u16, &'static str); 13] = [
+        (Self::XEB, "XEB"),
+        (Self::SA, "SA"),
+        (Self::SS, "SS"),
+        (Self::FS, "FS"),
+        (Self::SN, "SN"),
+        (Self::B, "B"),
+        (Self::C, "C"),
+        (Self::SLI, "SLI"),
+        (Self::TN, "TN"),
+        (Self::U, "U"),
+        (Self::K, "K"),
+        (Self::NR, "NR"),
+        (Self::H, "H"),
+    ];
+
+    /// Whether every bit of `flag` is set
+    #[must_use]
+    pub fn has(self, flag: u16) -> bool {
+        self.0 & flag == flag
+    }
+
+    /// Names of the set flags, most significant first
+    #[must_use]
+    pub fn flag_names(self) -> Vec<&'static str> {
+        Self::NAMES
+            .iter()
+            .filter(|(flag, _)| self.has(*flag))
+            .map(|(_, name)| *name)
+            .collect()
+    }
+}
+
+impl fmt::Display for XpcW1 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:04X}", self.0)?;
+        let names = self.flag_names();
+        if !names.is_empty() {
+            write!(f, ": {}", names.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Contents of the EPC bank from word 0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpcMemory {
+    /// `StoredCRC` word
+    pub stored_crc: u16,
+    /// Protocol Control word
+    pub pc: ProtocolControl,
+    /// EPC, as many words as the PC length field says or as were read
+    pub epc: Vec<u8>,
+    /// `XPC_W1`, if XI is set and it was read
+    pub xpc_w1: Option<XpcW1>,
+    /// `XPC_W2`, if XEB is set and it was read
+    pub xpc_w2: Option<u16>,
+}
+
+impl EpcMemory {
+    /// Decode EPC bank words read from word 0
+    ///
+    /// Returns `None` if fewer than the `StoredCRC` and PC words were read. The EPC is cut
+    /// short if `bytes` end before the length the PC word gives; XPC words are decoded when
+    /// `bytes` reach them.
+    #[must_use]
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        let word = |address: u32| {
+            let start = usize::try_from(address).ok()? * 2;
+            bytes
+                .get(start..start + 2)
+                .map(|word| u16::from_be_bytes([word[0], word[1]]))
+        };
+        let stored_crc = word(STORED_CRC_ADDRESS)?;
+        let pc = ProtocolControl::from_word(word(PC_ADDRESS)?);
+        let start = 4;
+        let end = (start + usize::from(pc.epc_words) * 2).min(bytes.len());
+        let xpc_w1 = word(XPC_W1_ADDRESS).filter(|_| pc.xi).map(XpcW1);
+        let xpc_w2 = xpc_w1
+            .filter(|w1| w1.has(XpcW1::XEB))
+            .and_then(|_| word(XPC_W1_ADDRESS + 1));
+        Some(Self {
+            stored_crc,
+            pc,
+            epc: bytes[start..end].to_vec(),
+            xpc_w1,
+            xpc_w2,
+        })
+    }
+
+    /// Whether the whole EPC the PC word describes was read
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.epc.len() == usize::from(self.pc.epc_words) * 2
+    }
+
+    /// CRC-16 over the PC word and EPC, as the tag computes `StoredCRC`
+    #[must_use]
+    pub fn computed_crc(&self) -> u16 {
+        let mut data = self.pc.to_word().to_be_bytes().to_vec();
+        data.extend_from_slice(&self.epc);
+        crc16(&data)
+    }
+
+    /// Whether `StoredCRC` matches the PC word and EPC; `None` if the EPC was not read whole
+    #[must_use]
+    pub fn crc_matches(&self) -> Option<bool> {
+        self.is_complete()
+            .then(|| self.stored_crc == self.computed_crc())
+    }
+
+    /// Labelled lines describing the EPC bank, for display
+    #[must_use]
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        let crc = match self.crc_matches() {
+            Some(true) => "valid".to_string(),
+            Some(false) => format!("expected 0x{:04X}", self.computed_crc()),
+            None => "EPC not read in full".to_string(),
+        };
+        let mut fields = vec![
+            ("StoredCRC", format!("0x{:04X} ({crc})", self.stored_crc)),
+            ("PC", self.pc.to_string()),
+            ("EPC", hex::encode_upper(&self.epc)),
+        ];
+        if let Some(xpc_w1) = self.xpc_w1 {
+            fields.push(("XPC_W1", xpc_w1.to_string()));
+        }
+        if let Some(xpc_w2) = self.xpc_w2 {
+            fields.push(("XPC_W2", format!("0x{xpc_w2:04X}")));
+        }
+        fields
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc16_matches_gen2_check_value() {
+        // CRC-16/GENIBUS check value for "123456789"
+        assert_eq!(crc16(b"123456789"), 0xD64E);
+    }
+
+    #[test]
+    fn pc_words_round_trip() {
+        let pc = ProtocolControl::from_word(0x3400);
+        assert_eq!(
+            pc,
+            ProtocolControl {
+                epc_words: 6,
+                umi: true,
+                ..ProtocolControl::default()
+            }
+        );
+        assert_eq!(pc.to_word(), 0x3400);
+        assert_eq!(pc.with_epc_words(8).to_word(), 0x4400);
+        let iso = ProtocolControl::from_word(0x31A2);
+        assert!(iso.toggle && !iso.xi);
+        assert_eq!(iso.attributes, 0xA2);
+        assert_eq!(iso.to_string(), "0x31A2: 6-word EPC, ISO toggle, AFI 0xA2");
+    }
+
+    #[test]
+    fn epc_memory_decodes_and_checks_the_crc() {
+        let epc = [
+            0x30, 0x74, 0x25, 0x7B, 0xF7, 0x19, 0x4E, 0x40, 0x00, 0x00, 0x1A, 0x85,
+        ];
+        let mut bank = vec![0, 0, 0x30, 0x00];
+        bank.extend_from_slice(&epc);
+        let crc = crc16(&bank[2..]);
+        bank[..2].copy_from_slice(&crc.to_be_bytes());
+
+        let memory = EpcMemory::decode(&bank).unwrap();
+        assert_eq!(memory.epc, epc);
+        assert_eq!(memory.crc_matches(), Some(true));
+        assert_eq!(memory.xpc_w1, None);
+
+        bank[15] ^= 1;
+        assert_eq!(EpcMemory::decode(&bank).unwrap().crc_matches(), Some(false));
+        assert_eq!(EpcMemory::decode(&bank[..8]).unwrap().crc_matches(), None);
+        assert_eq!(EpcMemory::decode(&bank[..3]), None);
+    }
+
+    #[test]
+    fn xpc_words_are_decoded_when_indicated() {
+        let mut bank = vec![0u8; 70];
+        bank[2..4].copy_from_slice(&0x3200u16.to_be_bytes());
+        bank[66..68].copy_from_slice(&(XpcW1::XEB | XpcW1::K).to_be_bytes());
+        bank[68..70].copy_from_slice(&0x1234u16.to_be_bytes());
+        let memory = EpcMemory::decode(&bank).unwrap();
+        let xpc_w1 = memory.xpc_w1.unwrap();
+        assert_eq!(xpc_w1.flag_names(), ["XEB", "K"]);
+        assert_eq!(memory.xpc_w2, Some(0x1234));
+
+        // Without XI the words are EPC memory
+        bank[2..4].copy_from_slice(&0x3000u16.to_be_bytes());
+        assert_eq!(EpcMemory::decode(&bank).unwrap().xpc_w1, None);
+    }
+}
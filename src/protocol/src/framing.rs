@@ -0,0 +1,445 @@
+//! Splitting of frames into 64-byte HID reports and their reassembly.
+//!
+//! Every frame starts with a length byte counting the bytes that follow it, and travels in
+//! one or more 64-byte reports:
+//! - host to reader: a frame that fits one report is sent as is, padded with zeros. A longer
+//!   frame is cut into 63-byte pieces, each sent after a marker byte: [`MARKER_CONTINUE`]
+//!   for every piece but the last and [`MARKER_END`] for the last. The frame's own length
+//!   byte tells where the padding of the last report starts
+//! - reader to host: the length byte is left out. Byte 0 of every report holds the number
+//!   of frame bytes that follow in that report, and a full report (63) means another one
+//!   continues the frame, so a frame that exactly fills its last report is closed by an
+//!   empty one
+//!
+//! [`Framer`] produces the reports of a frame and [`Deframer`] reassembles frames from
+//! reports, rejecting reports that break the rules with [`UhfError::Fragmentation`].
+
+use crate::interface::Result;
+use crate::types::UhfError;
+
+/// Size of a single HID report exchanged with the reader
+pub const REPORT_SIZE: usize = 64;
+/// Frame bytes a report carries after its leading length or marker byte
+pub const REPORT_PAYLOAD_SIZE: usize = REPORT_SIZE - 1;
+/// Leads every report but the last of a multi-report frame sent to the reader
+pub const MARKER_CONTINUE: u8 = 0x82;
+/// Leads the last report of a multi-report frame sent to the reader
+pub const MARKER_END: u8 = 0x02;
+/// Longest frame the length byte can describe, including the length byte itself
+pub const MAX_FRAME_SIZE: usize = 1 + u8::MAX as usize;
+
+/// Which way a frame travels, which decides how it is split into reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+    /// Commands from the host to the reader, continued with marker bytes
+    #[default]
+    HostToReader,
+    /// Replies from the reader to the host, with a length byte in every report
+    ReaderToHost,
+}
+
+fn fragmentation(message: impl Into<String>) -> UhfError {
+    UhfError::Fragmentation(message.into())
+}
+
+/// Iterator over the reports carrying one frame
+#[derive(Debug, Clone)]
+pub struct Framer<'a> {
+    direction: Direction,
+    /// Frame bytes still to send; the reply length byte is already dropped
+    remaining: &'a [u8],
+    /// Whether the frame needs more than one report
+    multi_report: bool,
+    /// Whether the empty report closing a reply that fills its last report is still due
+    closing_report: bool,
+}
+
+impl<'a> Framer<'a> {
+    /// Prepare the reports of `frame`, a length byte followed by that many bytes
+    ///
+    /// # Errors
+    /// Returns `UhfError::Fragmentation` if the length byte does not match the frame.
+    pub fn new(direction: Direction, frame: &'a [u8]) -> Result<Self> {
+        let Some((&len, body)) = frame.split_first() else {
+            return Err(fragmentation("Cannot frame an empty frame"));
+        };
+        if usize::from(len) != body.len() {
+            return Err(fragmentation(format!(
+                "Length byte {len} does not match the {} bytes that follow it",
+                body.len()
+            )));
+        }
+        Ok(match direction {
+            Direction::HostToReader => Self {
+                direction,
+                remaining: frame,
+                multi_report: frame.len() > REPORT_SIZE,
+                closing_report: false,
+            },
+            Direction::ReaderToHost => Self {
+                direction,
+                remaining: body,
+                multi_report: body.len() >= REPORT_PAYLOAD_SIZE,
+                closing_report: body.len().is_multiple_of(REPORT_PAYLOAD_SIZE),
+            },
+        })
+    }
+
+    /// Whether the frame is split across several reports
+    #[must_use]
+    pub fn is_multi_report(&self) -> bool {
+        self.multi_report
+    }
+}
+
+impl Iterator for Framer<'_> {
+    type Item = [u8; REPORT_SIZE];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut report = [0u8; REPORT_SIZE];
+        match self.direction {
+            Direction::HostToReader if !self.multi_report => {
+                if self.remaining.is_empty() {
+                    return None;
+                }
+                report[..self.remaining.len()].copy_from_slice(self.remaining);
+                self.remaining = &[];
+            }
+            Direction::HostToReader => {
+                if self.remaining.is_empty() {
+                    return None;
+                }
+                let (piece, rest) = self
+                    .remaining
+                    .split_at(self.remaining.len().min(REPORT_PAYLOAD_SIZE));
+                report[0] = if rest.is_empty() {
+                    MARKER_END
+                } else {
+                    MARKER_CONTINUE
+                };
+                report[1..=piece.len()].copy_from_slice(piece);
+                self.remaining = rest;
+            }
+            Direction::ReaderToHost => {
+                if self.remaining.is_empty() {
+                    // The closing report is all zeros: it carries no bytes
+                    return std::mem::take(&mut self.closing_report).then_some(report);
+                }
+                let (piece, rest) = self
+                    .remaining
+                    .split_at(self.remaining.len().min(REPORT_PAYLOAD_SIZE));
+                report[0] = u8::try_from(piece.len()).unwrap_or(0);
+                report[1..=piece.len()].copy_from_slice(piece);
+                self.remaining = rest;
+            }
+        }
+        Some(report)
+    }
+}
+
+/// Reassembly state of a [`Deframer`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+enum DeframerState {
+    /// Waiting for the first report of a frame
+    #[default]
+    Idle,
+    /// Collecting the reports of a frame, holding its bytes so far
+    Continuing(Vec<u8>),
+}
+
+/// State machine reassembling frames from the reports that carry them
+///
+/// Reports are fed in order with [`push`](Self::push), which hands back each frame once
+/// its last report arrives. A report that breaks the framing rules drops the partial frame
+/// and leaves the deframer ready for the next one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Deframer {
+    direction: Direction,
+    state: DeframerState,
+}
+
+impl Deframer {
+    /// Deframer for frames travelling in `direction`
+    #[must_use]
+    pub fn new(direction: Direction) -> Self {
+        Self {
+            direction,
+            state: DeframerState::Idle,
+        }
+    }
+
+    /// Whether no frame is partially received
+    #[must_use]
+    pub fn is_idle(&self) -> bool {
+        self.state == DeframerState::Idle
+    }
+
+    /// Drop any partially received frame
+    pub fn reset(&mut self) {
+        self.state = DeframerState::Idle;
+    }
+
+    /// Feed the next report, which may be shorter than [`REPORT_SIZE`] if the transport
+    /// dropped its padding
+    ///
+    /// # Returns
+    /// The complete frame, length byte first and padding removed, once `report` ends it
+    ///
+    /// # Errors
+    /// Returns `UhfError::Fragmentation` if the report is empty, announces more bytes than
+    /// it carries or than fit a report, interrupts a multi-report frame, or completes a
+    /// frame whose length byte does not match it.
+    pub fn push(&mut self, report: &[u8]) -> Result<Option<Vec<u8>>> {
+        let result = match self.direction {
+            Direction::HostToReader => self.push_command_report(report),
+            Direction::ReaderToHost => self.push_reply_report(report),
+        };
+        if result.is_err() {
+            self.reset();
+        }
+        result
+    }
+
+    fn push_command_report(&mut self, report: &[u8]) -> Result<Option<Vec<u8>>> {
+        let Some((&first, rest)) = report.split_first() else {
+            return Err(fragmentation("Empty report"));
+        };
+        let state = std::mem::take(&mut self.state);
+        match (state, first) {
+            (state, MARKER_CONTINUE) => {
+                if rest.len() < REPORT_PAYLOAD_SIZE {
+                    return Err(fragmentation(format!(
+                        "Continuation report carries {} of {REPORT_PAYLOAD_SIZE} bytes",
+                        rest.len()
+                    )));
+                }
+                let mut frame = match state {
+                    DeframerState::Idle => Vec::new(),
+                    DeframerState::Continuing(frame) => frame,
+                };
+                frame.extend_from_slice(&rest[..REPORT_PAYLOAD_SIZE]);
+                if frame.len() >= MAX_FRAME_SIZE {
+                    return Err(fragmentation(format!(
+                        "Frame exceeds {MAX_FRAME_SIZE} bytes"
+                    )));
+                }
+                self.state = DeframerState::Continuing(frame);
+                Ok(None)
+            }
+            (DeframerState::Continuing(mut frame), MARKER_END) => {
+                frame.extend_from_slice(&rest[..rest.len().min(REPORT_PAYLOAD_SIZE)]);
+                let len = 1 + usize::from(frame[0]);
+                // The padding must fit the last report, or that report was not needed
+                if len > frame.len() || frame.len() - len >= REPORT_PAYLOAD_SIZE {
+                    return Err(fragmentation(format!(
+                        "Length byte {} does not match a frame of {} reports",
+                        frame[0],
+                        frame.len().div_ceil(REPORT_PAYLOAD_SIZE)
+                    )));
+                }
+                frame.truncate(len);
+                Ok(Some(frame))
+            }
+            (DeframerState::Continuing(_), first) => Err(fragmentation(format!(
+                "Report starting with 0x{first:02X} interrupts a multi-report frame"
+            ))),
+            (DeframerState::Idle, len) => {
+                let len = usize::from(len);
+                if len > REPORT_PAYLOAD_SIZE {
+                    return Err(fragmentation(format!(
+                        "Length byte {len} exceeds a single report"
+                    )));
+                }
+                if rest.len() < len {
+                    return Err(fragmentation(format!(
+                        "Short report: {} of {len} bytes",
+                        rest.len()
+                    )));
+                }
+                Ok(Some(report[..=len].to_vec()))
+            }
+        }
+    }
+
+    fn push_reply_report(&mut self, report: &[u8]) -> Result<Option<Vec<u8>>> {
+        let Some((&len, rest)) = report.split_first() else {
+            return Err(fragmentation("Empty report"));
+        };
+        let len = usize::from(len);
+        if len > REPORT_PAYLOAD_SIZE {
+            return Err(fragmentation(format!(
+                "Length byte {len} exceeds a single report"
+            )));
+        }
+        if rest.len() < len {
+            return Err(fragmentation(format!(
+                "Short report: {} of {len} bytes",
+                rest.len()
+            )));
+        }
+        let mut body = match std::mem::take(&mut self.state) {
+            DeframerState::Idle => Vec::new(),
+            DeframerState::Continuing(body) => body,
+        };
+        body.extend_from_slice(&rest[..len]);
+        let Ok(body_len) = u8::try_from(body.len()) else {
+            return Err(fragmentation(format!(
+                "Frame exceeds {MAX_FRAME_SIZE} bytes"
+            )));
+        };
+        if len == REPORT_PAYLOAD_SIZE {
+            self.state = DeframerState::Continuing(body);
+            return Ok(None);
+        }
+        let mut frame = Vec::with_capacity(1 + body.len());
+        frame.push(body_len);
+        frame.extend_from_slice(&body);
+        Ok(Some(frame))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame_of(body_len: usize) -> Vec<u8> {
+        let body = (0..body_len).map(|i| u8::try_from(i % 251).unwrap() + 1);
+        std::iter::once(u8::try_from(body_len).unwrap())
+            .chain(body)
+            .collect()
+    }
+
+    fn round_trip(direction: Direction, frame: &[u8]) -> (usize, Vec<u8>) {
+        let mut deframer = Deframer::new(direction);
+        let reports: Vec<_> = Framer::new(direction, frame).unwrap().collect();
+        let (last, others) = reports.split_last().unwrap();
+        for report in others {
+            assert_eq!(deframer.push(report).unwrap(), None);
+        }
+        let frame = deframer.push(last).unwrap().unwrap();
+        assert!(deframer.is_idle());
+        (reports.len(), frame)
+    }
+
+    #[test]
+    fn every_frame_length_survives_both_directions() {
+        for body_len in 0..=usize::from(u8::MAX) {
+            let frame = frame_of(body_len);
+            for direction in [Direction::HostToReader, Direction::ReaderToHost] {
+                assert_eq!(
+                    round_trip(direction, &frame).1,
+                    frame,
+                    "{direction:?} {body_len}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn report_counts_follow_each_direction() {
+        let reports = |direction, body_len| round_trip(direction, &frame_of(body_len)).0;
+        assert_eq!(reports(Direction::HostToReader, 63), 1);
+        assert_eq!(reports(Direction::HostToReader, 64), 2);
+        assert_eq!(reports(Direction::HostToReader, 125), 2);
+        assert_eq!(reports(Direction::HostToReader, 126), 3);
+        assert_eq!(reports(Direction::ReaderToHost, 0), 1);
+        assert_eq!(reports(Direction::ReaderToHost, 62), 1);
+        // A reply filling its last report is closed by an empty one
+        assert_eq!(reports(Direction::ReaderToHost, 63), 2);
+        assert_eq!(reports(Direction::ReaderToHost, 64), 2);
+        assert_eq!(reports(Direction::ReaderToHost, 126), 3);
+    }
+
+    #[test]
+    fn markers_lead_multi_report_commands() {
+        let frame = frame_of(100);
+        let framer = Framer::new(Direction::HostToReader, &frame).unwrap();
+        assert!(framer.is_multi_report());
+        let reports: Vec<_> = framer.collect();
+        assert_eq!(reports[0][0], MARKER_CONTINUE);
+        assert_eq!(&reports[0][1..], &frame[..63]);
+        assert_eq!(reports[1][0], MARKER_END);
+        assert_eq!(&reports[1][1..39], &frame[63..]);
+        // Trailing zero bytes of the frame are kept, not mistaken for padding
+        let mut frame = frame_of(70);
+        frame[60..].fill(0);
+        assert_eq!(round_trip(Direction::HostToReader, &frame).1, frame);
+    }
+
+    #[test]
+    fn mismatched_length_bytes_are_not_framed() {
+        assert!(Framer::new(Direction::HostToReader, &[]).is_err());
+        assert!(Framer::new(Direction::HostToReader, &[3, 1, 2]).is_err());
+        assert!(Framer::new(Direction::ReaderToHost, &[1, 1, 2]).is_err());
+    }
+
+    #[test]
+    fn padding_and_unpadded_reports_are_accepted() {
+        let mut deframer = Deframer::new(Direction::ReaderToHost);
+        assert_eq!(
+            deframer.push(&[2, 0xAA, 0xBB]).unwrap(),
+            Some(vec![2, 0xAA, 0xBB])
+        );
+        let mut padded = [0xEE; REPORT_SIZE];
+        padded[..3].copy_from_slice(&[2, 0xAA, 0xBB]);
+        assert_eq!(deframer.push(&padded).unwrap(), Some(vec![2, 0xAA, 0xBB]));
+
+        let mut deframer = Deframer::new(Direction::HostToReader);
+        assert_eq!(deframer.push(&[1, 0x57]).unwrap(), Some(vec![1, 0x57]));
+        // A single-report frame may start with the end marker value as its length
+        assert_eq!(
+            deframer.push(&[MARKER_END, 2, 0x57, 0, 0]).unwrap(),
+            Some(vec![2, 2, 0x57])
+        );
+    }
+
+    #[test]
+    fn malformed_reply_reports_are_rejected() {
+        let mut deframer = Deframer::new(Direction::ReaderToHost);
+        let rejected = |deframer: &mut Deframer, report: &[u8]| {
+            matches!(deframer.push(report), Err(UhfError::Fragmentation(_)))
+        };
+        assert!(rejected(&mut deframer, &[]));
+        assert!(rejected(&mut deframer, &[64; REPORT_SIZE]));
+        assert!(rejected(&mut deframer, &[5, 1, 2]));
+
+        // A short report in the middle of a frame drops the partial frame
+        assert_eq!(deframer.push(&[63; REPORT_SIZE]).unwrap(), None);
+        assert!(!deframer.is_idle());
+        assert!(rejected(&mut deframer, &[63; 10]));
+        assert!(deframer.is_idle());
+
+        // Five full reports would overflow the length byte
+        for _ in 0..4 {
+            assert_eq!(deframer.push(&[63; REPORT_SIZE]).unwrap(), None);
+        }
+        assert!(rejected(&mut deframer, &[63; REPORT_SIZE]));
+        assert!(deframer.is_idle());
+    }
+
+    #[test]
+    fn malformed_command_reports_are_rejected() {
+        let mut deframer = Deframer::new(Direction::HostToReader);
+        let rejected = |deframer: &mut Deframer, report: &[u8]| {
+            matches!(deframer.push(report), Err(UhfError::Fragmentation(_)))
+        };
+        assert!(rejected(&mut deframer, &[64; REPORT_SIZE]));
+        assert!(rejected(&mut deframer, &[4, 1, 2]));
+        assert!(rejected(&mut deframer, &[MARKER_CONTINUE, 1, 2]));
+
+        let mut continuation = [0u8; REPORT_SIZE];
+        continuation[0] = MARKER_CONTINUE;
+        continuation[1] = 70;
+        assert_eq!(deframer.push(&continuation).unwrap(), None);
+        // Only another continuation or the end report may follow
+        assert!(rejected(&mut deframer, &[3, 1, 2, 3]));
+        assert!(deframer.is_idle());
+
+        // The length byte must end the frame inside its last report
+        assert_eq!(deframer.push(&continuation).unwrap(), None);
+        assert!(rejected(&mut deframer, &[MARKER_END; 3]));
+        continuation[1] = 200;
+        assert_eq!(deframer.push(&continuation).unwrap(), None);
+        assert!(rejected(&mut deframer, &[MARKER_END; REPORT_SIZE]));
+    }
+}
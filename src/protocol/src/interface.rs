@@ -9,8 +9,14 @@
 //! risking data loss or tag unusability. Test with disposable tags first.
 //!
 
-pub use crate::types::{MemoryBank, UhfError, UsbIo};
+use crate::codec::{Command, CommandKind, Response, STATUS_OK};
+use crate::framing::{Deframer, Direction, Framer, REPORT_SIZE};
+pub use crate::types::{
+    LockPayload, MemoryBank, ReaderIdentity, ReaderSettings, TagFilter, TagReport, UhfError, UsbIo,
+};
+use std::thread;
 use std::time::{Duration, Instant};
+use tracing::{debug, debug_span, field, trace};
 
 /// USB Device constants
 /// Vendor ID for the RFID reader USB device (Fongwah Technology Co., Ltd)
@@ -22,162 +28,451 @@ pub const ENDPOINT_OUT: u8 = 0x03; // EP 3 OUT
 /// Default endpoint address for reading, use only as a fallback
 pub const ENDPOINT_IN: u8 = 0x82; // EP 2 IN
 
+/// Time to wait for the reader to answer a command
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Extra attempts at a command that failed with a retryable error
+const DEFAULT_RETRIES: u8 = 2;
+
+/// Pause before the first retry of a command
+const DEFAULT_BACKOFF: Duration = Duration::from_millis(50);
+
+/// Logged in place of frames that carry passwords
+const REDACTED: &str = "<redacted>";
+
+/// How long to wait for each stale report when draining input before a command
+const DRAIN_TIMEOUT: Duration = Duration::from_millis(1);
+
+/// Upper bound on stale reports drained before a command
+const MAX_STALE_REPORTS: usize = 64;
+
+/// Upper bound on replies to other commands skipped while waiting for the right one
+const MAX_STALE_REPLIES: usize = 8;
+
+/// Upper bound on follow-up polls in one inventory round
+const MAX_INVENTORY_POLLS: usize = 1024;
+
 /// Convenient result alias for protocol operations
 pub type Result<T> = std::result::Result<T, UhfError>;
 
-/// UHF RFID Reader device handle
-pub struct Interface {
-    /// When enabled, prints protocol I/O for troubleshooting
-    pub debug_mode: bool,
+/// Reply timeouts and retries of reader commands
+///
+/// A failed command is sent again up to [`retries`](Self::retries) times, after waiting
+/// [`backoff`](Self::backoff) before the first retry and twice as long before each further
+/// one. Only retryable errors ([`UhfError::is_retryable`]) are retried. Once the reader has
+/// received the whole frame, only commands that are safe to repeat
+/// ([`Command::is_idempotent`]) are: a write, lock, kill or password change that may already
+/// have taken effect is never sent twice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    default_timeout: Duration,
+    /// Per-kind timeouts overriding the default, indexed by `CommandKind`
+    timeouts: [Option<Duration>; CommandKind::ALL.len()],
+    retries: u8,
+    backoff: Duration,
 }
 
-impl Interface {
-    /// Enable or disable debug output
-    pub fn set_debug_mode(&mut self, enabled: bool) {
-        self.debug_mode = enabled;
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            default_timeout: RESPONSE_TIMEOUT,
+            timeouts: [None; CommandKind::ALL.len()],
+            retries: DEFAULT_RETRIES,
+            backoff: DEFAULT_BACKOFF,
+        }
     }
+}
 
-    /// Send a command to the device
-    fn send_command(&self, device: &impl UsbIo, data: &[u8]) -> Result<()> {
-        let mut bytes_written = 0;
+impl RetryPolicy {
+    /// Policy that sends every command exactly once
+    #[must_use]
+    pub fn no_retries() -> Self {
+        Self::default().with_retries(0)
+    }
 
-        while bytes_written < data.len() {
-            let end = (bytes_written + 64).min(data.len());
-            let mut chunk = data[bytes_written..end].to_vec();
-            chunk.resize(64, 0); // Pad to 64 bytes
+    /// Reply timeout of commands without a timeout of their own, and of raw transfers
+    #[must_use]
+    pub fn default_timeout(&self) -> Duration {
+        self.default_timeout
+    }
 
-            if data.len() > 64 && bytes_written + 64 < data.len() {
-                chunk[0] = 0x82; // Continuation marker
-            } else if data.len() > 64 {
-                chunk[0] = 0x02; // End marker
-            }
+    /// Reply timeout of commands of the given kind
+    #[must_use]
+    pub fn timeout(&self, kind: CommandKind) -> Duration {
+        self.timeouts[kind as usize].unwrap_or(self.default_timeout)
+    }
 
-            let written = device.write_bulk(ENDPOINT_OUT, &chunk, Duration::from_secs(2))?;
+    /// Extra attempts after a retryable failure
+    #[must_use]
+    pub fn retries(&self) -> u8 {
+        self.retries
+    }
 
-            if self.debug_mode {
-                println!(
-                    "Sent command chunk: {} (Bytes written: {})",
-                    hex::encode_upper(&chunk),
-                    written
-                );
-            }
+    /// Pause before the first retry
+    #[must_use]
+    pub fn backoff(&self) -> Duration {
+        self.backoff
+    }
+
+    /// Set the reply timeout of commands without a timeout of their own
+    #[must_use]
+    pub fn with_default_timeout(mut self, timeout: Duration) -> Self {
+        self.default_timeout = timeout;
+        self
+    }
+
+    /// Give commands of the given kind their own reply timeout
+    #[must_use]
+    pub fn with_timeout(mut self, kind: CommandKind, timeout: Duration) -> Self {
+        self.timeouts[kind as usize] = Some(timeout);
+        self
+    }
+
+    /// Set the number of extra attempts after a retryable failure
+    #[must_use]
+    pub fn with_retries(mut self, retries: u8) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Set the pause before the first retry
+    #[must_use]
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Pause before retry number `retry`, counting from 1
+    #[must_use]
+    pub fn delay_before_retry(&self, retry: u8) -> Duration {
+        let doublings = u32::from(retry.saturating_sub(1)).min(16);
+        self.backoff.saturating_mul(1 << doublings)
+    }
+
+    /// Whether `command` may be sent again after `attempts` attempts, the last of which failed
+    /// with `error`; `delivered` tells whether the reader received the whole frame
+    #[must_use]
+    pub fn should_retry(
+        &self,
+        command: &Command,
+        error: &UhfError,
+        delivered: bool,
+        attempts: u8,
+    ) -> bool {
+        attempts <= self.retries && error.is_retryable() && (!delivered || command.is_idempotent())
+    }
+}
+
+/// Error of one attempt at a command
+struct Failure {
+    error: UhfError,
+    /// Whether the reader received the whole frame before the error
+    delivered: bool,
+}
+
+/// How [`Interface::raw_transaction`] sends bytes and collects the reply
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawOptions {
+    /// Prefix the bytes with their length and split them into reports like a command frame;
+    /// otherwise send them as they are, in a single report
+    pub framed: bool,
+    /// How long to wait for the reply; the policy's default timeout if `None`
+    pub timeout: Option<Duration>,
+    /// Keep reading continuation reports until the reply frame is complete; otherwise stop
+    /// after the first report
+    pub collect_continuations: bool,
+}
+
+impl Default for RawOptions {
+    fn default() -> Self {
+        Self {
+            framed: true,
+            timeout: None,
+            collect_continuations: true,
+        }
+    }
+}
+
+/// What the reader sent back in a raw transaction
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RawReply {
+    /// Reports in the order received, each as long as the transport returned
+    pub reports: Vec<Vec<u8>>,
+    /// Reply frame reassembled from the reports, length byte included, if they completed one
+    pub frame: Option<Vec<u8>>,
+}
+
+/// UHF RFID Reader device handle
+///
+/// Every command runs in a `command` tracing span carrying the command name, request and reply
+/// frames as hex, the number of attempts, the round-trip time in microseconds and the outcome.
+/// Frames carrying passwords are redacted there; the raw reports are only traced at `TRACE`
+/// level.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Interface {
+    /// Reply timeouts and retries applied to every command
+    pub policy: RetryPolicy,
+}
 
-            bytes_written += 64;
+impl Interface {
+    /// Send a command frame to the device, split into as many reports as it needs
+    fn send_command(device: &impl UsbIo, frame: &[u8], timeout: Duration) -> Result<()> {
+        for report in Framer::new(Direction::HostToReader, frame)? {
+            Self::send_report(device, &report, timeout)?;
         }
+        Ok(())
+    }
 
+    /// Write one whole report to the device
+    fn send_report(
+        device: &impl UsbIo,
+        report: &[u8; REPORT_SIZE],
+        timeout: Duration,
+    ) -> Result<()> {
+        let written = device.write_bulk(ENDPOINT_OUT, report, timeout)?;
+        trace!(report = %hex::encode_upper(report), written, "sent report");
+        if written != REPORT_SIZE {
+            return Err(UhfError::Fragmentation(format!(
+                "Short write: {written} of {REPORT_SIZE} report bytes"
+            )));
+        }
         Ok(())
     }
 
-    /// Read a response from the device
-    fn read_response(&self, device: &impl UsbIo, timeout: Duration) -> Result<Vec<u8>> {
-        let mut response = Vec::new();
+    /// Read one report from the device
+    fn read_report(
+        device: &impl UsbIo,
+        report: &mut [u8; REPORT_SIZE],
+        timeout: Duration,
+    ) -> Result<usize> {
+        let len = device
+            .read_bulk(ENDPOINT_IN, report, timeout)
+            .inspect_err(|e| {
+                if matches!(e, UhfError::Timeout) {
+                    trace!(?timeout, "no report received within timeout");
+                }
+            })?;
+        if len > REPORT_SIZE {
+            return Err(UhfError::Communication(format!(
+                "Transport returned {len} bytes for a {REPORT_SIZE}-byte report"
+            )));
+        }
+        trace!(report = %hex::encode_upper(&report[..len]), len, "received report");
+        Ok(len)
+    }
+
+    /// Read reports from the device until they complete a reply frame
+    fn read_response(device: &impl UsbIo, timeout: Duration) -> Result<Vec<u8>> {
+        let mut deframer = Deframer::new(Direction::ReaderToHost);
         let start_time = Instant::now();
 
         loop {
-            let mut chunk = [0u8; 64];
-            match device.read_bulk(ENDPOINT_IN, &mut chunk, timeout) {
-                Ok(len) => {
-                    response.extend_from_slice(&chunk[..len]);
-
-                    if self.debug_mode {
-                        println!(
-                            "Received response chunk: {} (Length: {})",
-                            hex::encode_upper(&chunk[..len]),
-                            len
-                        );
-                    }
-
-                    if chunk[0] != 63 || start_time.elapsed() > timeout {
-                        break;
-                    }
-                    if len < 64 {
-                        break;
-                    }
-                }
-                Err(UhfError::Timeout) => {
-                    if self.debug_mode {
-                        println!("No data received within timeout period");
-                    }
-                    return Err(UhfError::Timeout);
-                }
-                Err(e) => return Err(e),
+            let mut report = [0u8; REPORT_SIZE];
+            let len = Self::read_report(device, &mut report, timeout)?;
+            if let Some(frame) = deframer.push(&report[..len])? {
+                return Ok(frame);
+            }
+            if start_time.elapsed() > timeout {
+                trace!(?timeout, "reply frame not completed within timeout");
+                return Err(UhfError::Timeout);
             }
         }
+    }
+
+    /// Send a command and decode the reader's reply along with its raw frame
+    ///
+    /// Non-zero status bytes are turned into the matching `UhfError`. Failed attempts are
+    /// retried as the policy allows, as long as the device stays connected.
+    fn transact(&self, device: &impl UsbIo, command: &Command) -> Result<(Response, Vec<u8>)> {
+        let request = command.encode()?;
+        let frame_hex = |frame: &[u8]| {
+            if command.carries_secret() {
+                REDACTED.to_string()
+            } else {
+                hex::encode_upper(frame)
+            }
+        };
+        let span = debug_span!(
+            "command",
+            command = command.name(),
+            request = %frame_hex(&request),
+            reply = field::Empty,
+            attempts = field::Empty,
+            duration_us = field::Empty,
+            outcome = field::Empty,
+        );
+        let _entered = span.enter();
+        let started = Instant::now();
+        let timeout = self.policy.timeout(command.kind());
+
+        let mut attempts = 0;
+        let result = loop {
+            attempts += 1;
+            match Self::exchange(device, command, &request, timeout) {
+                Err(failure)
+                    if device.is_connected()
+                        && self.policy.should_retry(
+                            command,
+                            &failure.error,
+                            failure.delivered,
+                            attempts,
+                        ) =>
+                {
+                    let delay = self.policy.delay_before_retry(attempts);
+                    debug!(attempts, error = %failure.error, ?delay, "retrying command");
+                    thread::sleep(delay);
+                }
+                result => break result.map_err(|failure| failure.error),
+            }
+        };
 
-        if self.debug_mode && !response.is_empty() {
-            println!(
-                "Full response: {} (Length: {})",
-                hex::encode_upper(&response),
-                response.len()
-            );
+        span.record("attempts", attempts);
+        span.record(
+            "duration_us",
+            u64::try_from(started.elapsed().as_micros()).unwrap_or(u64::MAX),
+        );
+        match &result {
+            Ok((_, frame)) => {
+                span.record("reply", field::display(frame_hex(frame)));
+                span.record("outcome", "ok");
+                debug!("command completed");
+            }
+            Err(error) => {
+                if let Some(frame) = error.frame() {
+                    span.record("reply", field::display(frame_hex(frame)));
+                }
+                span.record("outcome", field::display(error));
+                debug!("command failed");
+            }
         }
+        result
+    }
 
-        if response.is_empty() {
-            Err(UhfError::InvalidResponse)
-        } else {
-            Ok(response)
+    /// Discard input reports already waiting, such as the late reply to a command that
+    /// timed out, so they are not taken for the answer to the next command
+    fn drain_stale_reports(device: &impl UsbIo) -> Result<()> {
+        let mut report = [0u8; REPORT_SIZE];
+        for _ in 0..MAX_STALE_REPORTS {
+            match device.read_bulk(ENDPOINT_IN, &mut report, DRAIN_TIMEOUT) {
+                Ok(len) => debug!(len, "discarded stale report"),
+                Err(UhfError::Timeout) => return Ok(()),
+                Err(e) => return Err(e),
+            }
         }
+        Err(UhfError::Communication(format!(
+            "Reader sent more than {MAX_STALE_REPORTS} unsolicited reports"
+        )))
     }
 
-    /// Convert value to hex character (ASCII)
-    fn hex_value(value: u8) -> u8 {
-        if value >= 10 {
-            value + 55 // 'A'-'F'
-        } else {
-            value + 48 // '0'-'9'
+    /// Write an encoded command and read back the decoded reply
+    ///
+    /// Pending input is drained first, and replies that do not answer `command` or fail to
+    /// decode are skipped, so a late reply to an earlier command cannot be mistaken for this
+    /// one's and a garbled frame does not end the exchange.
+    fn exchange(
+        device: &impl UsbIo,
+        command: &Command,
+        request: &[u8],
+        timeout: Duration,
+    ) -> std::result::Result<(Response, Vec<u8>), Failure> {
+        let undelivered = |error| Failure {
+            error,
+            delivered: false,
+        };
+        Self::drain_stale_reports(device).map_err(undelivered)?;
+        Self::send_command(device, request, timeout).map_err(undelivered)?;
+        let delivered = |error| Failure {
+            error,
+            delivered: true,
+        };
+        for _ in 0..=MAX_STALE_REPLIES {
+            let frame = Self::read_response(device, timeout).map_err(delivered)?;
+            let response = match Response::decode(&frame) {
+                Ok(response) => response,
+                Err(error) => {
+                    // A garbled frame may be what is left of a reply to a secret-bearing command
+                    let reply = if command.carries_secret() {
+                        REDACTED.to_string()
+                    } else {
+                        hex::encode_upper(&frame)
+                    };
+                    debug!(%reply, %error, "discarded reply that failed to decode");
+                    continue;
+                }
+            };
+            if !response.answers(command) {
+                // The reply may hold Reserved bank words read by the earlier command
+                let reply = if matches!(response, Response::Read { .. }) {
+                    REDACTED.to_string()
+                } else {
+                    hex::encode_upper(&frame)
+                };
+                debug!(%reply, "discarded reply to another command");
+                continue;
+            }
+            return match response {
+                Response::AccessFailed { status, .. }
+                | Response::ConfigFailed { status, .. }
+                | Response::IdentifyFailed { status }
+                | Response::Action { status }
+                    if status != STATUS_OK =>
+                {
+                    Err(delivered(UhfError::from_status(status, frame)))
+                }
+                decoded => Ok((decoded, frame)),
+            };
         }
+        Err(delivered(UhfError::Communication(format!(
+            "No reply to the command among {} replies",
+            MAX_STALE_REPLIES + 1
+        ))))
     }
 
-    /// Get the raw EPC list (first 47 hex characters of valid responses)
+    /// Run one inventory round, polling until the reader reports no more tags
     ///
     /// # Returns
-    /// Vector of hex strings representing detected tags
+    /// Every tag reply of the round in the order received; a tag may appear more than once
     ///
     /// # Errors
     /// Returns an error if USB communication fails, times out, or the device response is invalid.
-    pub fn get_epc_list_raw(&self, device: &impl UsbIo) -> Result<Vec<String>> {
-        let mut responses = Vec::new();
-
-        // Start inventory command
-        let command = vec![3, 2, 0x55, 0x80];
-        self.send_command(device, &command)?;
+    pub fn inventory(&self, device: &impl UsbIo) -> Result<Vec<TagReport>> {
+        let mut tags = Vec::new();
 
-        // Read initial response
-        let _ = self.read_response(device, Duration::from_secs(2))?;
-
-        // Get up to 10 tag responses
-        for _ in 0..10 {
-            let followup = vec![3, 2, 0x55, 0x91];
-            if self.send_command(device, &followup).is_err() {
-                break;
-            }
+        match self.transact(device, &Command::InventoryStart)? {
+            (Response::InventoryStarted { .. }, _) => {}
+            (_, frame) => return Err(UhfError::InvalidResponse(frame)),
+        }
 
-            match self.read_response(device, Duration::from_secs(2)) {
-                Ok(resp) if resp.len() >= 5 => {
-                    let hex_str: String = hex::encode_upper(&resp);
-                    if !hex_str.starts_with("0402559100") { // Skip invalid responses
-                        responses.push(hex_str);
-                    }
+        // Bound the round so a reader that never reports the end cannot hang the caller
+        for _ in 0..MAX_INVENTORY_POLLS {
+            match self.transact(device, &Command::InventoryNext)? {
+                (Response::InventoryTag(tag), _) => {
+                    debug!(epc = %hex::encode_upper(&tag.epc), rssi = tag.rssi, "inventory tag");
+                    tags.push(tag);
                 }
-                _ => break,
+                (Response::InventoryEmpty, _) => return Ok(tags),
+                (_, frame) => return Err(UhfError::InvalidResponse(frame)),
             }
         }
 
-        Ok(responses)
+        Err(UhfError::Communication(format!(
+            "Inventory round did not end after {MAX_INVENTORY_POLLS} polls"
+        )))
     }
 
     /// Read data from the UHF tag
     ///
     /// # Arguments
     /// * `memory_bank` - Memory bank type
-    /// * `address` - Start address
-    /// * `r_len` - Length of data to read (will get `r_len`*4 bytes)
+    /// * `address` - Start word address
+    /// * `r_len` - Number of words to read, at most 15 (will get `r_len`*2 bytes)
     ///
     /// # Returns
-    /// Vector containing the read data
+    /// Vector containing the read words, two bytes per word
     ///
     /// # Errors
-    /// Returns an error if USB communication fails, times out, or the device response is invalid.
+    /// Returns an error if parameters are invalid, USB communication fails, times out, the reader
+    /// reports a failure status (see `UhfError::from_status`), or the device response is invalid.
     pub fn read(
         &self,
         device: &impl UsbIo,
@@ -185,45 +480,20 @@ impl Interface {
         address: u8,
         r_len: u8,
     ) -> Result<Vec<u8>> {
-        let mut command = vec![0u8; 256];
-        command[1] = 2;
-        command[2] = b'A';
-        command[3] = b'R';
-        command[4] = memory_bank.to_ascii();
-        command[5] = b',';
-
-        let command_length = if address >= 0x10 {
-            command[6] = Self::hex_value(address >> 4);
-            command[7] = Self::hex_value(address & 0xF);
-            command[8] = b',';
-            command[9] = if r_len >= 10 { r_len + 55 } else { r_len + 48 };
-            command[0] = 9;
-            10
-        } else {
-            command[6] = Self::hex_value(address);
-            command[7] = b',';
-            command[8] = if r_len >= 10 { r_len + 55 } else { r_len + 48 };
-            command[0] = 8;
-            9
+        let command = Command::Read {
+            bank: memory_bank,
+            address,
+            words: r_len,
         };
 
-        self.send_command(device, &command[..command_length])?;
-        let response = self.read_response(device, Duration::from_secs(2))?;
-
-        if response.len() >= 6 && response[4] == b'R' {
-            let data_length = (r_len as usize) * 4;
-            let data = response[5..].to_vec();
-
-            if self.debug_mode {
-                println!(
-                    "Read data: {}",
-                    hex::encode(&data[..data_length.min(data.len())])
-                );
+        match self.transact(device, &command)? {
+            (Response::Read { mut data }, _) if data.len() >= usize::from(r_len) * 2 => {
+                if r_len > 0 {
+                    data.truncate(usize::from(r_len) * 2);
+                }
+                Ok(data)
             }
-
-            Ok(data)
-        } else {
-            Err(UhfError::InvalidResponse)
+            (_, frame) => Err(UhfError::InvalidResponse(frame)),
         }
     }
 
@@ -231,11 +501,12 @@ impl Interface {
     ///
     /// # Arguments
     /// * `memory_bank` - Memory bank type
-    /// * `address` - Start address
-    /// * `data` - Data to write (length must be multiple of 4)
+    /// * `address` - Start word address
+    /// * `data` - Data to write, two bytes per word and at most 15 words
     ///
     /// # Errors
-    /// Returns an error if parameters are invalid, USB communication fails, times out, or the device response is invalid.
+    /// Returns an error if parameters are invalid, USB communication fails, times out, the reader
+    /// reports a failure status (see `UhfError::from_status`), or the device response is invalid.
     pub fn write(
         &self,
         device: &impl UsbIo,
@@ -243,51 +514,15 @@ impl Interface {
         address: u8,
         data: &[u8],
     ) -> Result<()> {
-        if data.is_empty() || !data.len().is_multiple_of(4) {
-            return Err(UhfError::InvalidParameter(
-                "Data length must be a non-zero multiple of 4".to_string(),
-            ));
-        }
-
-        let w_len = u8::try_from(data.len() / 4)
-            .map_err(|_| UhfError::InvalidParameter("Data length too large".to_string()))?;
-        let mut command = vec![0u8; 256];
-        command[1] = 2;
-        command[2] = b'A';
-        command[3] = b'W';
-        command[4] = memory_bank.to_ascii();
-        command[5] = b',';
-
-        let command_length = if address >= 0x10 {
-            command[6] = Self::hex_value(address >> 4);
-            command[7] = Self::hex_value(address & 0xF);
-            command[8] = b',';
-            command[9] = if w_len >= 10 { w_len + 55 } else { w_len + 48 };
-            command[10] = b',';
-            command[11..11 + data.len()].copy_from_slice(data);
-            11 + data.len()
-        } else {
-            command[6] = Self::hex_value(address);
-            command[7] = b',';
-            command[8] = if w_len >= 10 { w_len + 55 } else { w_len + 48 };
-            command[9] = b',';
-            command[10..10 + data.len()].copy_from_slice(data);
-            10 + data.len()
+        let command = Command::Write {
+            bank: memory_bank,
+            address,
+            data: data.to_vec(),
         };
 
-        command[0] = u8::try_from(command_length - 1)
-            .map_err(|_| UhfError::InvalidParameter("Command length too large".to_string()))?;
-
-        self.send_command(device, &command[..command_length])?;
-        let response = self.read_response(device, Duration::from_secs(2))?;
-
-        if !response.is_empty() && response[0] == 8 {
-            if self.debug_mode {
-                println!("Write data: {} - Success", hex::encode(data));
-            }
-            Ok(())
-        } else {
-            Err(UhfError::InvalidResponse)
+        match self.transact(device, &command)? {
+            (Response::Written, _) => Ok(()),
+            (_, frame) => Err(UhfError::InvalidResponse(frame)),
         }
     }
 
@@ -298,104 +533,256 @@ impl Interface {
     /// * `time_units` - Duration in units of 10ms
     ///
     /// # Errors
-    /// Returns an error if parameters are invalid, USB communication fails, times out, or the device response is invalid.
+    /// Returns an error if parameters are invalid, USB communication fails, times out, the reader
+    /// reports a failure status (see `UhfError::from_status`), or the device response is invalid.
     pub fn action(&self, device: &impl UsbIo, action: u8, time_units: u8) -> Result<()> {
-        if action == 0 || action > 15 {
-            return Err(UhfError::InvalidParameter(
-                "Invalid action value".to_string(),
-            ));
-        }
-
-        let command = vec![4, 2, 145, action, time_units];
-        self.send_command(device, &command)?;
-
-        let response = self.read_response(device, Duration::from_secs(2))?;
-
-        if response.len() >= 4
-            && response[0] == 3
-            && response[1] == 2
-            && response[2] == 145
-            && response[3] == 0
-        {
-            if self.debug_mode {
-                println!(
-                    "Action (beep/led) executed: action={}, time={}ms",
-                    action,
-                    time_units * 10
-                );
-            }
-            Ok(())
-        } else {
-            Err(UhfError::InvalidResponse)
+        match self.transact(device, &Command::Action { action, time_units })? {
+            (Response::Action { .. }, _) => Ok(()),
+            (_, frame) => Err(UhfError::InvalidResponse(frame)),
         }
     }
 
     /// Set access password for tag
     ///
     /// # Arguments
-    /// * `password` - 8-byte password (two 4-byte integers)
+    /// * `password` - 32-bit access password
     ///
     /// # Errors
-    /// Returns an error if USB communication fails, times out, or the device response is invalid.
-    pub fn set_access_password(&self, device: &impl UsbIo, password: &[u8; 8]) -> Result<()> {
-        let mut command = vec![0u8; 12];
-        command[0] = 11; // Length
-        command[1] = 2; // Protocol marker
-        command[2] = b'A';
-        command[3] = b'P';
-        command[4..8].copy_from_slice(&password[..4]);
-        command[8..12].copy_from_slice(&password[4..]);
-
-        self.send_command(device, &command)?;
-        let response = self.read_response(device, Duration::from_secs(2))?;
-
-        if response.len() >= 5
-            && response[0] == 4
-            && response[1] == 2
-            && response[2] == 65
-            && response[3] == 0
-            && response[4] == 80
-        {
-            if self.debug_mode {
-                println!("Set Access Password: {} - Success", hex::encode(password));
-            }
-            Ok(())
-        } else {
-            Err(UhfError::InvalidResponse)
+    /// Returns an error if USB communication fails, times out, the reader reports a failure
+    /// status (see `UhfError::from_status`), or the device response is invalid.
+    pub fn set_access_password(&self, device: &impl UsbIo, password: u32) -> Result<()> {
+        match self.transact(device, &Command::SetAccessPassword { password })? {
+            (Response::PasswordSet, _) => Ok(()),
+            (_, frame) => Err(UhfError::InvalidResponse(frame)),
         }
     }
 
     /// Lock memory bank
     ///
     /// # Arguments
-    /// * `lock_setting` - 6-byte ASCII lock setting
+    /// * `payload` - Gen2 lock payload naming the areas to change and their new lock actions
     ///
     /// # Errors
-    /// Returns an error if USB communication fails, times out, or the device response is invalid.
-    pub fn lock_memory(&self, device: &impl UsbIo, lock_setting: &[u8; 6]) -> Result<()> {
-        let mut command = vec![0u8; 11];
-        command[0] = 10; // Length
-        command[1] = 2; // Protocol marker
-        command[2] = b'A';
-        command[3] = b'L';
-        command[4..10].copy_from_slice(lock_setting);
-
-        self.send_command(device, &command)?;
-        let response = self.read_response(device, Duration::from_secs(2))?;
-
-        if response.len() >= 8
-            && response[0] == 8
-            && response[4] == 76
-            && response[6] == 79
-            && response[7] == 75
-        {
-            if self.debug_mode {
-                println!("Lock Memory: {} - Success", hex::encode(lock_setting));
-            }
-            Ok(())
-        } else {
-            Err(UhfError::InvalidResponse)
+    /// Returns an error if parameters are invalid, USB communication fails, times out, the reader
+    /// reports a failure status (see `UhfError::from_status`), or the device response is invalid.
+    pub fn lock_memory(&self, device: &impl UsbIo, payload: LockPayload) -> Result<()> {
+        match self.transact(device, &Command::Lock { payload })? {
+            (Response::Locked, _) => Ok(()),
+            (_, frame) => Err(UhfError::InvalidResponse(frame)),
         }
     }
 
+    /// Move the tag to the Secured state so password-locked banks can be accessed
+    ///
+    /// The tag stays Secured until the next inventory round singulates it again. The `AA`
+    /// layout is unverified, so the command is only sent with the `unverified-commands`
+    /// feature.
+    ///
+    /// # Arguments
+    /// * `password` - 32-bit access password stored in Reserved words 2-3
+    ///
+    /// # Errors
+    /// Returns an error if USB communication fails, times out, the reader reports a failure
+    /// status (see `UhfError::from_status`), or the device response is invalid, and
+    /// `UhfError::Unverified` without the `unverified-commands` feature.
+    pub fn authenticate(&self, device: &impl UsbIo, password: u32) -> Result<()> {
+        match self.transact(device, &Command::Authenticate { password })? {
+            (Response::Authenticated, _) => Ok(()),
+            (_, frame) => Err(UhfError::InvalidResponse(frame)),
+        }
+    }
+
+    /// Permanently kill the tag
+    ///
+    /// The `AK` layout is unverified, so the command is only sent with the
+    /// `unverified-commands` feature.
+    ///
+    /// # Arguments
+    /// * `kill_password` - 32-bit kill password stored in Reserved words 0-1
+    ///
+    /// # Errors
+    /// Returns an error if USB communication fails, times out, the reader reports a failure
+    /// status (see `UhfError::from_status`), or the device response is invalid, and
+    /// `UhfError::Unverified` without the `unverified-commands` feature.
+    pub fn kill(&self, device: &impl UsbIo, kill_password: u32) -> Result<()> {
+        match self.transact(
+            device,
+            &Command::Kill {
+                password: kill_password,
+            },
+        )? {
+            (Response::Killed, _) => Ok(()),
+            (_, frame) => Err(UhfError::InvalidResponse(frame)),
+        }
+    }
+
+    /// Make only the tags matching `filter` answer later read, write, lock and password commands
+    ///
+    /// The filter stays in effect until it is replaced or cleared with [`Self::clear_select`].
+    /// Inventory rounds are not affected. The `AS` layout is unverified, so the command is only
+    /// sent with the `unverified-commands` feature.
+    ///
+    /// # Errors
+    /// Returns an error if the filter does not fit a command, USB communication fails, times out,
+    /// the reader reports a failure status (see `UhfError::from_status`), or the device response
+    /// is invalid, and `UhfError::Unverified` without the `unverified-commands` feature.
+    pub fn select(&self, device: &impl UsbIo, filter: &TagFilter) -> Result<()> {
+        match self.transact(device, &Command::Select(filter.clone()))? {
+            (Response::Selected, _) => Ok(()),
+            (_, frame) => Err(UhfError::InvalidResponse(frame)),
+        }
+    }
+
+    /// Remove the select filter so the first tag in the field answers access commands again
+    ///
+    /// # Errors
+    /// Returns an error if USB communication fails, times out, the reader reports a failure
+    /// status (see `UhfError::from_status`), or the device response is invalid, and
+    /// `UhfError::Unverified` without the `unverified-commands` feature.
+    pub fn clear_select(&self, device: &impl UsbIo) -> Result<()> {
+        match self.transact(device, &Command::ClearSelect)? {
+            (Response::Selected, _) => Ok(()),
+            (_, frame) => Err(UhfError::InvalidResponse(frame)),
+        }
+    }
+
+    /// Read the reader's RF and Gen2 settings
+    ///
+    /// The `0x56` layout is unverified, so the command is only sent with the
+    /// `unverified-commands` feature.
+    ///
+    /// # Errors
+    /// Returns an error if USB communication fails, times out, the reader reports a failure
+    /// status (see `UhfError::from_status`), or the device response is invalid, and
+    /// `UhfError::Unverified` without the `unverified-commands` feature.
+    pub fn get_config(&self, device: &impl UsbIo) -> Result<ReaderSettings> {
+        match self.transact(device, &Command::GetConfig)? {
+            (Response::Config(settings), _) => Ok(settings),
+            (_, frame) => Err(UhfError::InvalidResponse(frame)),
+        }
+    }
+
+    /// Apply RF and Gen2 settings, optionally storing them in the reader
+    ///
+    /// Returns whether the reader stored the settings; readers without non-volatile settings
+    /// storage apply them until the next power cycle only. The `0x56` layout is unverified, so
+    /// the command is only sent with the `unverified-commands` feature.
+    ///
+    /// # Errors
+    /// Returns an error if a setting is out of range, USB communication fails, times out, the
+    /// reader reports a failure status (see `UhfError::from_status`), or the device response is
+    /// invalid, and `UhfError::Unverified` without the `unverified-commands` feature.
+    pub fn set_config(
+        &self,
+        device: &impl UsbIo,
+        settings: ReaderSettings,
+        persist: bool,
+    ) -> Result<bool> {
+        let command = Command::SetConfig { settings, persist };
+        match self.transact(device, &command)? {
+            (Response::ConfigSet { persisted }, _) => Ok(persisted),
+            (_, frame) => Err(UhfError::InvalidResponse(frame)),
+        }
+    }
+
+    /// Query the reader's firmware version, hardware revision and capabilities
+    ///
+    /// The `0x57` layout and the statuses it answers with are unverified, so the command is
+    /// only sent with the `unverified-commands` feature.
+    ///
+    /// # Errors
+    /// Returns an error if USB communication fails, times out, the reader reports a failure
+    /// status (firmware without the identify command answers `UhfError::CommandRejected`), or
+    /// the device response is invalid, and `UhfError::Unverified` without the
+    /// `unverified-commands` feature.
+    pub fn identify(&self, device: &impl UsbIo) -> Result<ReaderIdentity> {
+        match self.transact(device, &Command::Identify)? {
+            (Response::Identity(identity), _) => Ok(identity),
+            (_, frame) => Err(UhfError::InvalidResponse(frame)),
+        }
+    }
+
+    /// Send bytes entered by hand and collect whatever the reader sends back
+    ///
+    /// Stale input is drained first and the bytes go out in whole reports, as for any
+    /// command, but they are neither checked nor retried, and the reply is not decoded. A
+    /// reply that stops arriving or does not reassemble is returned as far as it got; no
+    /// reports at all means the reader did not answer within the timeout.
+    ///
+    /// # Errors
+    /// Returns `UhfError::InvalidParameter` if framed bytes do not fit a frame or unframed
+    /// bytes do not fit a report, or an error if USB communication fails.
+    pub fn raw_transaction(
+        &self,
+        device: &impl UsbIo,
+        data: &[u8],
+        options: &RawOptions,
+    ) -> Result<RawReply> {
+        let timeout = options.timeout.unwrap_or(self.policy.default_timeout());
+        // The bytes may hold a password, so only their length and opcode are logged
+        let span = debug_span!(
+            "raw",
+            len = data.len(),
+            opcode = field::Empty,
+            framed = options.framed,
+            reports = field::Empty,
+        );
+        let opcode = if options.framed {
+            data.first()
+        } else {
+            data.get(1)
+        };
+        if let Some(opcode) = opcode {
+            span.record("opcode", field::display(format_args!("{opcode:02X}")));
+        }
+        let _entered = span.enter();
+
+        let reports: Vec<[u8; REPORT_SIZE]> = if options.framed {
+            let len = u8::try_from(data.len()).map_err(|_| {
+                UhfError::InvalidParameter(format!("Framed data must be at most {} bytes", u8::MAX))
+            })?;
+            let frame = [&[len], data].concat();
+            Framer::new(Direction::HostToReader, &frame)?.collect()
+        } else {
+            let mut report = [0u8; REPORT_SIZE];
+            report
+                .get_mut(..data.len())
+                .ok_or_else(|| {
+                    UhfError::InvalidParameter(format!(
+                        "Unframed data must fit one {REPORT_SIZE}-byte report"
+                    ))
+                })?
+                .copy_from_slice(data);
+            vec![report]
+        };
+        Self::drain_stale_reports(device)?;
+        for report in &reports {
+            Self::send_report(device, report, timeout)?;
+        }
+
+        let mut reply = RawReply::default();
+        let mut deframer = Deframer::new(Direction::ReaderToHost);
+        let start_time = Instant::now();
+        while reply.frame.is_none() && start_time.elapsed() <= timeout {
+            let mut report = [0u8; REPORT_SIZE];
+            let len = match Self::read_report(device, &mut report, timeout) {
+                Ok(len) => len,
+                Err(UhfError::Timeout) => break,
+                Err(e) => return Err(e),
+            };
+            reply.reports.push(report[..len].to_vec());
+            match deframer.push(&report[..len]) {
+                Ok(frame) => reply.frame = frame,
+                Err(e) => {
+                    debug!(error = %e, "reply does not reassemble");
+                    break;
+                }
+            }
+            if !options.collect_continuations {
+                break;
+            }
+        }
+        span.record("reports", reply.reports.len());
+        Ok(reply)
+    }
 }
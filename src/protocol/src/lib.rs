@@ -1,5 +1,12 @@
 //! Protocol crate exposing low-level RFID interface and types.
 //! This module provides USB protocol types and device interface helpers.
 
+pub mod capture;
+pub mod codec;
+pub mod dissect;
+#[cfg(any(test, feature = "emulator"))]
+pub mod emulator;
+pub mod epc_bank;
+pub mod framing;
 pub mod interface;
 pub mod types;
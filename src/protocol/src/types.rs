@@ -1,7 +1,10 @@
 //! Constants and types for UHF RFID communication protocol.
-//! Contains memory bank enums, device actions, inventory results, and errors.
+//! Contains memory bank enums, device actions, reader settings and identity, inventory results,
+//! and errors.
+use crate::codec;
 use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
 
@@ -46,6 +49,246 @@ pub enum LockableMemoryBank {
     KillPassword,
 }
 
+impl LockAction {
+    /// Gen2 action bits of the action: password-lock bit, then permalock bit
+    #[must_use]
+    pub const fn bits(self) -> u16 {
+        match self {
+            LockAction::Writeable => 0b00,
+            LockAction::PermanentlyWriteable => 0b01,
+            LockAction::SecureWriteable => 0b10,
+            LockAction::NotWriteable => 0b11,
+        }
+    }
+
+    /// Action described by the two low bits of `bits`
+    #[must_use]
+    pub const fn from_bits(bits: u16) -> Self {
+        match bits & 0b11 {
+            0b00 => LockAction::Writeable,
+            0b01 => LockAction::PermanentlyWriteable,
+            0b10 => LockAction::SecureWriteable,
+            _ => LockAction::NotWriteable,
+        }
+    }
+}
+
+impl PasswordLockAction {
+    /// Gen2 action bits of the action: password-lock bit, then permalock bit
+    #[must_use]
+    pub const fn bits(self) -> u16 {
+        match self {
+            PasswordLockAction::ReadWriteable => 0b00,
+            PasswordLockAction::PermanentlyReadWriteable => 0b01,
+            PasswordLockAction::SecureReadWriteable => 0b10,
+            PasswordLockAction::NotReadWriteable => 0b11,
+        }
+    }
+
+    /// Action described by the two low bits of `bits`
+    #[must_use]
+    pub const fn from_bits(bits: u16) -> Self {
+        match bits & 0b11 {
+            0b00 => PasswordLockAction::ReadWriteable,
+            0b01 => PasswordLockAction::PermanentlyReadWriteable,
+            0b10 => PasswordLockAction::SecureReadWriteable,
+            _ => PasswordLockAction::NotReadWriteable,
+        }
+    }
+}
+
+impl LockableMemoryBank {
+    /// All lockable areas in lock payload order, most significant first
+    pub const ALL: [LockableMemoryBank; 5] = [
+        LockableMemoryBank::KillPassword,
+        LockableMemoryBank::AccessPassword,
+        LockableMemoryBank::Epc,
+        LockableMemoryBank::Tid,
+        LockableMemoryBank::User,
+    ];
+
+    /// Offset of the area's permalock bit in the action half of a lock payload;
+    /// the password-lock bit sits directly above it
+    #[must_use]
+    pub const fn lock_shift(self) -> u16 {
+        match self {
+            LockableMemoryBank::KillPassword => 8,
+            LockableMemoryBank::AccessPassword => 6,
+            LockableMemoryBank::Epc => 4,
+            LockableMemoryBank::Tid => 2,
+            LockableMemoryBank::User => 0,
+        }
+    }
+
+    /// Whether the area is one of the passwords in Reserved memory
+    #[must_use]
+    pub const fn is_password(self) -> bool {
+        matches!(
+            self,
+            LockableMemoryBank::KillPassword | LockableMemoryBank::AccessPassword
+        )
+    }
+}
+
+/// 20-bit Gen2 lock payload: mask bits 19..10 choose which action bits 9..0 are applied
+///
+/// Every lockable area owns a pair of bits in each half, from the kill password in the
+/// top pair down to User memory in the bottom one. Areas left out of the mask keep their
+/// current lock state, so one payload can lock several banks at once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct LockPayload {
+    mask: u16,
+    action: u16,
+}
+
+impl LockPayload {
+    /// Payload that changes nothing
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { mask: 0, action: 0 }
+    }
+
+    /// Apply `action` to `bank`, replacing any action set for it earlier
+    #[must_use]
+    pub const fn with_action(self, bank: LockableMemoryBank, action: LockAction) -> Self {
+        self.with_bits(bank, action.bits())
+    }
+
+    /// Apply a read/write lock `action` to a password area
+    ///
+    /// # Errors
+    /// Returns `UhfError::InvalidParameter` if `bank` is not the kill or access password.
+    pub fn with_password_action(
+        self,
+        bank: LockableMemoryBank,
+        action: PasswordLockAction,
+    ) -> Result<Self, UhfError> {
+        if !bank.is_password() {
+            return Err(UhfError::InvalidParameter(format!(
+                "{bank:?} is not a password area"
+            )));
+        }
+        Ok(self.with_bits(bank, action.bits()))
+    }
+
+    const fn with_bits(self, bank: LockableMemoryBank, bits: u16) -> Self {
+        let shift = bank.lock_shift();
+        Self {
+            mask: self.mask | (0b11 << shift),
+            action: (self.action & !(0b11 << shift)) | (bits << shift),
+        }
+    }
+
+    /// Build a payload from its 20-bit wire value
+    ///
+    /// # Errors
+    /// Returns `UhfError::InvalidParameter` if `bits` does not fit in 20 bits.
+    pub fn from_bits(bits: u32) -> Result<Self, UhfError> {
+        if bits >= 1 << 20 {
+            return Err(UhfError::InvalidParameter(
+                "Lock payload must fit in 20 bits".to_string(),
+            ));
+        }
+        Ok(Self {
+            mask: u16::try_from(bits >> 10).unwrap_or(0),
+            action: u16::try_from(bits & 0x3FF).unwrap_or(0),
+        })
+    }
+
+    /// 20-bit wire value, mask bits above action bits
+    #[must_use]
+    pub const fn bits(self) -> u32 {
+        ((self.mask as u32) << 10) | self.action as u32
+    }
+
+    /// Mask half of the payload (10 bits)
+    #[must_use]
+    pub const fn mask(self) -> u16 {
+        self.mask
+    }
+
+    /// Action half of the payload (10 bits); only bits selected by the mask are applied
+    #[must_use]
+    pub const fn action_bits(self) -> u16 {
+        self.action & self.mask
+    }
+
+    /// Whether the payload leaves every area unchanged
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.mask == 0
+    }
+
+    /// Lock action applied to `bank`, or `None` if the mask does not cover both of its bits
+    #[must_use]
+    pub const fn action(self, bank: LockableMemoryBank) -> Option<LockAction> {
+        match self.area_bits(bank) {
+            Some(bits) => Some(LockAction::from_bits(bits)),
+            None => None,
+        }
+    }
+
+    /// Read/write lock action applied to a password area, or `None` if `bank` is not a
+    /// password or the mask does not cover both of its bits
+    #[must_use]
+    pub const fn password_action(self, bank: LockableMemoryBank) -> Option<PasswordLockAction> {
+        match self.area_bits(bank) {
+            Some(bits) if bank.is_password() => Some(PasswordLockAction::from_bits(bits)),
+            _ => None,
+        }
+    }
+
+    const fn area_bits(self, bank: LockableMemoryBank) -> Option<u16> {
+        let shift = bank.lock_shift();
+        if (self.mask >> shift) & 0b11 == 0b11 {
+            Some((self.action >> shift) & 0b11)
+        } else {
+            None
+        }
+    }
+}
+
+impl From<LockPayload> for u32 {
+    fn from(payload: LockPayload) -> Self {
+        payload.bits()
+    }
+}
+
+impl TryFrom<u32> for LockPayload {
+    type Error = UhfError;
+
+    fn try_from(bits: u32) -> Result<Self, Self::Error> {
+        Self::from_bits(bits)
+    }
+}
+
+impl fmt::Display for LockPayload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no change");
+        }
+        let mut separator = "";
+        for bank in LockableMemoryBank::ALL {
+            let shift = bank.lock_shift();
+            let mask = (self.mask >> shift) & 0b11;
+            if mask == 0 {
+                continue;
+            }
+            write!(f, "{separator}{bank:?}: ")?;
+            if let Some(action) = self.password_action(bank) {
+                write!(f, "{action:?}")?;
+            } else if let Some(action) = self.action(bank) {
+                write!(f, "{action:?}")?;
+            } else {
+                let action = (self.action >> shift) & mask;
+                write!(f, "mask {mask:02b}, action {action:02b}")?;
+            }
+            separator = ", ";
+        }
+        Ok(())
+    }
+}
+
 /// Memory Banks enum
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MemoryBank {
@@ -96,6 +339,14 @@ impl MemoryBank {
 }
 
 impl DeviceAction {
+    /// All actions in bit order
+    pub const ALL: [DeviceAction; 4] = [
+        DeviceAction::Beep,
+        DeviceAction::RedLed,
+        DeviceAction::GreenLed,
+        DeviceAction::YellowLed,
+    ];
+
     /// Combine multiple device actions into a single bitmask.
     #[must_use]
     pub fn combine(actions: &[DeviceAction]) -> u8 {
@@ -116,17 +367,554 @@ pub enum DeviceAction {
     YellowLed = 0x08,
 }
 
+/// Tag reported by a single inventory poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagReport {
+    /// Protocol Control word; bits 15..11 hold the EPC length in words
+    pub pc: u16,
+    /// Electronic Product Code bytes
+    pub epc: Vec<u8>,
+    /// CRC-16 backscattered with the EPC
+    pub crc: u16,
+    /// Received signal strength in dBm, if the reader reports it
+    pub rssi: Option<i8>,
+    /// Antenna port the tag was seen on, if the reader reports it (requires `rssi`)
+    pub antenna: Option<u8>,
+}
+
+/// Select filter singulating the tags whose memory matches a bit pattern
+///
+/// `mask` holds `bit_length` bits, left-aligned; the unused low bits of its last byte are
+/// ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagFilter {
+    /// Memory bank the pattern is compared against (not `Reserved`)
+    pub bank: MemoryBank,
+    /// Bit address in the bank where the comparison starts
+    pub bit_pointer: u16,
+    /// Number of bits to compare
+    pub bit_length: u8,
+    /// Pattern to compare, most significant bit first
+    pub mask: Vec<u8>,
+}
+
+impl TagFilter {
+    /// Bit address of the EPC in the EPC bank, after the `StoredCRC` and PC words
+    pub const EPC_BIT_POINTER: u16 = 0x20;
+
+    /// Filter matching a tag by its full EPC
+    #[must_use]
+    pub fn epc(epc: &[u8]) -> Self {
+        Self {
+            bank: MemoryBank::Epc,
+            bit_pointer: Self::EPC_BIT_POINTER,
+            bit_length: u8::try_from(epc.len() * 8).unwrap_or(u8::MAX),
+            mask: epc.to_vec(),
+        }
+    }
+
+    /// Whether `memory`, the contents of the filter's bank, matches the pattern
+    ///
+    /// Memory that ends before the pattern does not match.
+    #[must_use]
+    pub fn matches(&self, memory: &[u8]) -> bool {
+        let bit = |bytes: &[u8], index: usize| {
+            bytes.get(index / 8).map(|byte| byte >> (7 - index % 8) & 1)
+        };
+        let start = usize::from(self.bit_pointer);
+        (0..usize::from(self.bit_length))
+            .all(|i| bit(&self.mask, i).is_some() && bit(memory, start + i) == bit(&self.mask, i))
+    }
+}
+
 /// Inventory Read results with the read count
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct InventoryResult {
-    /// Electronic Product Code of the tag
+    /// Electronic Product Code of the tag as upper-case hex
     pub epc: String,
+    /// Protocol Control word
+    pub pc: u16,
+    /// CRC-16 backscattered with the EPC
+    pub crc: u16,
+    /// Strongest signal strength seen for the tag in dBm
+    pub rssi: Option<i8>,
+    /// Antenna port of the strongest read
+    pub antenna: Option<u8>,
     /// Number of times the tag was read
-    pub read_count: u8,
+    pub read_count: u32,
+}
+
+impl From<TagReport> for InventoryResult {
+    fn from(tag: TagReport) -> Self {
+        Self {
+            epc: hex::encode_upper(&tag.epc),
+            pc: tag.pc,
+            crc: tag.crc,
+            rssi: tag.rssi,
+            antenna: tag.antenna,
+            read_count: 1,
+        }
+    }
+}
+
+impl InventoryResult {
+    /// Merge tag reports with the same EPC, counting reads and keeping the strongest signal
+    ///
+    /// Results keep the order in which each EPC was first seen.
+    #[must_use]
+    pub fn collect(reports: impl IntoIterator<Item = TagReport>) -> Vec<Self> {
+        let mut results: Vec<Self> = Vec::new();
+        for report in reports {
+            let report = Self::from(report);
+            match results.iter_mut().find(|seen| seen.epc == report.epc) {
+                Some(seen) => {
+                    seen.read_count = seen.read_count.saturating_add(1);
+                    if report.rssi > seen.rssi {
+                        seen.rssi = report.rssi;
+                        seen.antenna = report.antenna;
+                    }
+                }
+                None => results.push(report),
+            }
+        }
+        results
+    }
 }
 
 impl fmt::Display for InventoryResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{} (Read Count: {})", self.epc, self.read_count)
+        write!(f, "{} (Read Count: {}", self.epc, self.read_count)?;
+        if let Some(rssi) = self.rssi {
+            write!(f, ", RSSI: {rssi} dBm")?;
+        }
+        if let Some(antenna) = self.antenna {
+            write!(f, ", Antenna: {antenna}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Regulatory region, selecting the reader's frequency channel plan
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Region {
+    /// FCC Part 15: 50 channels of 500 kHz from 902.75 MHz
+    #[default]
+    Fcc = 1,
+    /// ETSI EN 302 208: 4 channels of 600 kHz from 865.7 MHz
+    Etsi = 2,
+    /// China: 16 channels of 250 kHz from 920.125 MHz
+    China = 3,
+    /// Japan: 4 channels of 1.2 MHz from 916.8 MHz
+    Japan = 4,
+}
+
+impl Region {
+    /// All regions in code order
+    pub const ALL: [Region; 4] = [Region::Fcc, Region::Etsi, Region::China, Region::Japan];
+
+    /// Region with the given wire code
+    #[must_use]
+    pub fn from_code(code: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|region| *region as u8 == code)
+    }
+
+    /// Short name of the region
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Region::Fcc => "FCC",
+            Region::Etsi => "ETSI",
+            Region::China => "China",
+            Region::Japan => "Japan",
+        }
+    }
+
+    /// Regions whose bits (`1 << code`) are set in `mask`
+    #[must_use]
+    pub fn from_mask(mask: u8) -> Vec<Self> {
+        Self::ALL
+            .into_iter()
+            .filter(|region| mask & (1 << *region as u8) != 0)
+            .collect()
+    }
+
+    /// Region mask with the bits of `regions` set
+    #[must_use]
+    pub fn mask(regions: &[Self]) -> u8 {
+        regions
+            .iter()
+            .fold(0, |mask, region| mask | (1 << *region as u8))
+    }
+
+    /// Number of channels in the region's channel plan
+    #[must_use]
+    pub const fn channel_count(self) -> u8 {
+        match self {
+            Region::Fcc => 50,
+            Region::Etsi | Region::Japan => 4,
+            Region::China => 16,
+        }
+    }
+
+    /// Centre frequency of `channel` in kHz, or `None` past the end of the channel plan
+    #[must_use]
+    pub fn channel_khz(self, channel: u8) -> Option<u32> {
+        let (first, spacing) = match self {
+            Region::Fcc => (902_750, 500),
+            Region::Etsi => (865_700, 600),
+            Region::China => (920_125, 250),
+            Region::Japan => (916_800, 1_200),
+        };
+        (channel < self.channel_count()).then(|| first + spacing * u32::from(channel))
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let band = match self {
+            Region::Fcc => "902-928",
+            Region::Etsi => "865-868",
+            Region::China => "920-925",
+            Region::Japan => "916-921",
+        };
+        write!(f, "{} ({band} MHz)", self.label())
+    }
+}
+
+/// Gen2 inventory session; tags keep their inventoried flag longer in higher sessions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Session {
+    /// Flag resets as soon as the tag loses power
+    S0 = 0,
+    /// Flag resets after 0.5 to 5 seconds
+    #[default]
+    S1 = 1,
+    /// Flag persists for at least 2 seconds without power; suits dense reader sites
+    S2 = 2,
+    /// Like S2, on a flag separate from it
+    S3 = 3,
+}
+
+impl Session {
+    /// All sessions in code order
+    pub const ALL: [Session; 4] = [Session::S0, Session::S1, Session::S2, Session::S3];
+
+    /// Session with the given wire code
+    #[must_use]
+    pub fn from_code(code: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|session| *session as u8 == code)
+    }
+}
+
+/// Inventoried flag value the reader asks tags for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum InventoryTarget {
+    /// Only tags with the flag in state A answer, then move to B
+    #[default]
+    A = 0,
+    /// Only tags with the flag in state B answer, then move to A
+    B = 1,
+    /// Alternate between A and B every round
+    AB = 2,
+}
+
+impl InventoryTarget {
+    /// All targets in code order
+    pub const ALL: [InventoryTarget; 3] =
+        [InventoryTarget::A, InventoryTarget::B, InventoryTarget::AB];
+
+    /// Target with the given wire code
+    #[must_use]
+    pub fn from_code(code: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|target| *target as u8 == code)
+    }
+}
+
+/// Radio settings of the reader
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RfSettings {
+    /// Transmit power in dBm
+    pub tx_power_dbm: u8,
+    /// Regulatory region and its channel plan
+    pub region: Region,
+    /// Hop across the region's channels instead of staying on `channel`
+    pub hopping: bool,
+    /// Fixed channel index into the region's channel plan, used when not hopping
+    pub channel: u8,
+}
+
+impl RfSettings {
+    /// Lowest transmit power the reader accepts, in dBm
+    pub const MIN_TX_POWER_DBM: u8 = 5;
+    /// Highest transmit power the reader accepts, in dBm
+    pub const MAX_TX_POWER_DBM: u8 = 30;
+}
+
+impl Default for RfSettings {
+    fn default() -> Self {
+        Self {
+            tx_power_dbm: 26,
+            region: Region::default(),
+            hopping: true,
+            channel: 0,
+        }
+    }
+}
+
+/// Gen2 air-interface settings used for inventory rounds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Gen2Settings {
+    /// Inventory session
+    pub session: Session,
+    /// Inventoried flag value to ask for
+    pub target: InventoryTarget,
+    /// Initial Q; a round offers 2^Q slots
+    pub q: u8,
+}
+
+impl Gen2Settings {
+    /// Largest Q the Gen2 protocol allows
+    pub const MAX_Q: u8 = 15;
+}
+
+impl Default for Gen2Settings {
+    fn default() -> Self {
+        Self {
+            session: Session::default(),
+            target: InventoryTarget::default(),
+            q: 4,
+        }
+    }
+}
+
+/// Complete reader configuration
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ReaderSettings {
+    /// Radio settings
+    pub rf: RfSettings,
+    /// Gen2 air-interface settings
+    pub gen2: Gen2Settings,
+}
+
+impl ReaderSettings {
+    /// Check every value against the range the reader accepts
+    ///
+    /// # Errors
+    /// Returns `UhfError::InvalidParameter` naming the first value out of range.
+    pub fn validate(&self) -> Result<(), UhfError> {
+        let power = RfSettings::MIN_TX_POWER_DBM..=RfSettings::MAX_TX_POWER_DBM;
+        if !power.contains(&self.rf.tx_power_dbm) {
+            return Err(UhfError::InvalidParameter(format!(
+                "Transmit power must be {} to {} dBm",
+                power.start(),
+                power.end()
+            )));
+        }
+        if self.rf.channel >= self.rf.region.channel_count() {
+            return Err(UhfError::InvalidParameter(format!(
+                "Channel must be below {} in region {}",
+                self.rf.region.channel_count(),
+                self.rf.region
+            )));
+        }
+        if self.gen2.q > Gen2Settings::MAX_Q {
+            return Err(UhfError::InvalidParameter(format!(
+                "Q must be at most {}",
+                Gen2Settings::MAX_Q
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ReaderSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Transmit power: {} dBm", self.rf.tx_power_dbm)?;
+        writeln!(f, "Region: {}", self.rf.region)?;
+        if self.rf.hopping {
+            writeln!(
+                f,
+                "Channel: hopping over {} channels",
+                self.rf.region.channel_count()
+            )?;
+        } else {
+            let khz = self.rf.region.channel_khz(self.rf.channel).unwrap_or(0);
+            writeln!(
+                f,
+                "Channel: {} ({}.{:03} MHz)",
+                self.rf.channel,
+                khz / 1000,
+                khz % 1000
+            )?;
+        }
+        writeln!(f, "Session: {:?}", self.gen2.session)?;
+        writeln!(f, "Target: {:?}", self.gen2.target)?;
+        write!(f, "Q: {}", self.gen2.q)
+    }
+}
+
+/// Firmware version reported by the reader
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FirmwareVersion {
+    /// Major version
+    pub major: u8,
+    /// Minor version
+    pub minor: u8,
+    /// Patch level
+    pub patch: u8,
+}
+
+impl fmt::Display for FirmwareVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Command group a reader may or may not implement
+///
+/// The bit of each feature in the identify reply, like the rest of that reply, is inferred
+/// rather than taken from the vendor protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReaderFeature {
+    /// Inventory rounds
+    Inventory = 0,
+    /// `AR` tag memory reads
+    Read = 1,
+    /// `AW` tag memory writes
+    Write = 2,
+    /// `AL` lock
+    Lock = 3,
+    /// `AK` kill
+    Kill = 4,
+    /// `AP` access password changes
+    AccessPassword = 5,
+    /// `AA` authentication with the access password
+    Authenticate = 6,
+    /// `AS` select filters
+    Select = 7,
+    /// Reading and applying RF and Gen2 settings
+    Config = 8,
+    /// Storing settings across power cycles
+    PersistSettings = 9,
+    /// Buzzer and LED actions
+    DeviceAction = 10,
+}
+
+impl ReaderFeature {
+    /// All features in bit order
+    pub const ALL: [ReaderFeature; 11] = [
+        ReaderFeature::Inventory,
+        ReaderFeature::Read,
+        ReaderFeature::Write,
+        ReaderFeature::Lock,
+        ReaderFeature::Kill,
+        ReaderFeature::AccessPassword,
+        ReaderFeature::Authenticate,
+        ReaderFeature::Select,
+        ReaderFeature::Config,
+        ReaderFeature::PersistSettings,
+        ReaderFeature::DeviceAction,
+    ];
+
+    /// Bit of the feature in the reader's feature mask
+    #[must_use]
+    pub const fn bit(self) -> u16 {
+        1 << self as u16
+    }
+
+    /// Features whose bits are set in `mask`; unknown bits are ignored
+    #[must_use]
+    pub fn from_mask(mask: u16) -> Vec<Self> {
+        Self::ALL
+            .into_iter()
+            .filter(|feature| mask & feature.bit() != 0)
+            .collect()
+    }
+
+    /// Feature mask with the bits of `features` set
+    #[must_use]
+    pub fn mask(features: &[Self]) -> u16 {
+        features
+            .iter()
+            .fold(0, |mask, feature| mask | feature.bit())
+    }
+}
+
+impl fmt::Display for ReaderFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ReaderFeature::Inventory => "inventory",
+            ReaderFeature::Read => "read",
+            ReaderFeature::Write => "write",
+            ReaderFeature::Lock => "lock",
+            ReaderFeature::Kill => "kill",
+            ReaderFeature::AccessPassword => "access password",
+            ReaderFeature::Authenticate => "authenticate",
+            ReaderFeature::Select => "select",
+            ReaderFeature::Config => "config",
+            ReaderFeature::PersistSettings => "persistent settings",
+            ReaderFeature::DeviceAction => "device action",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Identity and capabilities the reader reports about itself
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReaderIdentity {
+    /// Firmware version
+    pub firmware: FirmwareVersion,
+    /// Hardware revision
+    pub hardware_revision: u8,
+    /// Regions whose channel plans the reader can use
+    pub regions: Vec<Region>,
+    /// Highest transmit power the hardware supports, in dBm
+    pub max_tx_power_dbm: u8,
+    /// Command groups the firmware implements
+    pub features: Vec<ReaderFeature>,
+}
+
+impl ReaderIdentity {
+    /// Whether the firmware implements `feature`
+    #[must_use]
+    pub fn supports(&self, feature: ReaderFeature) -> bool {
+        self.features.contains(&feature)
+    }
+
+    /// Whether the reader can use `region`
+    #[must_use]
+    pub fn supports_region(&self, region: Region) -> bool {
+        self.regions.contains(&region)
+    }
+}
+
+impl fmt::Display for ReaderIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |items: Vec<String>| {
+            if items.is_empty() {
+                "none".to_string()
+            } else {
+                items.join(", ")
+            }
+        };
+        writeln!(f, "Firmware: {}", self.firmware)?;
+        writeln!(f, "Hardware Revision: {}", self.hardware_revision)?;
+        writeln!(
+            f,
+            "Regions: {}",
+            list(
+                self.regions
+                    .iter()
+                    .map(|region| region.label().to_string())
+                    .collect()
+            )
+        )?;
+        writeln!(f, "Max Transmit Power: {} dBm", self.max_tx_power_dbm)?;
+        write!(
+            f,
+            "Commands: {}",
+            list(self.features.iter().map(ToString::to_string).collect())
+        )
     }
 }
 
@@ -145,6 +933,14 @@ pub enum UhfError {
     #[error("Invalid parameter: {0}")]
     InvalidParameter(String),
 
+    /// The command's frame layout is not verified against the reader, and the crate was
+    /// built without the `unverified-commands` feature
+    #[error(
+        "The {0} command is not verified against the reader; build with the \
+         `unverified-commands` feature to send it"
+    )]
+    Unverified(&'static str),
+
     /// Communication layer error (e.g., USB I/O)
     #[error("Communication error: {0}")]
     Communication(String),
@@ -153,9 +949,117 @@ pub enum UhfError {
     #[error("Timeout waiting for response")]
     Timeout,
 
-    /// Device returned an invalid or unexpected response
-    #[error("Invalid response from device")]
-    InvalidResponse,
+    /// A frame could not be split into reports or reassembled from them
+    #[error("Framing error: {0}")]
+    Fragmentation(String),
+
+    /// Device returned an invalid or unexpected response, carrying the raw frame
+    #[error("Invalid response from device: {}", describe_frame(.0))]
+    InvalidResponse(Vec<u8>),
+
+    /// No tag answered the command
+    #[error("No tag responded to the command")]
+    NoTag {
+        /// Raw reply frame
+        frame: Vec<u8>,
+    },
+
+    /// The reader could not parse the command
+    #[error("Command rejected by the reader")]
+    CommandRejected {
+        /// Raw reply frame
+        frame: Vec<u8>,
+    },
+
+    /// The command addressed memory past the end of the bank
+    #[error("Memory overrun: the address range exceeds the memory bank")]
+    MemoryOverrun {
+        /// Raw reply frame
+        frame: Vec<u8>,
+    },
+
+    /// The addressed memory is locked or permalocked
+    #[error("Memory is locked")]
+    MemoryLocked {
+        /// Raw reply frame
+        frame: Vec<u8>,
+    },
+
+    /// The tag did not accept the access password
+    #[error("Access denied: wrong access password")]
+    AccessDenied {
+        /// Raw reply frame
+        frame: Vec<u8>,
+    },
+
+    /// The reader reported a status byte without a known meaning
+    #[error("Reader reported status 0x{status:02X}")]
+    DeviceStatus {
+        /// Status byte
+        status: u8,
+        /// Raw reply frame
+        frame: Vec<u8>,
+    },
+}
+
+impl UhfError {
+    /// Map a non-zero status byte reported by the reader to its error
+    #[must_use]
+    pub fn from_status(status: u8, frame: Vec<u8>) -> Self {
+        match status {
+            codec::STATUS_NO_TAG => UhfError::NoTag { frame },
+            codec::STATUS_BAD_COMMAND => UhfError::CommandRejected { frame },
+            codec::STATUS_MEMORY_OVERRUN => UhfError::MemoryOverrun { frame },
+            codec::STATUS_MEMORY_LOCKED => UhfError::MemoryLocked { frame },
+            codec::STATUS_ACCESS_DENIED => UhfError::AccessDenied { frame },
+            status => UhfError::DeviceStatus { status, frame },
+        }
+    }
+
+    /// Status byte reported by the reader, if the error came from one
+    #[must_use]
+    pub fn status(&self) -> Option<u8> {
+        match self {
+            UhfError::NoTag { .. } => Some(codec::STATUS_NO_TAG),
+            UhfError::CommandRejected { .. } => Some(codec::STATUS_BAD_COMMAND),
+            UhfError::MemoryOverrun { .. } => Some(codec::STATUS_MEMORY_OVERRUN),
+            UhfError::MemoryLocked { .. } => Some(codec::STATUS_MEMORY_LOCKED),
+            UhfError::AccessDenied { .. } => Some(codec::STATUS_ACCESS_DENIED),
+            UhfError::DeviceStatus { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// Whether sending the command again may succeed: timeouts and communication errors, as
+    /// opposed to failures the reader or the tag reported
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, UhfError::Timeout | UhfError::Communication(_))
+    }
+
+    /// Raw reply frame the error was decoded from, if any
+    #[must_use]
+    pub fn frame(&self) -> Option<&[u8]> {
+        match self {
+            UhfError::InvalidResponse(frame)
+            | UhfError::NoTag { frame }
+            | UhfError::CommandRejected { frame }
+            | UhfError::MemoryOverrun { frame }
+            | UhfError::MemoryLocked { frame }
+            | UhfError::AccessDenied { frame }
+            | UhfError::DeviceStatus { frame, .. } => Some(frame),
+            _ => None,
+        }
+    }
+}
+
+/// Hex dump of a reply frame for error messages
+fn describe_frame(frame: &[u8]) -> String {
+    if frame.is_empty() {
+        "empty reply".to_string()
+    } else {
+        hex::encode_upper(frame)
+    }
 }
 
 /// Minimal USB I/O abstraction to decouple protocol from a concrete backend (rusb/nusb)
@@ -201,4 +1105,34 @@ pub trait UsbIo {
         data: &[u8],
         timeout: Duration,
     ) -> crate::interface::Result<usize>;
+
+    /// Reports whether the transport currently holds an open device handle
+    fn is_connected(&self) -> bool {
+        true
+    }
+}
+
+/// Shared transports, e.g. a device handed to a background thread while still inspected elsewhere
+impl<T: UsbIo + ?Sized> UsbIo for Arc<T> {
+    fn read_bulk(
+        &self,
+        endpoint: u8,
+        buf: &mut [u8],
+        timeout: Duration,
+    ) -> crate::interface::Result<usize> {
+        (**self).read_bulk(endpoint, buf, timeout)
+    }
+
+    fn write_bulk(
+        &self,
+        endpoint: u8,
+        data: &[u8],
+        timeout: Duration,
+    ) -> crate::interface::Result<usize> {
+        (**self).write_bulk(endpoint, data, timeout)
+    }
+
+    fn is_connected(&self) -> bool {
+        (**self).is_connected()
+    }
 }
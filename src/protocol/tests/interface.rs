@@ -0,0 +1,266 @@
+//! `Interface` behaviour against the reader emulator: stale and late replies, retries, tag
+//! selection and kill, and the reader settings and identity commands.
+
+use protocl::codec::{Command, CommandKind, Response};
+use protocl::emulator::{ReaderEmulator, VirtualTag};
+use protocl::framing::{Direction, Framer};
+use protocl::interface::{Interface, RetryPolicy};
+use protocl::types::{
+    MemoryBank, ReaderFeature, ReaderIdentity, ReaderSettings, Region, Session, TagFilter, UhfError,
+};
+use std::time::Duration;
+
+const EPC: [u8; 12] = [0x11; 12];
+const OTHER_EPC: [u8; 12] = [
+    0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x12,
+];
+
+/// Reader with a single tag in the field
+fn reader() -> ReaderEmulator {
+    ReaderEmulator::with_tags(vec![VirtualTag::new(&EPC)])
+}
+
+/// Reader with the `EPC` tag, which answers first, and the `OTHER_EPC` tag
+fn two_tags() -> ReaderEmulator {
+    ReaderEmulator::with_tags(vec![VirtualTag::new(&EPC), VirtualTag::new(&OTHER_EPC)])
+}
+
+/// Interface that sends every command once
+fn once() -> Interface {
+    Interface {
+        policy: RetryPolicy::no_retries(),
+    }
+}
+
+/// Interface that retries with the default count and no backoff
+fn retrying() -> Interface {
+    Interface {
+        policy: RetryPolicy::default().with_backoff(Duration::ZERO),
+    }
+}
+
+#[test]
+fn stale_reports_are_drained_before_a_command() {
+    let reader = reader();
+    let stale = Response::Read {
+        data: vec![0xEE; 30],
+    };
+    // Half of a multi-report reply is discarded as well
+    let mut reports: Vec<_> = Framer::new(Direction::ReaderToHost, &stale.encode().unwrap())
+        .unwrap()
+        .collect();
+    reports.pop();
+    reader.queue_stale_reports(reports);
+
+    assert_eq!(once().read(&reader, MemoryBank::Epc, 2, 6).unwrap(), EPC);
+    assert_eq!(reader.pending_reports(), 0);
+}
+
+#[test]
+fn late_replies_to_earlier_commands_are_skipped() {
+    let reader = reader();
+    reader.delay_replies(1);
+    let result = once().read(&reader, MemoryBank::Epc, 2, 6);
+    assert!(matches!(result, Err(UhfError::Timeout)));
+
+    // The read's reply arrives after the drain, just ahead of the write's own reply
+    once()
+        .write(&reader, MemoryBank::User, 0, &[0xAB, 0xCD])
+        .unwrap();
+    assert_eq!(reader.tag(0).unwrap().user[..2], [0xAB, 0xCD]);
+    assert_eq!(reader.pending_reports(), 0);
+
+    // A late failure is not taken for the next command's failure either
+    reader.delay_replies(1);
+    assert!(once().read(&reader, MemoryBank::Tid, 0, 15).is_err());
+    once()
+        .write(&reader, MemoryBank::User, 0, &[0x12, 0x34])
+        .unwrap();
+}
+
+#[test]
+fn undecodable_replies_are_skipped() {
+    let reader = reader();
+    // A garbled frame turns up after the drain, just ahead of the read's own reply
+    reader
+        .queue_late_reports(Framer::new(Direction::ReaderToHost, &[3, b'?', b'?', b'?']).unwrap());
+
+    assert_eq!(once().read(&reader, MemoryBank::Epc, 2, 6).unwrap(), EPC);
+    assert_eq!(reader.received_frames().len(), 1);
+    assert_eq!(reader.pending_reports(), 0);
+}
+
+#[test]
+fn retry_policy_timeouts_and_backoff() {
+    let policy = RetryPolicy::default()
+        .with_backoff(Duration::from_millis(10))
+        .with_timeout(CommandKind::Inventory, Duration::from_secs(5));
+    assert_eq!(
+        policy.timeout(CommandKind::Inventory),
+        Duration::from_secs(5)
+    );
+    assert_eq!(policy.timeout(CommandKind::Read), policy.default_timeout());
+    assert_eq!(policy.delay_before_retry(1), Duration::from_millis(10));
+    assert_eq!(policy.delay_before_retry(3), Duration::from_millis(40));
+
+    let read = Command::Read {
+        bank: MemoryBank::Epc,
+        address: 2,
+        words: 6,
+    };
+    let kill = Command::Kill { password: 1 };
+    assert!(policy.should_retry(&read, &UhfError::Timeout, true, 2));
+    assert!(!policy.should_retry(&read, &UhfError::Timeout, true, 3));
+    assert!(!policy.should_retry(
+        &read,
+        &UhfError::CommandRejected { frame: Vec::new() },
+        true,
+        1
+    ));
+    assert!(!policy.should_retry(&kill, &UhfError::Timeout, true, 1));
+    assert!(policy.should_retry(&kill, &UhfError::Communication(String::new()), false, 1));
+}
+
+#[test]
+fn lost_replies_are_retried_only_for_idempotent_commands() {
+    let reader = reader();
+    reader.lose_replies(2);
+    assert_eq!(
+        retrying().read(&reader, MemoryBank::Epc, 2, 6).unwrap(),
+        EPC
+    );
+    assert_eq!(reader.received_frames().len(), 3);
+
+    // The write took effect, but sending it again could repeat a partial write
+    reader.lose_replies(1);
+    let result = retrying().write(&reader, MemoryBank::User, 0, &[0xAB, 0xCD]);
+    assert!(matches!(result, Err(UhfError::Timeout)));
+    assert_eq!(reader.received_frames().len(), 4);
+    assert_eq!(reader.tag(0).unwrap().user[..2], [0xAB, 0xCD]);
+
+    // A write that never reached the reader is safe to send again
+    reader.fail_writes(1);
+    retrying()
+        .write(&reader, MemoryBank::User, 0, &[0x12, 0x34])
+        .unwrap();
+    assert_eq!(reader.tag(0).unwrap().user[..2], [0x12, 0x34]);
+
+    reader.lose_replies(3);
+    let result = retrying().read(&reader, MemoryBank::Epc, 2, 6);
+    assert!(matches!(result, Err(UhfError::Timeout)));
+    reader.lose_replies(1);
+    assert!(once().read(&reader, MemoryBank::Epc, 2, 6).is_err());
+}
+
+#[test]
+fn select_filter_picks_the_tag_that_answers() {
+    let reader = two_tags();
+    once().select(&reader, &TagFilter::epc(&OTHER_EPC)).unwrap();
+    once()
+        .write(&reader, MemoryBank::User, 0, &[0xCA, 0xFE])
+        .unwrap();
+    assert_eq!(&reader.tag(1).unwrap().user[..2], &[0xCA, 0xFE]);
+    assert_eq!(&reader.tag(0).unwrap().user[..2], &[0, 0]);
+
+    // Last EPC bit only: 0x...11 matches the first tag, 0x...12 does not
+    let odd = TagFilter {
+        bank: MemoryBank::Epc,
+        bit_pointer: TagFilter::EPC_BIT_POINTER + 95,
+        bit_length: 1,
+        mask: vec![0x80],
+    };
+    once().select(&reader, &odd).unwrap();
+    assert_eq!(once().read(&reader, MemoryBank::Epc, 2, 6).unwrap(), EPC);
+
+    once()
+        .select(&reader, &TagFilter::epc(&[0xFF; 12]))
+        .unwrap();
+    assert!(matches!(
+        once().read(&reader, MemoryBank::Epc, 2, 6),
+        Err(UhfError::NoTag { .. })
+    ));
+    assert_eq!(once().inventory(&reader).unwrap().len(), 2);
+
+    once().clear_select(&reader).unwrap();
+    assert_eq!(once().read(&reader, MemoryBank::Epc, 2, 6).unwrap(), EPC);
+}
+
+#[test]
+fn kill_needs_the_stored_non_zero_password() {
+    let reader = ReaderEmulator::with_tags(vec![
+        VirtualTag::new(&EPC),
+        VirtualTag::new(&OTHER_EPC).with_passwords(0xDEAD_BEEF, 0),
+    ]);
+    assert!(matches!(
+        once().kill(&reader, 0),
+        Err(UhfError::AccessDenied { .. })
+    ));
+
+    once().select(&reader, &TagFilter::epc(&OTHER_EPC)).unwrap();
+    assert!(matches!(
+        once().kill(&reader, 0x1234_5678),
+        Err(UhfError::AccessDenied { .. })
+    ));
+    once().kill(&reader, 0xDEAD_BEEF).unwrap();
+    assert_eq!(reader.tag_count(), 1);
+    assert_eq!(once().inventory(&reader).unwrap()[0].epc, EPC);
+}
+
+#[test]
+fn saved_settings_survive_a_restart() {
+    let reader = ReaderEmulator::new();
+    assert_eq!(
+        once().get_config(&reader).unwrap(),
+        ReaderSettings::default()
+    );
+    let mut settings = ReaderSettings::default();
+    settings.rf.tx_power_dbm = 18;
+    settings.gen2.session = Session::S2;
+
+    assert!(!once().set_config(&reader, settings, false).unwrap());
+    assert_eq!(once().get_config(&reader).unwrap(), settings);
+    reader.restart();
+    assert_eq!(reader.settings(), ReaderSettings::default());
+
+    assert!(once().set_config(&reader, settings, true).unwrap());
+    reader.restart();
+    assert_eq!(once().get_config(&reader).unwrap(), settings);
+
+    // A reader without settings storage applies them until the next restart only
+    reader.set_volatile_settings(true);
+    settings.gen2.q = 7;
+    assert!(!once().set_config(&reader, settings, true).unwrap());
+    reader.restart();
+    assert_eq!(reader.settings().gen2.q, 4);
+}
+
+#[test]
+fn identity_limits_the_settings_a_reader_accepts() {
+    let reader = ReaderEmulator::new();
+    let default = reader.identity().unwrap();
+    assert_eq!(default.regions, Region::ALL);
+    assert_eq!(once().identify(&reader).unwrap(), default);
+    reader.set_volatile_settings(true);
+    let identity = once().identify(&reader).unwrap();
+    assert!(!identity.supports(ReaderFeature::PersistSettings));
+
+    reader.set_identity(Some(ReaderIdentity {
+        regions: vec![Region::Etsi],
+        max_tx_power_dbm: 27,
+        ..default
+    }));
+    let mut settings = ReaderSettings::default();
+    settings.rf.region = Region::Etsi;
+    settings.rf.tx_power_dbm = 28;
+    assert!(once().set_config(&reader, settings, false).is_err());
+    settings.rf.tx_power_dbm = 27;
+    assert!(once().set_config(&reader, settings, false).is_ok());
+    settings.rf.region = Region::Fcc;
+    assert!(once().set_config(&reader, settings, false).is_err());
+
+    reader.set_identity(None);
+    assert!(matches!(
+        once().identify(&reader),
+        Err(UhfError::CommandRejected { .. })
+    ));
+}
@@ -0,0 +1,236 @@
+//! Property tests feeding arbitrary reader output through the decoders and every
+//! `Interface` method: malformed input must come back as a `UhfError`, never a panic.
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use protocl::codec::{
+    ACCESS_PREFIX, ACTION_OPCODE, CONFIG_OPCODE, Command, IDENTIFY_OPCODE, INVENTORY_OPCODE,
+    PROTOCOL_MARKER, Response,
+};
+use protocl::dissect::dissect;
+use protocl::framing::{Deframer, Direction, Framer, REPORT_SIZE};
+use protocl::interface::{Interface, RawOptions, Result, RetryPolicy};
+use protocl::types::{
+    FirmwareVersion, LockPayload, MemoryBank, ReaderFeature, ReaderIdentity, ReaderSettings,
+    Region, TagFilter, TagReport, UhfError, UsbIo,
+};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Reader that answers each report written to it with the next batch of scripted reports
+///
+/// A scripted report longer than the read buffer is cut short, but its full length is
+/// still returned, like a misbehaving transport.
+struct ScriptedReader {
+    batches: RefCell<VecDeque<Vec<Vec<u8>>>>,
+    pending: RefCell<VecDeque<Vec<u8>>>,
+}
+
+impl ScriptedReader {
+    fn new(stale: Vec<Vec<u8>>, batches: Vec<Vec<Vec<u8>>>) -> Self {
+        Self {
+            batches: RefCell::new(batches.into()),
+            pending: RefCell::new(stale.into()),
+        }
+    }
+}
+
+impl UsbIo for ScriptedReader {
+    fn read_bulk(&self, _endpoint: u8, buf: &mut [u8], _timeout: Duration) -> Result<usize> {
+        let report = self
+            .pending
+            .borrow_mut()
+            .pop_front()
+            .ok_or(UhfError::Timeout)?;
+        let len = report.len().min(buf.len());
+        buf[..len].copy_from_slice(&report[..len]);
+        Ok(report.len())
+    }
+
+    fn write_bulk(&self, _endpoint: u8, data: &[u8], _timeout: Duration) -> Result<usize> {
+        if let Some(batch) = self.batches.borrow_mut().pop_front() {
+            self.pending.borrow_mut().extend(batch);
+        }
+        Ok(data.len())
+    }
+}
+
+/// Call every `Interface` method once against the scripted reader
+fn exercise_interface(reader: &ScriptedReader) {
+    let interface = Interface {
+        policy: RetryPolicy::default().with_backoff(Duration::ZERO),
+    };
+    let _ = interface.inventory(reader);
+    let _ = interface.read(reader, MemoryBank::Epc, 2, 6);
+    let _ = interface.read(reader, MemoryBank::User, 0, 0);
+    let _ = interface.write(reader, MemoryBank::User, 0, &[0x12, 0x34]);
+    let _ = interface.action(reader, 0x05, 20);
+    let _ = interface.set_access_password(reader, 0x1234_5678);
+    let _ = interface.lock_memory(reader, LockPayload::new());
+    let _ = interface.authenticate(reader, 0x1234_5678);
+    let _ = interface.kill(reader, 0x0BAD_F00D);
+    let _ = interface.select(reader, &TagFilter::epc(&[0x30, 0x08]));
+    let _ = interface.clear_select(reader);
+    let _ = interface.get_config(reader);
+    let _ = interface.set_config(reader, ReaderSettings::default(), false);
+    let _ = interface.identify(reader);
+    let _ = interface.raw_transaction(
+        reader,
+        &[PROTOCOL_MARKER, IDENTIFY_OPCODE],
+        &RawOptions::default(),
+    );
+}
+
+/// Reply payloads that get past the opcode checks into the parsers
+fn reply_payload() -> impl Strategy<Value = Vec<u8>> {
+    let prefix = prop_oneof![
+        Just(vec![PROTOCOL_MARKER, ACCESS_PREFIX]),
+        Just(vec![PROTOCOL_MARKER, INVENTORY_OPCODE]),
+        Just(vec![PROTOCOL_MARKER, CONFIG_OPCODE]),
+        Just(vec![PROTOCOL_MARKER, IDENTIFY_OPCODE]),
+        Just(vec![PROTOCOL_MARKER, ACTION_OPCODE]),
+        Just(Vec::new()),
+    ];
+    (prefix, vec(any::<u8>(), 0..80)).prop_map(|(mut payload, rest)| {
+        payload.extend(rest);
+        payload.truncate(usize::from(u8::MAX));
+        payload
+    })
+}
+
+/// Well-formed replies of every kind
+fn valid_replies() -> Vec<Response> {
+    vec![
+        Response::Read {
+            data: (0..30).collect(),
+        },
+        Response::Written,
+        Response::Locked,
+        Response::AccessFailed {
+            command: b'W',
+            status: 0x04,
+        },
+        Response::InventoryStarted { tag_count: 2 },
+        Response::InventoryTag(TagReport {
+            pc: 0x3000,
+            epc: vec![0xE2; 12],
+            crc: 0x1234,
+            rssi: Some(-61),
+            antenna: Some(1),
+        }),
+        Response::InventoryEmpty,
+        Response::Config(ReaderSettings::default()),
+        Response::ConfigSet { persisted: true },
+        Response::Identity(ReaderIdentity {
+            firmware: FirmwareVersion {
+                major: 2,
+                minor: 1,
+                patch: 0,
+            },
+            hardware_revision: 1,
+            regions: Region::ALL.to_vec(),
+            max_tx_power_dbm: 30,
+            features: ReaderFeature::ALL.to_vec(),
+        }),
+        Response::Action { status: 0 },
+    ]
+}
+
+/// Payload of a valid reply, cut short and with a few bytes overwritten
+fn mutated_payload() -> impl Strategy<Value = Vec<u8>> {
+    let replies = valid_replies();
+    (
+        0..replies.len(),
+        any::<prop::sample::Index>(),
+        vec((any::<prop::sample::Index>(), any::<u8>()), 0..4),
+    )
+        .prop_map(move |(reply, cut, flips)| {
+            let mut payload = replies[reply].encode().unwrap().split_off(1);
+            payload.truncate(cut.index(payload.len() + 1));
+            for (at, byte) in flips {
+                if !payload.is_empty() {
+                    let at = at.index(payload.len());
+                    payload[at] = byte;
+                }
+            }
+            payload
+        })
+}
+
+/// Well-framed replies with arbitrary or mutated payloads, or arbitrary reports
+fn reports() -> impl Strategy<Value = Vec<Vec<u8>>> {
+    prop_oneof![
+        prop_oneof![reply_payload(), mutated_payload()].prop_map(|payload| {
+            let frame = [&[u8::try_from(payload.len()).unwrap()], payload.as_slice()].concat();
+            Framer::new(Direction::ReaderToHost, &frame)
+                .unwrap()
+                .map(|report| report.to_vec())
+                .collect()
+        }),
+        vec(vec(any::<u8>(), 0..=REPORT_SIZE + 8), 1..3),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn decoders_reject_arbitrary_frames(frame in vec(any::<u8>(), 0..300)) {
+        let _ = Response::decode(&frame);
+        let _ = Command::decode(&frame);
+        let dissection = dissect(&frame);
+        // Every byte is labelled exactly once
+        let bytes: Vec<u8> = dissection.fields.into_iter().flat_map(|field| field.bytes).collect();
+        prop_assert_eq!(bytes, frame);
+    }
+
+    #[test]
+    fn decoders_reject_arbitrary_payloads(payload in reply_payload()) {
+        let frame = [&[u8::try_from(payload.len()).unwrap()], payload.as_slice()].concat();
+        if let Ok(response) = Response::decode(&frame) {
+            // Whatever decodes must encode again
+            prop_assert!(response.encode().is_ok());
+        }
+        let _ = Command::decode(&frame);
+    }
+
+    #[test]
+    fn decoders_reject_mutated_replies(payload in mutated_payload()) {
+        let frame = [&[u8::try_from(payload.len()).unwrap()], payload.as_slice()].concat();
+        if let Ok(response) = Response::decode(&frame) {
+            prop_assert!(response.encode().is_ok());
+        }
+    }
+
+    #[test]
+    fn deframers_reject_arbitrary_reports(reports in vec(vec(any::<u8>(), 0..=REPORT_SIZE), 0..8)) {
+        for direction in [Direction::HostToReader, Direction::ReaderToHost] {
+            let mut deframer = Deframer::new(direction);
+            for report in &reports {
+                if let Ok(Some(frame)) = deframer.push(report) {
+                    prop_assert_eq!(usize::from(frame[0]) + 1, frame.len());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn frames_survive_the_framer_and_deframer(body in vec(any::<u8>(), 0..=255)) {
+        let frame = [&[u8::try_from(body.len()).unwrap()], body.as_slice()].concat();
+        for direction in [Direction::HostToReader, Direction::ReaderToHost] {
+            let mut deframer = Deframer::new(direction);
+            let mut frames = Vec::new();
+            for report in Framer::new(direction, &frame).unwrap() {
+                frames.extend(deframer.push(&report).unwrap());
+            }
+            prop_assert_eq!(frames, vec![frame.clone()]);
+        }
+    }
+
+    #[test]
+    fn interface_methods_survive_arbitrary_replies(
+        stale in vec(vec(any::<u8>(), 0..=REPORT_SIZE + 8), 0..3),
+        batches in vec(reports(), 0..24),
+    ) {
+        exercise_interface(&ScriptedReader::new(stale, batches));
+    }
+}